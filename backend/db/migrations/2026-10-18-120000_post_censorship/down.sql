@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE post_censor_actions;
+ALTER TABLE posts
+    DROP COLUMN censor_changed_date,
+    DROP COLUMN censor_changed_by,
+    DROP COLUMN censor_reason;
@@ -0,0 +1,18 @@
+-- Record who last changed the censor state of a post, when, and why.
+ALTER TABLE posts
+    ADD COLUMN censor_reason VARCHAR,
+    ADD COLUMN censor_changed_by UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    ADD COLUMN censor_changed_date TIMESTAMP;
+
+-- Every change to the censor state of a post, so moderators are accountable for what they censor.
+-- The content is kept as it was when the change was made.
+CREATE TABLE post_censor_actions (
+    uuid UUID PRIMARY KEY NOT NULL Default gen_random_uuid(),
+    post_uuid UUID NOT NULL REFERENCES posts(uuid) ON DELETE CASCADE,
+    moderator_uuid UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    censored BOOLEAN NOT NULL,
+    reason VARCHAR NOT NULL,
+    content VARCHAR NOT NULL,
+    created_date TIMESTAMP NOT NULL
+);
+CREATE INDEX post_censor_actions_post_idx ON post_censor_actions (post_uuid, created_date);
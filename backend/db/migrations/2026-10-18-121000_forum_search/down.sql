@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX posts_search_vector_idx;
+DROP INDEX threads_search_vector_idx;
+
+ALTER TABLE posts DROP COLUMN search_vector;
+ALTER TABLE threads DROP COLUMN search_vector;
@@ -0,0 +1,9 @@
+-- Full-text search over thread titles and post content.
+-- The generated columns are only read by raw search queries, so they are left out of the diesel schema.
+ALTER TABLE threads
+    ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (to_tsvector('english', title)) STORED;
+ALTER TABLE posts
+    ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (to_tsvector('english', content)) STORED;
+
+CREATE INDEX threads_search_vector_idx ON threads USING GIN (search_vector);
+CREATE INDEX posts_search_vector_idx ON posts USING GIN (search_vector);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE forums DROP COLUMN position;
+ALTER TABLE threads DROP COLUMN pinned_position;
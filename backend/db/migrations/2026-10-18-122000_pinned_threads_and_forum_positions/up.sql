@@ -0,0 +1,8 @@
+-- Pinned threads are listed before all others, in ascending order of their pinned position.
+-- Threads that are not pinned have a NULL position.
+ALTER TABLE threads
+    ADD COLUMN pinned_position INTEGER;
+
+-- Forums are listed in ascending order of their position.
+ALTER TABLE forums
+    ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE threads
+    DROP COLUMN reply_count,
+    DROP COLUMN last_post_author_uuid,
+    DROP COLUMN last_post_date;
@@ -0,0 +1,30 @@
+-- Denormalized activity information for threads, maintained as posts are created.
+ALTER TABLE threads
+    ADD COLUMN last_post_date TIMESTAMP,
+    ADD COLUMN last_post_author_uuid UUID REFERENCES users(uuid) ON DELETE SET NULL,
+    ADD COLUMN reply_count INTEGER NOT NULL DEFAULT 0;
+
+UPDATE threads t
+SET last_post_date = latest.created_date,
+    last_post_author_uuid = latest.author_uuid
+FROM (
+    SELECT DISTINCT ON (thread_uuid) thread_uuid, created_date, author_uuid
+    FROM posts
+    ORDER BY thread_uuid, created_date DESC
+) latest
+WHERE latest.thread_uuid = t.uuid;
+
+UPDATE threads t
+SET reply_count = replies.count
+FROM (
+    SELECT thread_uuid, COUNT(*) AS count
+    FROM posts
+    WHERE parent_uuid IS NOT NULL
+    GROUP BY thread_uuid
+) replies
+WHERE replies.thread_uuid = t.uuid;
+
+-- Threads without any posts count their creation as their latest activity.
+UPDATE threads SET last_post_date = created_date WHERE last_post_date IS NULL;
+
+ALTER TABLE threads ALTER COLUMN last_post_date SET NOT NULL;
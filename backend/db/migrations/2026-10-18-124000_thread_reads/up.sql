@@ -0,0 +1,8 @@
+-- Records, for each user, the date of the most recent post they have seen in each thread they have read.
+CREATE TABLE thread_reads (
+    uuid UUID PRIMARY KEY NOT NULL Default gen_random_uuid(),
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    thread_uuid UUID NOT NULL REFERENCES threads(uuid) ON DELETE CASCADE,
+    last_read_date TIMESTAMP NOT NULL,
+    UNIQUE (user_uuid, thread_uuid)
+);
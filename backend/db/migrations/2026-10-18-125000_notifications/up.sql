@@ -0,0 +1,18 @@
+-- Notifications tell users about posts that reply to or mention them.
+CREATE TABLE notifications (
+    uuid UUID PRIMARY KEY NOT NULL Default gen_random_uuid(),
+    -- The user being notified.
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    -- The user who wrote the post that caused the notification.
+    actor_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    -- 1 for replies, 2 for mentions.
+    kind INTEGER NOT NULL,
+    post_uuid UUID NOT NULL REFERENCES posts(uuid) ON DELETE CASCADE,
+    thread_uuid UUID NOT NULL REFERENCES threads(uuid) ON DELETE CASCADE,
+    created_date TIMESTAMP NOT NULL,
+    read BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Editing a post shouldn't notify the same user about it again.
+    UNIQUE (user_uuid, post_uuid, kind)
+);
+
+CREATE INDEX notifications_user_uuid_idx ON notifications (user_uuid);
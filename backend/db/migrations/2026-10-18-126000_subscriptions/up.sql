@@ -0,0 +1,15 @@
+-- Subscriptions let users follow every new post in a thread, or in any thread of a forum.
+CREATE TABLE subscriptions (
+    uuid UUID PRIMARY KEY NOT NULL Default gen_random_uuid(),
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    thread_uuid UUID REFERENCES threads(uuid) ON DELETE CASCADE,
+    forum_uuid UUID REFERENCES forums(uuid) ON DELETE CASCADE,
+    created_date TIMESTAMP NOT NULL,
+    -- A subscription is to either a thread or a forum.
+    CHECK ((thread_uuid IS NULL) <> (forum_uuid IS NULL)),
+    UNIQUE (user_uuid, thread_uuid),
+    UNIQUE (user_uuid, forum_uuid)
+);
+
+-- When the notification was included in a digest, so it is only ever sent once.
+ALTER TABLE notifications ADD COLUMN digested_date TIMESTAMP;
@@ -0,0 +1,31 @@
+-- A thread can have a single poll attached to it.
+CREATE TABLE polls (
+    uuid UUID PRIMARY KEY NOT NULL Default gen_random_uuid(),
+    thread_uuid UUID NOT NULL UNIQUE REFERENCES threads(uuid) ON DELETE CASCADE,
+    question VARCHAR NOT NULL,
+    -- Whether users can vote for more than one option.
+    multiple_choice BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Votes are no longer accepted after this date. Polls without one stay open.
+    close_date TIMESTAMP,
+    -- If set, nobody can see the results until the poll closes.
+    -- Otherwise, users can see the results once they have voted.
+    hide_results_until_closed BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE TABLE poll_options (
+    uuid UUID PRIMARY KEY NOT NULL Default gen_random_uuid(),
+    poll_uuid UUID NOT NULL REFERENCES polls(uuid) ON DELETE CASCADE,
+    -- Options are listed in ascending order of their position.
+    position INTEGER NOT NULL,
+    content VARCHAR NOT NULL
+);
+
+CREATE TABLE poll_votes (
+    uuid UUID PRIMARY KEY NOT NULL Default gen_random_uuid(),
+    poll_uuid UUID NOT NULL REFERENCES polls(uuid) ON DELETE CASCADE,
+    option_uuid UUID NOT NULL REFERENCES poll_options(uuid) ON DELETE CASCADE,
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    UNIQUE (user_uuid, option_uuid)
+);
+
+CREATE INDEX poll_votes_poll_uuid_idx ON poll_votes (poll_uuid);
@@ -0,0 +1,21 @@
+-- This file should undo anything in `up.sql`
+CREATE TABLE post_upvotes (
+    uuid Uuid PRIMARY KEY NOT NULL Default gen_random_uuid(),
+    post_uuid UUID NOT NULL REFERENCES posts(uuid) ON DELETE CASCADE,
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE
+);
+
+CREATE TABLE post_downvotes (
+    uuid Uuid PRIMARY KEY NOT NULL Default gen_random_uuid(),
+    post_uuid UUID NOT NULL REFERENCES posts(uuid) ON DELETE CASCADE,
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE
+);
+
+-- Reactions other than up and down votes are lost.
+INSERT INTO post_upvotes (post_uuid, user_uuid)
+SELECT post_uuid, user_uuid FROM post_reactions WHERE reaction = 'up';
+
+INSERT INTO post_downvotes (post_uuid, user_uuid)
+SELECT post_uuid, user_uuid FROM post_reactions WHERE reaction = 'down';
+
+DROP TABLE post_reactions;
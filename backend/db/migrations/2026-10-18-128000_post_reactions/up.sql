@@ -0,0 +1,21 @@
+-- Up and down votes become two reactions among many.
+CREATE TABLE post_reactions (
+    uuid UUID PRIMARY KEY NOT NULL Default gen_random_uuid(),
+    post_uuid UUID NOT NULL REFERENCES posts(uuid) ON DELETE CASCADE,
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    -- The name of the reaction, such as 'up', 'down', or 'heart'.
+    reaction VARCHAR NOT NULL,
+    created_date TIMESTAMP NOT NULL DEFAULT now(),
+    UNIQUE (post_uuid, user_uuid, reaction)
+);
+
+CREATE INDEX post_reactions_user_uuid_idx ON post_reactions (user_uuid);
+
+INSERT INTO post_reactions (post_uuid, user_uuid, reaction)
+SELECT DISTINCT post_uuid, user_uuid, 'up' FROM post_upvotes;
+
+INSERT INTO post_reactions (post_uuid, user_uuid, reaction)
+SELECT DISTINCT post_uuid, user_uuid, 'down' FROM post_downvotes;
+
+DROP TABLE post_upvotes;
+DROP TABLE post_downvotes;
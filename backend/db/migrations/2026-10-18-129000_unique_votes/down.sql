@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX post_reactions_one_reaction_idx;
+ALTER TABLE post_reactions ADD CONSTRAINT post_reactions_post_uuid_user_uuid_reaction_key UNIQUE (post_uuid, user_uuid, reaction);
+DROP INDEX post_reactions_one_vote_idx;
@@ -0,0 +1,21 @@
+-- A user gets a single vote on each post, either up or down.
+-- Where both were somehow cast, keep the up vote.
+DELETE FROM post_reactions d
+USING post_reactions u
+WHERE d.reaction = 'down'
+    AND u.reaction = 'up'
+    AND d.post_uuid = u.post_uuid
+    AND d.user_uuid = u.user_uuid;
+
+CREATE UNIQUE INDEX post_reactions_one_vote_idx
+    ON post_reactions (post_uuid, user_uuid)
+    WHERE reaction IN ('up', 'down');
+
+-- Votes are kept unique by post_reactions_one_vote_idx alone.
+-- If the general constraint also covered votes, upserting a vote could still violate it
+-- when racing another request, as only one index can arbitrate the conflict.
+ALTER TABLE post_reactions DROP CONSTRAINT post_reactions_post_uuid_user_uuid_reaction_key;
+
+CREATE UNIQUE INDEX post_reactions_one_reaction_idx
+    ON post_reactions (post_uuid, user_uuid, reaction)
+    WHERE reaction NOT IN ('up', 'down');
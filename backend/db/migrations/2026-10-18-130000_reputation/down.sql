@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX users_reputation_idx;
+ALTER TABLE users DROP COLUMN reputation;
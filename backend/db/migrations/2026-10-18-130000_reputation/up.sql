@@ -0,0 +1,14 @@
+-- A user's reputation is the net score of the votes left on their posts.
+ALTER TABLE users ADD COLUMN reputation INTEGER NOT NULL DEFAULT 0;
+
+UPDATE users SET reputation = totals.reputation
+FROM (
+    SELECT p.author_uuid, SUM(CASE WHEN r.reaction = 'up' THEN 1 ELSE -1 END)::INTEGER AS reputation
+    FROM post_reactions r
+    JOIN posts p ON p.uuid = r.post_uuid
+    WHERE r.reaction IN ('up', 'down')
+    GROUP BY p.author_uuid
+) totals
+WHERE users.uuid = totals.author_uuid;
+
+CREATE INDEX users_reputation_idx ON users (reputation DESC);
@@ -0,0 +1,9 @@
+-- Slugs that articles used to have, so that old links keep resolving after a title changes.
+CREATE TABLE article_slugs (
+    slug VARCHAR PRIMARY KEY NOT NULL,
+    article_uuid UUID NOT NULL REFERENCES articles(uuid) ON DELETE CASCADE,
+    -- When the article stopped using the slug.
+    replaced_date TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX article_slugs_article_uuid_idx ON article_slugs (article_uuid);
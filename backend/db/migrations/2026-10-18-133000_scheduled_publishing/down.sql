@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX articles_publish_date_idx;
+ALTER TABLE articles DROP COLUMN announced_date;
@@ -0,0 +1,6 @@
+-- When the article was announced as published.
+-- Articles can be scheduled to publish in the future, so this is set once their publish date has passed.
+ALTER TABLE articles ADD COLUMN announced_date TIMESTAMP;
+UPDATE articles SET announced_date = publish_date WHERE publish_date <= now();
+
+CREATE INDEX articles_publish_date_idx ON articles (publish_date);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE articles DROP COLUMN published_revision_uuid;
+DROP TABLE article_revisions;
@@ -0,0 +1,23 @@
+-- Every save of an article, including autosaves from the editor.
+CREATE TABLE article_revisions (
+    uuid UUID PRIMARY KEY NOT NULL DEFAULT gen_random_uuid(),
+    article_uuid UUID NOT NULL REFERENCES articles(uuid) ON DELETE CASCADE,
+    title VARCHAR NOT NULL,
+    body TEXT NOT NULL,
+    -- Whether the revision was saved by the editor on its own, rather than by the author.
+    autosave BOOLEAN NOT NULL DEFAULT FALSE,
+    created_date TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX article_revisions_article_uuid_idx ON article_revisions (article_uuid, created_date);
+
+-- The revision whose title and body readers see.
+ALTER TABLE articles ADD COLUMN published_revision_uuid UUID REFERENCES article_revisions(uuid) ON DELETE SET NULL;
+
+-- Existing articles start their history with their current content.
+INSERT INTO article_revisions (article_uuid, title, body)
+SELECT uuid, title, body FROM articles;
+
+UPDATE articles SET published_revision_uuid = article_revisions.uuid
+FROM article_revisions
+WHERE article_revisions.article_uuid = articles.uuid;
@@ -0,0 +1,15 @@
+-- Tags are shared between articles, and are identified by their normalized name.
+CREATE TABLE tags (
+    uuid UUID PRIMARY KEY NOT NULL DEFAULT gen_random_uuid(),
+    name VARCHAR NOT NULL UNIQUE
+);
+
+CREATE TABLE article_tags (
+    uuid UUID PRIMARY KEY NOT NULL DEFAULT gen_random_uuid(),
+    article_uuid UUID NOT NULL REFERENCES articles(uuid) ON DELETE CASCADE,
+    tag_uuid UUID NOT NULL REFERENCES tags(uuid) ON DELETE CASCADE,
+    UNIQUE (article_uuid, tag_uuid)
+);
+
+-- Articles are looked up by their tags when filtering and finding related articles.
+CREATE INDEX article_tags_tag_uuid_idx ON article_tags (tag_uuid);
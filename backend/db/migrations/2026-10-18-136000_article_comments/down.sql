@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE articles DROP COLUMN comment_thread_uuid;
+ALTER TABLE articles DROP COLUMN comments_enabled;
+DROP INDEX forums_article_comments_idx;
+ALTER TABLE forums DROP COLUMN article_comments;
@@ -0,0 +1,9 @@
+-- The forum that holds the comment threads of articles.
+-- It is created when the first article is published, and there can only be one.
+ALTER TABLE forums ADD COLUMN article_comments BOOLEAN NOT NULL DEFAULT FALSE;
+CREATE UNIQUE INDEX forums_article_comments_idx ON forums (article_comments) WHERE article_comments;
+
+-- Whether readers can comment on the article.
+ALTER TABLE articles ADD COLUMN comments_enabled BOOLEAN NOT NULL DEFAULT TRUE;
+-- The thread that the article's comments are posted in, which is created once the article is published.
+ALTER TABLE articles ADD COLUMN comment_thread_uuid UUID REFERENCES threads(uuid) ON DELETE SET NULL;
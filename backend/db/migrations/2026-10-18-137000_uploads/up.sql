@@ -0,0 +1,15 @@
+-- Files that users have uploaded to use in articles and posts.
+-- The files are stored by the hash of their content, so identical uploads share the same file.
+CREATE TABLE uploads (
+    uuid UUID PRIMARY KEY NOT NULL DEFAULT gen_random_uuid(),
+    owner_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    content_hash VARCHAR NOT NULL,
+    file_name VARCHAR NOT NULL,
+    mime_type VARCHAR NOT NULL,
+    size BIGINT NOT NULL,
+    has_thumbnail BOOLEAN NOT NULL DEFAULT FALSE,
+    created_date TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX uploads_owner_uuid_idx ON uploads (owner_uuid);
+CREATE INDEX uploads_content_hash_idx ON uploads (content_hash);
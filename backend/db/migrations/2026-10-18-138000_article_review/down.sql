@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE article_reviews;
+ALTER TABLE articles DROP COLUMN review_requested_date;
+ALTER TABLE articles DROP COLUMN review_state;
+DROP TABLE article_authors;
@@ -0,0 +1,31 @@
+-- Users credited on an article alongside its author, who can edit and publish it too.
+CREATE TABLE article_authors (
+    uuid UUID PRIMARY KEY NOT NULL DEFAULT gen_random_uuid(),
+    article_uuid UUID NOT NULL REFERENCES articles(uuid) ON DELETE CASCADE,
+    user_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    UNIQUE (article_uuid, user_uuid)
+);
+
+CREATE INDEX article_authors_user_uuid_idx ON article_authors (user_uuid);
+
+-- 1 is a draft, 2 is in review, 3 is approved, and 4 is published.
+ALTER TABLE articles ADD COLUMN review_state INTEGER NOT NULL DEFAULT 1;
+-- When the article was last submitted for review, which orders the editorial queue.
+ALTER TABLE articles ADD COLUMN review_requested_date TIMESTAMP;
+-- Articles published before reviews existed don't need to be reviewed.
+UPDATE articles SET review_state = 4 WHERE publish_date IS NOT NULL;
+
+-- Comments left on an article by its reviewers and authors.
+CREATE TABLE article_reviews (
+    uuid UUID PRIMARY KEY NOT NULL DEFAULT gen_random_uuid(),
+    article_uuid UUID NOT NULL REFERENCES articles(uuid) ON DELETE CASCADE,
+    reviewer_uuid UUID NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    -- 1 is a comment, 2 is an approval, and 3 is a request for changes.
+    decision INTEGER NOT NULL,
+    comment VARCHAR NOT NULL,
+    -- The latest revision when the review was left, which is the one an approval approves.
+    revision_uuid UUID REFERENCES article_revisions(uuid) ON DELETE SET NULL,
+    created_date TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX article_reviews_article_uuid_idx ON article_reviews (article_uuid);
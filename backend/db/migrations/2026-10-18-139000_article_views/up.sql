@@ -0,0 +1,21 @@
+-- The viewers that have been counted for each article today, so each viewer is only counted once a day.
+-- Viewers are only kept as a hash salted with a key that changes every day,
+-- and the rows from previous days are removed by a job that runs through the day.
+CREATE TABLE article_viewers (
+    article_uuid UUID NOT NULL REFERENCES articles(uuid) ON DELETE CASCADE,
+    view_date DATE NOT NULL,
+    viewer_hash VARCHAR NOT NULL,
+    PRIMARY KEY (article_uuid, view_date, viewer_hash)
+);
+
+CREATE INDEX article_viewers_view_date_idx ON article_viewers (view_date);
+
+-- The number of viewers each article had each day.
+CREATE TABLE article_daily_views (
+    article_uuid UUID NOT NULL REFERENCES articles(uuid) ON DELETE CASCADE,
+    view_date DATE NOT NULL,
+    views INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (article_uuid, view_date)
+);
+
+CREATE INDEX article_daily_views_view_date_idx ON article_daily_views (view_date);
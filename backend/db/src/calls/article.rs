@@ -1,18 +1,35 @@
 use crate::schema::articles;
+use crate::schema::article_slugs;
 use diesel;
 use diesel::RunQueryDsl;
 use diesel::QueryDsl;
 use diesel::ExpressionMethods;
+use diesel::BoolExpressionMethods;
 use chrono::{NaiveDateTime, Utc};
 use crate::user::User;
-use diesel::BelongingToDsl;
-use error::BackendResult;
+use error::{BackendResult, Error};
 use diesel::PgConnection;
+use diesel::Connection;
+use diesel::result::Error as DieselError;
 use uuid::Uuid;
 use identifiers::article::ArticleUuid;
 use identifiers::user::UserUuid;
+use identifiers::article_revision::ArticleRevisionUuid;
 use crate::calls::prelude::*;
 use crate::schema;
+use crate::conversions::article::slugify;
+use crate::article_revision::{ArticleRevision, NewArticleRevision};
+use crate::schema::article_revisions;
+use crate::tag::Tag;
+use crate::article_author::ArticleAuthor;
+use crate::article_review::ArticleReview;
+use wire::article::ReviewState;
+use crate::forum::Forum;
+use crate::thread::{Thread, NewThread, ThreadData};
+use identifiers::thread::ThreadUuid;
+use diesel::sql_types::{BigInt, Timestamp};
+use diesel::sql_types::Uuid as SqlUuid;
+use std::collections::HashMap;
 
 
 /// The database's representation of an article
@@ -33,7 +50,22 @@ pub struct Article {
     pub body: String,
     /// The presence of a publish date will idicate the article's published status,
     /// and will be used in ordering sets of the most recent articles.
+    /// Articles with a publish date in the future are embargoed until that date passes.
     pub publish_date: Option<NaiveDateTime>,
+    /// When the article's publication was announced, which happens once its publish date passes.
+    pub announced_date: Option<NaiveDateTime>,
+    /// The revision that the title and body were taken from.
+    /// Revisions saved after this one are drafts, and aren't shown to readers until the article is republished.
+    pub published_revision_uuid: Option<Uuid>,
+    /// Whether readers can comment on the article.
+    pub comments_enabled: bool,
+    /// The forum thread that the article's comments are posted in.
+    /// This is created once the article is published.
+    pub comment_thread_uuid: Option<Uuid>,
+    /// Where the article is in the editorial process, as the number of a `ReviewState`.
+    pub review_state: i32,
+    /// When the article was last submitted for review.
+    pub review_requested_date: Option<NaiveDateTime>,
 }
 
 /// Specifies the attributes that can be changed for an article.
@@ -46,6 +78,25 @@ pub struct ArticleChangeset {
     pub body: Option<String>,
 }
 
+/// A slug that an article used before its title was changed.
+#[derive(Clone, Queryable, Identifiable, Associations, Debug, PartialEq, TypeName)]
+#[primary_key(slug)]
+#[belongs_to(Article, foreign_key = "article_uuid")]
+#[table_name = "article_slugs"]
+pub struct ArticleSlug {
+    pub slug: String,
+    /// The key of the article that used to have the slug.
+    pub article_uuid: Uuid,
+    pub replaced_date: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "article_slugs"]
+pub struct NewArticleSlug {
+    pub slug: String,
+    pub article_uuid: Uuid,
+}
+
 
 
 /// Represents an article that will be inserted into the database.
@@ -58,22 +109,95 @@ pub struct NewArticle {
     pub author_uuid: Uuid,
 }
 
+#[derive(Debug)]
 pub struct ArticleData {
     pub article: Article,
     pub user: User,
+    /// The users credited alongside the author, in order of their display names.
+    pub co_authors: Vec<User>,
+    /// The names of the article's tags, in alphabetical order.
+    pub tags: Vec<String>,
+    /// The number of replies in the article's comment thread.
+    pub comment_count: i64,
 }
 
+impl ArticleData {
+    /// Whether the user wrote the article, or is one of its co-authors.
+    pub fn is_author(&self, user_uuid: UserUuid) -> bool {
+        self.article.author_uuid == user_uuid.0 || self.co_authors.iter().any(|user| user.uuid == user_uuid.0)
+    }
+
+    /// Unpublished and embargoed articles can only be seen by their authors and publishers.
+    pub fn is_visible_to(&self, user_uuid: Option<UserUuid>, is_publisher: bool) -> bool {
+        self.article.is_published() || is_publisher || user_uuid.map(|x| self.is_author(x)).unwrap_or(false)
+    }
+}
+
+/// Narrows down which published articles are listed.
+#[derive(Debug, Clone, Default)]
+pub struct ArticleFilter {
+    /// Only list articles with this tag.
+    pub tag: Option<String>,
+    /// Only list articles written by this user.
+    pub author_uuid: Option<UserUuid>,
+}
+
+/// An article that shares tags with another one.
+#[derive(Debug, Clone, QueryableByName)]
+struct RelatedArticle {
+    #[sql_type = "SqlUuid"]
+    article_uuid: Uuid,
+}
+
+/// Finds the published articles that share the most tags with the provided one,
+/// preferring more recently published articles when they share as many.
+const RELATED_ARTICLES_QUERY: &str = "
+    SELECT theirs.article_uuid AS article_uuid
+    FROM article_tags mine
+    INNER JOIN article_tags theirs ON theirs.tag_uuid = mine.tag_uuid AND theirs.article_uuid <> mine.article_uuid
+    INNER JOIN articles a ON a.uuid = theirs.article_uuid
+    WHERE mine.article_uuid = $1
+        AND a.publish_date <= $2
+    GROUP BY theirs.article_uuid, a.publish_date
+    ORDER BY COUNT(*) DESC, a.publish_date DESC
+    LIMIT $3
+";
+
 
 impl Article {
 
+    /// Whether the article has a publish date, and it has passed.
+    pub fn is_published(&self) -> bool {
+        self.publish_date
+            .map(|date| date <= Utc::now().naive_utc())
+            .unwrap_or(false)
+    }
+
     pub fn get_article(uuid: ArticleUuid,conn: &PgConnection) -> BackendResult<Article> {
         get_row::<Article,_>(schema::articles::table, uuid.0, conn)
     }
     pub fn delete_article(uuid: ArticleUuid, conn: &PgConnection) -> BackendResult<Article> {
         delete_row::<Article,_>(schema::articles::table, uuid.0, conn)
     }
+    /// Creates the article, along with its first revision.
     pub fn create_article(new: NewArticle, conn: &PgConnection) -> BackendResult<Article> {
-        create_row::<Article, NewArticle,_>(schema::articles::table, new, conn)
+        conn.transaction::<_, DieselError, _>(|| {
+            let article: Article = diesel::insert_into(articles::table)
+                .values(&new)
+                .get_result(conn)?;
+            let revision: ArticleRevision = diesel::insert_into(article_revisions::table)
+                .values(&NewArticleRevision {
+                    article_uuid: article.uuid,
+                    title: article.title.clone(),
+                    body: article.body.clone(),
+                    autosave: false,
+                })
+                .get_result(conn)?;
+            diesel::update(articles::table.find(article.uuid))
+                .set(articles::published_revision_uuid.eq(revision.uuid))
+                .get_result(conn)
+        })
+            .map_err(handle_err::<Article>)
     }
     pub fn update_article_2(changeset: ArticleChangeset, conn: &PgConnection) -> BackendResult<Article> {
         update_row::<Article, ArticleChangeset,_>(schema::articles::table, changeset, conn)
@@ -99,53 +223,167 @@ impl Article {
 
         let article = Article::get_article(article_uuid, conn)?;
         let user = User::get_user(UserUuid(article.author_uuid), conn)?;
-        Ok(ArticleData { article, user })
+        Article::attach_details(vec![(article, user)], conn)
+            .map(|mut article_data| article_data.remove(0))
     }
 
-    pub fn get_paginated(page_index: i32, page_size: i32, conn: &PgConnection) -> BackendResult<Vec<ArticleData>> {
+    /// Gets the article by its current slug.
+    pub fn get_article_data_by_slug(article_slug: &str, conn: &PgConnection) -> BackendResult<ArticleData> {
+        use crate::schema::articles::dsl::*;
+        use crate::schema::users;
+
+        let (article, user) = articles
+            .inner_join(users::table)
+            .filter(slug.eq(article_slug))
+            .first::<(Article, User)>(conn)
+            .map_err(handle_err::<Article>)?;
+        let mut article_data: Vec<ArticleData> = Article::attach_details(vec![(article, user)], conn)?;
+        Ok(article_data.remove(0))
+    }
+
+    /// Gets the current slug of the article that used to have the provided slug.
+    pub fn get_current_slug(old_slug: &str, conn: &PgConnection) -> BackendResult<String> {
+        article_slugs::table
+            .inner_join(articles::table)
+            .filter(article_slugs::slug.eq(old_slug))
+            .select(articles::slug)
+            .first::<String>(conn)
+            .map_err(handle_err::<ArticleSlug>)
+    }
+
+    /// Gets a page of published articles that match the filter.
+    /// Articles whose publish date is still in the future are left out.
+    pub fn get_paginated(filter: &ArticleFilter, page_index: i32, page_size: i32, conn: &PgConnection) -> BackendResult<Vec<ArticleData>> {
         use crate::schema::articles::dsl::*;
         use crate::diesel_extensions::pagination::*;
         use crate::schema::users;
+        use crate::schema::tags;
+        use crate::schema::article_tags;
 
-        let (articles_and_users, _count) = articles
+        let now: NaiveDateTime = Utc::now().naive_utc();
+        let mut query = articles
             .inner_join(users::table)
-            .filter(publish_date.is_not_null())
+            .filter(publish_date.le(now))
+            .into_boxed();
+
+        if let Some(author) = filter.author_uuid {
+            query = query.filter(author_uuid.eq(author.0));
+        }
+        if let Some(ref tag) = filter.tag {
+            // A tag that can't be normalized doesn't exist, so nothing will match it.
+            let tag_name: String = Tag::normalize(tag).unwrap_or_else(|| tag.clone());
+            let tagged = article_tags::table
+                .inner_join(tags::table)
+                .filter(tags::name.eq(tag_name))
+                .select(article_tags::article_uuid);
+            query = query.filter(uuid.eq_any(tagged));
+        }
+
+        let (articles_and_users, _count) = query
             .order(publish_date)
             .paginate(page_index.into())
             .per_page(page_size.into())
             .load_and_count_pages::<(Article, User)>(conn)
             .map_err(handle_err::<Article>)?;
 
+        Article::attach_details(articles_and_users, conn)
+    }
+
+    /// Gets the most recently published articles, newest first.
+    /// Articles whose publish date is still in the future are left out.
+    pub fn get_recently_published(limit: i64, conn: &PgConnection) -> BackendResult<Vec<ArticleData>> {
+        use crate::schema::articles::dsl::*;
+        use crate::schema::users;
+
+        let now: NaiveDateTime = Utc::now().naive_utc();
+        articles
+            .inner_join(users::table)
+            .filter(publish_date.le(now))
+            .order(publish_date.desc())
+            .limit(limit)
+            .load::<(Article, User)>(conn)
+            .map_err(handle_err::<Article>)
+            .and_then(|articles_and_users| Article::attach_details(articles_and_users, conn))
+    }
+
+    /// Gets published articles that share tags with the provided one, those sharing the most tags first.
+    pub fn get_related(article_uuid: ArticleUuid, limit: i64, conn: &PgConnection) -> BackendResult<Vec<ArticleData>> {
+        use crate::schema::articles::dsl::*;
+        use crate::schema::users;
+
+        let now: NaiveDateTime = Utc::now().naive_utc();
+        let related: Vec<RelatedArticle> = diesel::sql_query(RELATED_ARTICLES_QUERY)
+            .bind::<SqlUuid, _>(article_uuid.0)
+            .bind::<Timestamp, _>(now)
+            .bind::<BigInt, _>(limit)
+            .load(conn)
+            .map_err(handle_err::<Article>)?;
+
+        let related_uuids: Vec<Uuid> = related.iter().map(|x| x.article_uuid).collect();
+        let mut articles_and_users: Vec<(Article, User)> = articles
+            .inner_join(users::table)
+            .filter(uuid.eq_any(&related_uuids))
+            .load::<(Article, User)>(conn)
+            .map_err(handle_err::<Article>)?;
+        // Keep the order the articles were ranked in.
+        articles_and_users.sort_by_key(|(article, _)| related_uuids.iter().position(|x| *x == article.uuid));
+
+        Article::attach_details(articles_and_users, conn)
+    }
+
+    /// Gets the co-authors, the tags, and the number of comments of each of the articles.
+    pub(crate) fn attach_details(articles_and_users: Vec<(Article, User)>, conn: &PgConnection) -> BackendResult<Vec<ArticleData>> {
+        let article_uuids: Vec<Uuid> = articles_and_users
+            .iter()
+            .map(|(article, _)| article.uuid)
+            .collect();
+        let mut co_authors_by_article = ArticleAuthor::get_co_authors(article_uuids.clone(), conn)?;
+        let mut tags_by_article = Tag::get_tags_for_articles(article_uuids, conn)?;
+        let thread_uuids: Vec<Uuid> = articles_and_users
+            .iter()
+            .filter_map(|(article, _)| article.comment_thread_uuid)
+            .collect();
+        let reply_counts: HashMap<Uuid, i32> = Thread::get_reply_counts(thread_uuids, conn)?;
+
         let article_data = articles_and_users
             .into_iter()
-            .map(|x| {
-                ArticleData {
-                    article: x.0,
-                    user: x.1,
-                }
+            .map(|(article, user)| {
+                let co_authors: Vec<User> = co_authors_by_article
+                    .remove(&article.uuid)
+                    .unwrap_or_default();
+                let tags: Vec<String> = tags_by_article
+                    .remove(&article.uuid)
+                    .unwrap_or_default();
+                let comment_count: i64 = article.comment_thread_uuid
+                    .and_then(|thread_uuid| reply_counts.get(&thread_uuid).cloned())
+                    .map(i64::from)
+                    .unwrap_or(0);
+                ArticleData { article, user, co_authors, tags, comment_count }
             })
             .collect();
-
         Ok(article_data)
     }
 
 
 
 
-    /// Gets the unpublished articles for a given user
+    /// Gets the unpublished articles for a given user, including those they are a co-author of.
+    /// This includes articles that are scheduled to be published in the future.
     pub fn get_unpublished_articles_for_user(user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<Vec<Article>> {
         use crate::schema::articles::dsl::*;
-        use crate::schema::users::dsl::*;
-        //        use schema::users;
+        use crate::schema::users;
 
-        let user: User = users
+        let user: User = users::table
             .find(user_uuid.0)
             .get_result::<User>(conn)
             .map_err(handle_err::<User>)?;
+        let co_authored: Vec<Uuid> = ArticleAuthor::get_co_authored_article_uuids(user_uuid, conn)?;
 
 
-        Article::belonging_to(&user)
-            .filter(publish_date.is_null())
+        let now: NaiveDateTime = Utc::now().naive_utc();
+        articles
+            .filter(author_uuid.eq(user.uuid).or(uuid.eq_any(co_authored)))
+            .filter(publish_date.is_null().or(publish_date.gt(now)))
             .order(publish_date)
             .load::<Article>(conn)
             .map_err(handle_err::<Article>)
@@ -156,29 +394,324 @@ impl Article {
     /// If true, it will set the publish datetime to the current time, indicating it is published.
     /// If false, it will set the publish column to Null, indicating that it has not been published.
     pub fn set_publish_status(article_uuid: ArticleUuid, publish: bool, conn: &PgConnection) -> BackendResult<Article> {
-        use crate::schema::articles::dsl::*;
-        use crate::schema::articles;
-
         let publish_value: Option<NaiveDateTime> = if publish {
             Some(Utc::now().naive_utc())
         } else {
             None
         };
+        Article::set_publish_date(article_uuid, publish_value, conn)
+    }
+
+    /// Sets the article's publish date, which may be in the future to schedule its publication.
+    ///
+    /// Only approved articles can be published.
+    /// Publishing the article also publishes its latest draft, which has to have been approved too.
+    /// The article will be announced again once the new date passes.
+    pub fn set_publish_date(article_uuid: ArticleUuid, date: Option<NaiveDateTime>, conn: &PgConnection) -> BackendResult<Article> {
+        use crate::schema::articles::dsl::*;
+        use crate::schema::articles;
+
+        // The article is published along with its comment thread, or not at all.
+        conn.transaction::<_, Error, _>(|| {
+            // Lock the article, so it can't be changed between checking what was approved and publishing it.
+            let article: Article = Article::lock_article(article_uuid, conn)?;
+            let latest: ArticleRevision = ArticleRevision::get_latest_revision(article_uuid, conn)?;
+            let new_state: ReviewState = match (date, article.state()) {
+                (Some(_), _) => {
+                    article.check_latest_approved(&latest, conn)?;
+                    ReviewState::Published
+                }
+                // Unpublished articles stay approved, so they can be published again.
+                (None, ReviewState::Published) => ReviewState::Approved,
+                (None, state) => state,
+            };
+
+            if date.is_some() {
+                article.apply_revision(&latest, conn)
+                    .map_err(handle_err::<Article>)?;
+            }
+            let article: Article = diesel::update(articles::table)
+                .filter(articles::uuid.eq(article_uuid.0))
+                .set((
+                    publish_date.eq(date),
+                    announced_date.eq(None::<NaiveDateTime>),
+                    review_state.eq(i32::from(new_state)),
+                ))
+                .get_result(conn)
+                .map_err(handle_err::<Article>)?;
+
+            // Embargoed articles get their comment thread once they are announced,
+            // so the thread doesn't give the article away early.
+            if article.is_published() {
+                Article::open_comments(article_uuid, conn)
+            } else {
+                Article::hide_comments(&article, conn)?;
+                Ok(article)
+            }
+        })
+    }
+
+    /// Opens the article's comments, creating the thread they are posted in if it doesn't have one yet.
+    /// The thread is created in the forum for article comments, on behalf of the article's author.
+    ///
+    /// A thread hidden while the article wasn't published is shown again.
+    /// Nothing is created if comments are disabled for the article.
+    pub fn open_comments(article_uuid: ArticleUuid, conn: &PgConnection) -> BackendResult<Article> {
+        use crate::schema::articles::dsl::*;
+        use crate::schema::articles;
+
+        conn.transaction::<_, Error, _>(|| {
+            // Lock the article, so another request can't create a second thread for it at the same time.
+            let article: Article = articles::table
+                .find(article_uuid.0)
+                .for_update()
+                .first(conn)
+                .map_err(handle_err::<Article>)?;
+            if let Some(thread_uuid) = article.comment_thread_uuid {
+                Thread::unarchive_thread(ThreadUuid(thread_uuid), conn)?;
+                Thread::set_lock_status(ThreadUuid(thread_uuid), !article.comments_enabled, conn)?;
+                return Ok(article)
+            }
+            if !article.comments_enabled {
+                return Ok(article)
+            }
+
+            let forum: Forum = Forum::get_article_comments_forum(conn)?;
+            let now: NaiveDateTime = Utc::now().naive_utc();
+            let new_thread = NewThread {
+                forum_uuid: forum.uuid,
+                author_uuid: article.author_uuid,
+                created_date: now,
+                locked: false,
+                archived: false,
+                title: article.title.clone(),
+                last_post_date: now,
+            };
+            let content: String = format!("Comments on [{}](/article/{}).", article.title, article.slug);
+            let thread_data: ThreadData = Thread::create_thread_with_initial_post(new_thread, content, None, conn)?;
+
+            diesel::update(articles::table)
+                .filter(articles::uuid.eq(article_uuid.0))
+                .set(comment_thread_uuid.eq(thread_data.thread.uuid))
+                .get_result(conn)
+                .map_err(handle_err::<Article>)
+        })
+    }
+
+    /// Archives and locks the article's comment thread, so it can't be read or posted in while the article isn't published.
+    fn hide_comments(article: &Article, conn: &PgConnection) -> BackendResult<()> {
+        if let Some(thread_uuid) = article.comment_thread_uuid {
+            Thread::archive_thread(ThreadUuid(thread_uuid), conn)?;
+            Thread::set_lock_status(ThreadUuid(thread_uuid), true, conn)?;
+        }
+        Ok(())
+    }
+
+    /// Allows or disallows comments on the article.
+    ///
+    /// Disabling comments locks the article's thread, so the existing comments can still be read.
+    /// Enabling them unlocks the thread, or creates it if the article is published and doesn't have one yet.
+    pub fn set_comments_enabled(article_uuid: ArticleUuid, enabled: bool, conn: &PgConnection) -> BackendResult<Article> {
+        use crate::schema::articles::dsl::*;
+        use crate::schema::articles;
 
-        diesel::update(articles::table)
+        let article: Article = diesel::update(articles::table)
             .filter(articles::uuid.eq(article_uuid.0))
-            .set(publish_date.eq(publish_value))
+            .set(comments_enabled.eq(enabled))
             .get_result(conn)
+            .map_err(handle_err::<Article>)?;
+
+        match article.comment_thread_uuid {
+            Some(thread_uuid) => {
+                // The thread stays locked while the article isn't published.
+                Thread::set_lock_status(ThreadUuid(thread_uuid), !enabled || !article.is_published(), conn)?;
+                Ok(article)
+            }
+            None if enabled && article.is_published() => Article::open_comments(article_uuid, conn),
+            None => Ok(article)
+        }
+    }
+
+    /// Gets the articles whose publish date has passed, but haven't been announced yet.
+    pub fn get_unannounced_articles(now: NaiveDateTime, conn: &PgConnection) -> BackendResult<Vec<ArticleData>> {
+        use crate::schema::articles::dsl::*;
+        use crate::schema::users;
+
+        articles
+            .inner_join(users::table)
+            .filter(publish_date.le(now))
+            .filter(announced_date.is_null())
+            .order(publish_date)
+            .load::<(Article, User)>(conn)
             .map_err(handle_err::<Article>)
+            .and_then(|articles_and_users| Article::attach_details(articles_and_users, conn))
     }
 
+    /// Records that the articles' publication has been announced.
+    pub fn mark_announced(article_uuids: Vec<Uuid>, now: NaiveDateTime, conn: &PgConnection) -> BackendResult<usize> {
+        use crate::schema::articles::dsl::*;
+
+        diesel::update(articles.filter(uuid.eq_any(article_uuids)))
+            .set(announced_date.eq(now))
+            .execute(conn)
+            .map_err(handle_err::<Article>)
+    }
 
-    /// Applies the changeset to its corresponding article.
+    /// Saves a new revision of the article, taking whatever the changeset leaves out from the latest revision.
+    ///
+    /// Readers of a published or scheduled article keep seeing the published revision
+    /// until the changes are approved and the article is republished,
+    /// but changes to an unpublished article are applied to it right away.
     pub fn update_article(changeset: ArticleChangeset, conn: &PgConnection) -> BackendResult<Article> {
-        use crate::schema::articles;
-        diesel::update(articles::table)
-            .set(&changeset)
-            .get_result(conn)
+        Article::save_revision(changeset, false, conn).map(|(article, _)| article)
+    }
+
+    /// Saves a revision on behalf of the editor, rather than the author.
+    pub fn autosave(changeset: ArticleChangeset, conn: &PgConnection) -> BackendResult<ArticleRevision> {
+        Article::save_revision(changeset, true, conn).map(|(_, revision)| revision)
+    }
+
+    /// Saves a copy of an old revision, making it the latest one.
+    pub fn restore_revision(revision_uuid: ArticleRevisionUuid, conn: &PgConnection) -> BackendResult<(Article, ArticleRevision)> {
+        let revision: ArticleRevision = ArticleRevision::get_revision(revision_uuid, conn)?;
+        let changeset = ArticleChangeset {
+            uuid: revision.article_uuid,
+            title: Some(revision.title),
+            body: Some(revision.body),
+        };
+        Article::save_revision(changeset, false, conn)
+    }
+
+    /// Changing an article that is in review, has been approved, or has been published,
+    /// returns it to being a draft, so the changes get reviewed too.
+    /// Published and scheduled articles keep their approved revision until the changes are approved and republished.
+    fn save_revision(changeset: ArticleChangeset, autosave: bool, conn: &PgConnection) -> BackendResult<(Article, ArticleRevision)> {
+        conn.transaction::<_, Error, _>(|| {
+            // Lock the article, so it can't be reviewed or published between reading its state and saving the revision.
+            let article: Article = Article::lock_article(ArticleUuid(changeset.uuid), conn)?;
+            let latest: ArticleRevision = ArticleRevision::get_latest_revision(ArticleUuid(article.uuid), conn)?;
+            let new_revision = NewArticleRevision {
+                article_uuid: article.uuid,
+                title: changeset.title.unwrap_or_else(|| latest.title.clone()),
+                body: changeset.body.unwrap_or_else(|| latest.body.clone()),
+                autosave,
+            };
+            let changed: bool = new_revision.title != latest.title || new_revision.body != latest.body;
+            let needs_review: bool = changed && article.state() != ReviewState::Draft;
+
+            let revision: ArticleRevision = diesel::insert_into(article_revisions::table)
+                .values(&new_revision)
+                .get_result(conn)
+                .map_err(handle_err::<ArticleRevision>)?;
+            let article: Article = if needs_review {
+                diesel::update(articles::table.find(article.uuid))
+                    .set(articles::review_state.eq(i32::from(ReviewState::Draft)))
+                    .get_result(conn)
+                    .map_err(handle_err::<Article>)?
+            } else {
+                article
+            };
+            let article: Article = if article.publish_date.is_some() {
+                article
+            } else {
+                article.apply_revision(&revision, conn)
+                    .map_err(handle_err::<Article>)?
+            };
+            Ok((article, revision))
+        })
+    }
+
+    /// Shows the latest revision to readers, without changing the publish date.
+    ///
+    /// The revision has to have been approved, like the article was before it was first published.
+    pub fn publish_draft(article_uuid: ArticleUuid, conn: &PgConnection) -> BackendResult<Article> {
+        conn.transaction::<_, Error, _>(|| {
+            // Lock the article, so it can't be changed between checking what was approved and publishing it.
+            let article: Article = Article::lock_article(article_uuid, conn)?;
+            let latest: ArticleRevision = ArticleRevision::get_latest_revision(article_uuid, conn)?;
+            article.check_latest_approved(&latest, conn)?;
+            let article: Article = article.apply_revision(&latest, conn)
+                .map_err(handle_err::<Article>)?;
+            if article.publish_date.is_some() {
+                diesel::update(articles::table.find(article.uuid))
+                    .set(articles::review_state.eq(i32::from(ReviewState::Published)))
+                    .get_result(conn)
+                    .map_err(handle_err::<Article>)
+            } else {
+                Ok(article)
+            }
+        })
+    }
+
+    /// Gets the article, locking it until the end of the transaction.
+    pub(crate) fn lock_article(article_uuid: ArticleUuid, conn: &PgConnection) -> BackendResult<Article> {
+        articles::table
+            .find(article_uuid.0)
+            .for_update()
+            .first(conn)
             .map_err(handle_err::<Article>)
     }
+
+    /// Checks that the latest revision can be shown to readers.
+    ///
+    /// Approved articles can show the revision that was approved,
+    /// while published ones can only show revisions that don't change what was approved.
+    /// Articles published before reviews existed were never approved, so they can only show what they already did.
+    fn check_latest_approved(&self, latest: &ArticleRevision, conn: &PgConnection) -> BackendResult<()> {
+        let unchanged: bool = Some(latest.uuid) == self.published_revision_uuid
+            || (latest.title == self.title && latest.body == self.body);
+        match self.state() {
+            ReviewState::Approved => {
+                let approved_latest: bool = match ArticleReview::get_approved_revision_uuid(ArticleUuid(self.uuid), conn)? {
+                    Some(approved_uuid) => approved_uuid == latest.uuid,
+                    None => unchanged,
+                };
+                if approved_latest {
+                    Ok(())
+                } else {
+                    Err(Error::NotAuthorized { reason: "The article's changes haven't been approved" })
+                }
+            }
+            ReviewState::Published if unchanged => Ok(()),
+            ReviewState::Published => Err(Error::NotAuthorized { reason: "The article's changes haven't been approved" }),
+            _ => Err(Error::NotAuthorized { reason: "Article hasn't been approved" }),
+        }
+    }
+
+    /// Gets the revision saved after the published one, if there is one.
+    pub fn get_draft(&self, conn: &PgConnection) -> BackendResult<Option<ArticleRevision>> {
+        let latest: ArticleRevision = ArticleRevision::get_latest_revision(ArticleUuid(self.uuid), conn)?;
+        if Some(latest.uuid) == self.published_revision_uuid {
+            Ok(None)
+        } else {
+            Ok(Some(latest))
+        }
+    }
+
+    /// Copies the revision's title and body into the article.
+    ///
+    /// If the title changes, the article gets a new slug,
+    /// and the old one is kept so that it can still be used to find the article.
+    fn apply_revision(&self, revision: &ArticleRevision, conn: &PgConnection) -> Result<Article, DieselError> {
+        let new_slug: String = if revision.title != self.title {
+            diesel::insert_into(article_slugs::table)
+                .values(&NewArticleSlug {
+                    slug: self.slug.clone(),
+                    article_uuid: self.uuid,
+                })
+                .execute(conn)?;
+            slugify(&revision.title)
+        } else {
+            self.slug.clone()
+        };
+
+        diesel::update(articles::table.find(self.uuid))
+            .set((
+                articles::title.eq(&revision.title),
+                articles::body.eq(&revision.body),
+                articles::slug.eq(new_slug),
+                articles::published_revision_uuid.eq(revision.uuid),
+            ))
+            .get_result(conn)
+    }
 }
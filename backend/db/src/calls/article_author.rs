@@ -0,0 +1,129 @@
+use crate::schema::article_authors;
+use crate::schema::users;
+use crate::article::Article;
+use crate::user::User;
+use std::collections::HashMap;
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::PgConnection;
+use diesel::Connection;
+use diesel::result::Error as DieselError;
+use error::{BackendResult, Error};
+use uuid::Uuid;
+use identifiers::user::UserUuid;
+use crate::calls::prelude::*;
+
+/// The most co-authors an article can have.
+pub const MAX_CO_AUTHORS: usize = 10;
+
+/// Credits a user as one of an article's authors, alongside the user that created it.
+#[derive(Clone, Queryable, Identifiable, Associations, Debug, PartialEq, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(Article, foreign_key = "article_uuid")]
+#[belongs_to(User, foreign_key = "user_uuid")]
+#[table_name = "article_authors"]
+pub struct ArticleAuthor {
+    pub uuid: Uuid,
+    pub article_uuid: Uuid,
+    pub user_uuid: Uuid,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "article_authors"]
+pub struct NewArticleAuthor {
+    pub article_uuid: Uuid,
+    pub user_uuid: Uuid,
+}
+
+impl ArticleAuthor {
+    /// Replaces the article's co-authors, returning them in order of their display names.
+    ///
+    /// The article's author is left out if included, as they are already credited.
+    pub fn set_co_authors(article: &Article, user_uuids: Vec<UserUuid>, conn: &PgConnection) -> BackendResult<Vec<User>> {
+        let mut co_author_uuids: Vec<Uuid> = vec![];
+        for user_uuid in user_uuids {
+            if user_uuid.0 != article.author_uuid && !co_author_uuids.contains(&user_uuid.0) {
+                co_author_uuids.push(user_uuid.0);
+            }
+        }
+        if co_author_uuids.len() > MAX_CO_AUTHORS {
+            return Err(Error::BadRequest)
+        }
+
+        let co_authors: Vec<User> = users::table
+            .filter(users::uuid.eq_any(&co_author_uuids))
+            .order(users::display_name)
+            .load::<User>(conn)
+            .map_err(handle_err::<User>)?;
+        if co_authors.len() != co_author_uuids.len() {
+            return Err(Error::NotFound { type_name: "User".to_string() })
+        }
+
+        let new_authors: Vec<NewArticleAuthor> = co_author_uuids
+            .into_iter()
+            .map(|user_uuid| NewArticleAuthor {
+                article_uuid: article.uuid,
+                user_uuid,
+            })
+            .collect();
+        conn.transaction::<_, DieselError, _>(|| {
+            diesel::delete(article_authors::table.filter(article_authors::article_uuid.eq(article.uuid)))
+                .execute(conn)?;
+            if new_authors.is_empty() {
+                return Ok(0)
+            }
+            diesel::insert_into(article_authors::table)
+                .values(&new_authors)
+                .execute(conn)
+        })
+            .map_err(handle_err::<ArticleAuthor>)?;
+        Ok(co_authors)
+    }
+
+    /// Gets the co-authors of each of the articles, in order of their display names.
+    /// Articles without co-authors are left out of the map.
+    pub fn get_co_authors(article_uuids: Vec<Uuid>, conn: &PgConnection) -> BackendResult<HashMap<Uuid, Vec<User>>> {
+        let co_authors: Vec<(ArticleAuthor, User)> = article_authors::table
+            .inner_join(users::table)
+            .filter(article_authors::article_uuid.eq_any(article_uuids))
+            .order(users::display_name)
+            .load(conn)
+            .map_err(handle_err::<ArticleAuthor>)?;
+
+        let mut co_authors_by_article: HashMap<Uuid, Vec<User>> = HashMap::new();
+        for (author, user) in co_authors {
+            co_authors_by_article
+                .entry(author.article_uuid)
+                .or_insert_with(Vec::new)
+                .push(user);
+        }
+        Ok(co_authors_by_article)
+    }
+
+    /// Gets the articles that the user is a co-author of.
+    pub fn get_co_authored_article_uuids(user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<Vec<Uuid>> {
+        article_authors::table
+            .filter(article_authors::user_uuid.eq(user_uuid.0))
+            .select(article_authors::article_uuid)
+            .load(conn)
+            .map_err(handle_err::<ArticleAuthor>)
+    }
+}
+
+impl Article {
+    /// Whether the user wrote the article, or is credited as one of its co-authors.
+    pub fn is_author(&self, user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<bool> {
+        if self.author_uuid == user_uuid.0 {
+            return Ok(true)
+        }
+        article_authors::table
+            .filter(article_authors::article_uuid.eq(self.uuid))
+            .filter(article_authors::user_uuid.eq(user_uuid.0))
+            .count()
+            .get_result::<i64>(conn)
+            .map(|count| count > 0)
+            .map_err(handle_err::<ArticleAuthor>)
+    }
+}
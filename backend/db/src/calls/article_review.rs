@@ -0,0 +1,186 @@
+use crate::schema::article_reviews;
+use crate::schema::articles;
+use crate::schema::users;
+use crate::article::{Article, ArticleData};
+use crate::article_revision::ArticleRevision;
+use crate::user::User;
+use chrono::{NaiveDateTime, Utc};
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::PgConnection;
+use diesel::OptionalExtension;
+use diesel::Connection;
+use error::{BackendResult, Error};
+use uuid::Uuid;
+use identifiers::article::ArticleUuid;
+use identifiers::user::UserUuid;
+use wire::article::{ReviewState, ReviewDecision};
+use crate::calls::prelude::*;
+
+/// The longest a review's comment can be.
+pub const MAX_REVIEW_COMMENT_LENGTH: usize = 4096;
+
+/// A comment left on an article while it is being reviewed.
+#[derive(Clone, Queryable, Identifiable, Associations, Debug, PartialEq, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(Article, foreign_key = "article_uuid")]
+#[belongs_to(User, foreign_key = "reviewer_uuid")]
+#[table_name = "article_reviews"]
+pub struct ArticleReview {
+    pub uuid: Uuid,
+    pub article_uuid: Uuid,
+    /// The publisher or author that left the review.
+    pub reviewer_uuid: Uuid,
+    /// What the reviewer decided, as the number of a `ReviewDecision`.
+    pub decision: i32,
+    pub comment: String,
+    /// The latest revision of the article when the review was left, which is the one an approval approves.
+    pub revision_uuid: Option<Uuid>,
+    pub created_date: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "article_reviews"]
+pub struct NewArticleReview {
+    pub article_uuid: Uuid,
+    pub reviewer_uuid: Uuid,
+    pub decision: i32,
+    pub comment: String,
+    pub revision_uuid: Option<Uuid>,
+}
+
+#[derive(Debug)]
+pub struct ReviewData {
+    pub review: ArticleReview,
+    pub reviewer: User,
+}
+
+impl ArticleReview {
+    /// Records the review, moving the article to the state the decision calls for.
+    ///
+    /// Anyone allowed to see the review can comment on it,
+    /// but only someone other than the article's authors can approve it or request changes,
+    /// and only while it is in review.
+    pub fn review(
+        article_uuid: ArticleUuid,
+        reviewer_uuid: UserUuid,
+        decision: ReviewDecision,
+        comment: String,
+        conn: &PgConnection
+    ) -> BackendResult<ReviewData> {
+        if comment.chars().count() > MAX_REVIEW_COMMENT_LENGTH
+            || (decision == ReviewDecision::Comment && comment.trim().is_empty()) {
+            return Err(Error::BadRequest)
+        }
+        let new_state: Option<ReviewState> = match decision {
+            ReviewDecision::Comment => None,
+            ReviewDecision::Approve => Some(ReviewState::Approved),
+            ReviewDecision::RequestChanges => Some(ReviewState::Draft),
+        };
+        let reviewer: User = User::get_user(reviewer_uuid, conn)?;
+
+        let review: ArticleReview = conn.transaction::<_, Error, _>(|| {
+            // Lock the article, so it can't be changed or reviewed by someone else while this review is recorded.
+            let article: Article = Article::lock_article(article_uuid, conn)?;
+            if new_state.is_some() {
+                if article.state() != ReviewState::InReview {
+                    return Err(Error::BadRequest)
+                }
+                if article.is_author(reviewer_uuid, conn)? {
+                    return Err(Error::NotAuthorized { reason: "Authors can't review their own article" })
+                }
+            }
+            let latest: ArticleRevision = ArticleRevision::get_latest_revision(article_uuid, conn)?;
+
+            if let Some(new_state) = new_state {
+                diesel::update(articles::table.find(article.uuid))
+                    .set(articles::review_state.eq(i32::from(new_state)))
+                    .execute(conn)
+                    .map_err(handle_err::<Article>)?;
+            }
+            let new_review = NewArticleReview {
+                article_uuid: article.uuid,
+                reviewer_uuid: reviewer.uuid,
+                decision: decision.into(),
+                comment,
+                revision_uuid: Some(latest.uuid),
+            };
+            diesel::insert_into(article_reviews::table)
+                .values(&new_review)
+                .get_result(conn)
+                .map_err(handle_err::<ArticleReview>)
+        })?;
+        Ok(ReviewData { review, reviewer })
+    }
+
+    /// Gets the revision of the article that was most recently approved, if it has been approved.
+    pub fn get_approved_revision_uuid(article_uuid: ArticleUuid, conn: &PgConnection) -> BackendResult<Option<Uuid>> {
+        article_reviews::table
+            .filter(article_reviews::article_uuid.eq(article_uuid.0))
+            .filter(article_reviews::decision.eq(i32::from(ReviewDecision::Approve)))
+            .order(article_reviews::created_date.desc())
+            .select(article_reviews::revision_uuid)
+            .first::<Option<Uuid>>(conn)
+            .optional()
+            .map(Option::flatten)
+            .map_err(handle_err::<ArticleReview>)
+    }
+
+    /// Gets the reviews of the article, oldest first.
+    pub fn get_reviews_for_article(article_uuid: ArticleUuid, conn: &PgConnection) -> BackendResult<Vec<ReviewData>> {
+        article_reviews::table
+            .inner_join(users::table)
+            .filter(article_reviews::article_uuid.eq(article_uuid.0))
+            .order(article_reviews::created_date)
+            .load::<(ArticleReview, User)>(conn)
+            .map(|reviews| {
+                reviews
+                    .into_iter()
+                    .map(|(review, reviewer)| ReviewData { review, reviewer })
+                    .collect()
+            })
+            .map_err(handle_err::<ArticleReview>)
+    }
+}
+
+impl Article {
+    /// Where the article is in the editorial process.
+    pub fn state(&self) -> ReviewState {
+        ReviewState::from(self.review_state)
+    }
+
+    /// Asks the publishers to review the article.
+    ///
+    /// Only drafts can be submitted.
+    pub fn submit_for_review(article_uuid: ArticleUuid, conn: &PgConnection) -> BackendResult<Article> {
+        let article: Article = Article::get_article(article_uuid, conn)?;
+        let now: NaiveDateTime = Utc::now().naive_utc();
+        // The state is checked by the update itself, so a concurrent change to the article can't slip in between.
+        diesel::update(
+            articles::table
+                .find(article.uuid)
+                .filter(articles::review_state.eq(i32::from(ReviewState::Draft)))
+        )
+            .set((
+                articles::review_state.eq(i32::from(ReviewState::InReview)),
+                articles::review_requested_date.eq(now),
+            ))
+            .get_result(conn)
+            .optional()
+            .map_err(handle_err::<Article>)?
+            .ok_or(Error::BadRequest)
+    }
+
+    /// Gets the articles waiting to be reviewed, those submitted longest ago first.
+    pub fn get_review_queue(conn: &PgConnection) -> BackendResult<Vec<ArticleData>> {
+        articles::table
+            .inner_join(users::table)
+            .filter(articles::review_state.eq(i32::from(ReviewState::InReview)))
+            .order(articles::review_requested_date)
+            .load::<(Article, User)>(conn)
+            .map_err(handle_err::<Article>)
+            .and_then(|articles_and_users| Article::attach_details(articles_and_users, conn))
+    }
+}
@@ -0,0 +1,165 @@
+use crate::schema::article_revisions;
+use crate::article::Article;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::PgConnection;
+use chrono::NaiveDateTime;
+use error::{BackendResult, Error};
+use uuid::Uuid;
+use identifiers::article::ArticleUuid;
+use identifiers::article_revision::ArticleRevisionUuid;
+use crate::calls::prelude::*;
+use crate::schema;
+
+
+/// A saved version of an article's title and body.
+#[derive(Clone, Queryable, Identifiable, Associations, Debug, PartialEq, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(Article, foreign_key = "article_uuid")]
+#[table_name = "article_revisions"]
+pub struct ArticleRevision {
+    pub uuid: Uuid,
+    /// The key of the article the revision belongs to.
+    pub article_uuid: Uuid,
+    pub title: String,
+    pub body: String,
+    /// Whether the revision was saved by the editor on its own, rather than by the author.
+    pub autosave: bool,
+    pub created_date: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "article_revisions"]
+pub struct NewArticleRevision {
+    pub article_uuid: Uuid,
+    pub title: String,
+    pub body: String,
+    pub autosave: bool,
+}
+
+/// A line of text, and how it changed between two revisions.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiffLine {
+    Unchanged(String),
+    Added(String),
+    Removed(String),
+}
+
+/// The changes made to an article between two of its revisions.
+pub struct RevisionDiff {
+    pub from: ArticleRevision,
+    pub to: ArticleRevision,
+    pub title: Vec<DiffLine>,
+    pub body: Vec<DiffLine>,
+}
+
+impl ArticleRevision {
+    pub fn get_revision(uuid: ArticleRevisionUuid, conn: &PgConnection) -> BackendResult<ArticleRevision> {
+        get_row::<ArticleRevision,_>(schema::article_revisions::table, uuid.0, conn)
+    }
+
+    /// Gets the revisions with the given keys.
+    pub fn get_revisions(uuids: Vec<Uuid>, conn: &PgConnection) -> BackendResult<Vec<ArticleRevision>> {
+        article_revisions::table
+            .filter(article_revisions::uuid.eq_any(uuids))
+            .load::<ArticleRevision>(conn)
+            .map_err(handle_err::<ArticleRevision>)
+    }
+
+    /// Gets every revision of the article, newest first.
+    pub fn get_revisions_for_article(article_uuid: ArticleUuid, conn: &PgConnection) -> BackendResult<Vec<ArticleRevision>> {
+        article_revisions::table
+            .filter(article_revisions::article_uuid.eq(article_uuid.0))
+            .order(article_revisions::created_date.desc())
+            .load::<ArticleRevision>(conn)
+            .map_err(handle_err::<ArticleRevision>)
+    }
+
+    /// Gets the most recently saved revision of the article.
+    pub fn get_latest_revision(article_uuid: ArticleUuid, conn: &PgConnection) -> BackendResult<ArticleRevision> {
+        article_revisions::table
+            .filter(article_revisions::article_uuid.eq(article_uuid.0))
+            .order(article_revisions::created_date.desc())
+            .first::<ArticleRevision>(conn)
+            .map_err(handle_err::<ArticleRevision>)
+    }
+
+    /// Compares two revisions of the same article.
+    pub fn diff(from: ArticleRevisionUuid, to: ArticleRevisionUuid, conn: &PgConnection) -> BackendResult<RevisionDiff> {
+        let from: ArticleRevision = ArticleRevision::get_revision(from, conn)?;
+        let to: ArticleRevision = ArticleRevision::get_revision(to, conn)?;
+        if from.article_uuid != to.article_uuid {
+            return Err(Error::BadRequest)
+        }
+        Ok(RevisionDiff {
+            title: diff_lines(&from.title, &to.title)?,
+            body: diff_lines(&from.body, &to.body)?,
+            from,
+            to,
+        })
+    }
+}
+
+/// The most lines that can differ between the texts being compared.
+/// Finding the changes takes memory and time proportional to the product of the lines that differ in each text.
+pub const MAX_DIFF_LINES: usize = 2000;
+
+/// Finds the lines that were added and removed to get from the old text to the new one.
+///
+/// This uses the longest common subsequence of the lines,
+/// after setting aside the lines that are the same at the start and end of both texts.
+/// Texts with more than `MAX_DIFF_LINES` lines between those are rejected.
+pub fn diff_lines(old: &str, new: &str) -> BackendResult<Vec<DiffLine>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix: usize = old.iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix: usize = old[prefix..].iter().rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle: &[&str] = &old[prefix..old.len() - suffix];
+    let new_middle: &[&str] = &new[prefix..new.len() - suffix];
+    if old_middle.len() > MAX_DIFF_LINES || new_middle.len() > MAX_DIFF_LINES {
+        return Err(Error::BadRequest)
+    }
+
+    // lengths[i][j] is the length of the longest common subsequence of old_middle[i..] and new_middle[j..].
+    let mut lengths: Vec<Vec<usize>> = vec![vec![0; new_middle.len() + 1]; old_middle.len() + 1];
+    for i in (0..old_middle.len()).rev() {
+        for j in (0..new_middle.len()).rev() {
+            lengths[i][j] = if old_middle[i] == new_middle[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff: Vec<DiffLine> = old[..prefix]
+        .iter()
+        .map(|line| DiffLine::Unchanged(line.to_string()))
+        .collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_middle.len() && j < new_middle.len() {
+        if old_middle[i] == new_middle[j] {
+            diff.push(DiffLine::Unchanged(old_middle[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            diff.push(DiffLine::Removed(old_middle[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new_middle[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(old_middle[i..].iter().map(|line| DiffLine::Removed(line.to_string())));
+    diff.extend(new_middle[j..].iter().map(|line| DiffLine::Added(line.to_string())));
+    diff.extend(old[old.len() - suffix..].iter().map(|line| DiffLine::Unchanged(line.to_string())));
+    Ok(diff)
+}
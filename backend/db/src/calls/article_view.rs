@@ -0,0 +1,206 @@
+use crate::schema::article_viewers;
+use crate::schema::article_daily_views;
+use crate::schema::articles;
+use crate::schema::users;
+use crate::article::{Article, ArticleData};
+use crate::user::User;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::PgConnection;
+use diesel::Connection;
+use diesel::result::Error as DieselError;
+use diesel::sql_types::{BigInt, Date, Timestamp};
+use diesel::sql_types::Uuid as SqlUuid;
+use error::BackendResult;
+use uuid::Uuid;
+use identifiers::article::ArticleUuid;
+use crate::calls::prelude::*;
+
+/// The number of viewers an article had on a day.
+#[derive(Clone, Queryable, Identifiable, Associations, Debug, PartialEq, TypeName)]
+#[primary_key(article_uuid, view_date)]
+#[belongs_to(Article, foreign_key = "article_uuid")]
+#[table_name = "article_daily_views"]
+pub struct ArticleDailyViews {
+    pub article_uuid: Uuid,
+    pub view_date: NaiveDate,
+    pub views: i32,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "article_daily_views"]
+struct NewArticleDailyViews {
+    article_uuid: Uuid,
+    view_date: NaiveDate,
+    views: i32,
+}
+
+/// Records that someone viewed an article on a day.
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "article_viewers"]
+struct NewArticleViewer {
+    article_uuid: Uuid,
+    view_date: NaiveDate,
+    viewer_hash: String,
+}
+
+/// How many viewers an article has had, and how many it had on each day of a period.
+#[derive(Debug)]
+pub struct ArticleStats {
+    pub article_uuid: Uuid,
+    /// The viewers the article has had since it was first viewed.
+    pub total_views: i64,
+    /// Every day of the period, oldest first, including the days without any viewers.
+    pub daily_views: Vec<ArticleDailyViews>,
+}
+
+/// A published article, and how many viewers it had over a period.
+#[derive(Debug)]
+pub struct MostReadArticle {
+    pub data: ArticleData,
+    pub views: i64,
+}
+
+#[derive(Debug, Clone, QueryableByName)]
+struct ViewCount {
+    #[sql_type = "SqlUuid"]
+    article_uuid: Uuid,
+    #[sql_type = "BigInt"]
+    views: i64,
+}
+
+/// Finds the published articles with the most viewers since a date,
+/// preferring more recently published articles when they had as many.
+const MOST_READ_QUERY: &str = "
+    SELECT
+        v.article_uuid AS article_uuid,
+        SUM(v.views)::BIGINT AS views
+    FROM article_daily_views v
+    INNER JOIN articles a ON a.uuid = v.article_uuid
+    WHERE v.view_date >= $1
+        AND a.publish_date <= $2
+    GROUP BY v.article_uuid, a.publish_date
+    ORDER BY views DESC, a.publish_date DESC
+    LIMIT $3
+";
+
+impl ArticleDailyViews {
+    /// Counts the viewer, unless they already viewed the article on the day.
+    ///
+    /// The hash should identify the viewer without revealing who they are.
+    /// Returns whether the view was counted.
+    pub fn record_view(article_uuid: ArticleUuid, viewer_hash: String, today: NaiveDate, conn: &PgConnection) -> BackendResult<bool> {
+        let new_viewer = NewArticleViewer {
+            article_uuid: article_uuid.0,
+            view_date: today,
+            viewer_hash,
+        };
+        let new_views = NewArticleDailyViews {
+            article_uuid: article_uuid.0,
+            view_date: today,
+            views: 1,
+        };
+
+        conn.transaction::<_, DieselError, _>(|| {
+            let inserted: usize = diesel::insert_into(article_viewers::table)
+                .values(&new_viewer)
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            if inserted == 0 {
+                return Ok(false)
+            }
+
+            diesel::insert_into(article_daily_views::table)
+                .values(&new_views)
+                .on_conflict((article_daily_views::article_uuid, article_daily_views::view_date))
+                .do_update()
+                .set(article_daily_views::views.eq(article_daily_views::views + 1))
+                .execute(conn)?;
+            Ok(true)
+        })
+            .map_err(handle_err::<ArticleDailyViews>)
+    }
+
+    /// Deletes the hashes of the viewers from before the day.
+    ///
+    /// They were made with the salts of earlier days, so they won't match anyone viewing today.
+    /// Returns the number of hashes deleted.
+    pub fn purge_old_viewers(today: NaiveDate, conn: &PgConnection) -> BackendResult<usize> {
+        diesel::delete(article_viewers::table.filter(article_viewers::view_date.lt(today)))
+            .execute(conn)
+            .map_err(handle_err::<ArticleDailyViews>)
+    }
+
+    /// Gets the article's total number of viewers, and its viewers on each day from the first date through to the last.
+    pub fn get_stats(article_uuid: ArticleUuid, first: NaiveDate, last: NaiveDate, conn: &PgConnection) -> BackendResult<ArticleStats> {
+        let total_views: Option<i64> = article_daily_views::table
+            .filter(article_daily_views::article_uuid.eq(article_uuid.0))
+            .select(diesel::dsl::sum(article_daily_views::views))
+            .first(conn)
+            .map_err(handle_err::<ArticleDailyViews>)?;
+        let recorded: Vec<ArticleDailyViews> = article_daily_views::table
+            .filter(article_daily_views::article_uuid.eq(article_uuid.0))
+            .filter(article_daily_views::view_date.between(first, last))
+            .load(conn)
+            .map_err(handle_err::<ArticleDailyViews>)?;
+
+        let mut daily_views: Vec<ArticleDailyViews> = vec![];
+        let mut date: NaiveDate = first;
+        while date <= last {
+            let views: i32 = recorded
+                .iter()
+                .find(|day| day.view_date == date)
+                .map(|day| day.views)
+                .unwrap_or(0);
+            daily_views.push(ArticleDailyViews {
+                article_uuid: article_uuid.0,
+                view_date: date,
+                views,
+            });
+            date = date.succ();
+        }
+
+        Ok(ArticleStats {
+            article_uuid: article_uuid.0,
+            total_views: total_views.unwrap_or(0),
+            daily_views,
+        })
+    }
+}
+
+impl Article {
+    /// Gets the published articles with the most viewers since the date, those with the most first.
+    pub fn get_most_read(since: NaiveDate, now: NaiveDateTime, limit: i64, conn: &PgConnection) -> BackendResult<Vec<MostReadArticle>> {
+        let counts: Vec<ViewCount> = diesel::sql_query(MOST_READ_QUERY)
+            .bind::<Date, _>(since)
+            .bind::<Timestamp, _>(now)
+            .bind::<BigInt, _>(limit)
+            .load(conn)
+            .map_err(handle_err::<ArticleDailyViews>)?;
+
+        let article_uuids: Vec<Uuid> = counts.iter().map(|x| x.article_uuid).collect();
+        let mut articles_and_users: Vec<(Article, User)> = articles::table
+            .inner_join(users::table)
+            .filter(articles::uuid.eq_any(&article_uuids))
+            .load::<(Article, User)>(conn)
+            .map_err(handle_err::<Article>)?;
+        // Keep the order the articles were ranked in.
+        articles_and_users.sort_by_key(|(article, _)| article_uuids.iter().position(|x| *x == article.uuid));
+
+        let most_read = Article::attach_details(articles_and_users, conn)?
+            .into_iter()
+            .map(|data| {
+                let views: i64 = counts
+                    .iter()
+                    .find(|count| count.article_uuid == data.article.uuid)
+                    .map(|count| count.views)
+                    .unwrap_or(0);
+                MostReadArticle { data, views }
+            })
+            .collect();
+        Ok(most_read)
+    }
+}
@@ -2,10 +2,21 @@ use crate::schema::forums;
 use uuid::Uuid;
 use diesel::pg::PgConnection;
 use identifiers::forum::ForumUuid;
-use error::BackendResult;
+use error::{BackendResult, Error};
+use diesel::result::Error as DieselError;
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::Connection;
+use diesel::OptionalExtension;
 use crate::calls::prelude::*;
 use crate::schema;
 
+/// The title given to the forum that holds the comment threads of articles, when it is created.
+pub const ARTICLE_COMMENTS_TITLE: &str = "Article Comments";
+const ARTICLE_COMMENTS_DESCRIPTION: &str = "Discussion of the articles.";
+
 #[derive(Debug, Clone, Identifiable, Queryable, TypeName)]
 #[primary_key(uuid)]
 #[table_name = "forums"]
@@ -16,6 +27,10 @@ pub struct Forum {
     pub title: String,
     /// The description that informs users what topics should be discussed in the forum.
     pub description: String,
+    /// Forums are listed in ascending order of their position.
+    pub position: i32,
+    /// Whether the forum holds the comment threads of articles.
+    pub article_comments: bool,
 }
 
 #[derive(Insertable, Debug)]
@@ -25,17 +40,107 @@ pub struct NewForum {
     pub description: String,
 }
 
+#[derive(AsChangeset, Debug)]
+#[table_name = "forums"]
+pub struct ForumChangeset {
+    pub title: String,
+    pub description: String,
+}
+
 impl Forum {
     pub fn get_forum(uuid: ForumUuid,conn: &PgConnection) -> BackendResult<Forum> {
         get_row::<Forum,_>(schema::forums::table, uuid.0, conn)
     }
+    /// Gets all of the forums in the order set by the admins.
     pub fn get_forums(conn: &PgConnection) -> BackendResult<Vec<Forum>> {
-        get_rows::<Forum,_>(schema::forums::table, conn)
+        use crate::schema::forums::dsl::*;
+
+        forums
+            .order((position, title))
+            .load::<Forum>(conn)
+            .map_err(handle_err::<Forum>)
     }
     pub fn delete_forum(uuid: ForumUuid, conn: &PgConnection) -> BackendResult<Forum> {
         delete_row::<Forum,_>(schema::forums::table, uuid.0, conn)
     }
+
+    /// Deletes the forum, as long as it has no threads, archived or otherwise.
+    /// If forced, the forum is deleted along with all of its threads.
+    pub fn delete_forum_checked(uuid: ForumUuid, force: bool, conn: &PgConnection) -> BackendResult<Forum> {
+        use crate::schema::threads;
+
+        Forum::get_forum(uuid, conn)?;
+        if !force {
+            let thread_count: i64 = threads::table
+                .filter(threads::forum_uuid.eq(uuid.0))
+                .count()
+                .get_result(conn)
+                .map_err(handle_err::<Forum>)?;
+            if thread_count > 0 {
+                return Err(Error::BadRequest)
+            }
+        }
+        Forum::delete_forum(uuid, conn)
+    }
+
+    /// Changes the title and description of the forum.
+    pub fn update_forum(uuid: ForumUuid, changeset: ForumChangeset, conn: &PgConnection) -> BackendResult<Forum> {
+        use crate::schema::forums;
+
+        diesel::update(forums::table.find(uuid.0))
+            .set(&changeset)
+            .get_result(conn)
+            .map_err(handle_err::<Forum>)
+    }
     pub fn create_forum(new: NewForum, conn: &PgConnection) -> BackendResult<Forum> {
         create_row::<Forum, NewForum,_>(schema::forums::table, new, conn)
     }
+
+    /// Gets the forum that holds the comment threads of articles, creating it if it doesn't exist yet.
+    pub fn get_article_comments_forum(conn: &PgConnection) -> BackendResult<Forum> {
+        use crate::schema::forums::dsl::*;
+
+        let existing: Option<Forum> = forums
+            .filter(article_comments.eq(true))
+            .first::<Forum>(conn)
+            .optional()
+            .map_err(handle_err::<Forum>)?;
+        if let Some(forum) = existing {
+            return Ok(forum)
+        }
+
+        // If the forum was created concurrently, the unique index keeps this one from being inserted.
+        diesel::insert_into(forums)
+            .values((
+                title.eq(ARTICLE_COMMENTS_TITLE),
+                description.eq(ARTICLE_COMMENTS_DESCRIPTION),
+                article_comments.eq(true),
+            ))
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .map_err(handle_err::<Forum>)?;
+        forums
+            .filter(article_comments.eq(true))
+            .first::<Forum>(conn)
+            .map_err(handle_err::<Forum>)
+    }
+
+    /// Sets the position of each of the provided forums to its index in the list.
+    ///
+    /// Forums that are left out of the list keep their current position.
+    pub fn reorder_forums(forum_uuids: Vec<ForumUuid>, conn: &PgConnection) -> BackendResult<Vec<Forum>> {
+        use crate::schema::forums::dsl::*;
+
+        conn.transaction::<_, DieselError, _>(|| {
+            for (index, forum_uuid) in forum_uuids.into_iter().enumerate() {
+                diesel::update(forums.find(forum_uuid.0))
+                    .set(position.eq(index as i32))
+                    .get_result::<Forum>(conn)?;
+            }
+            Ok(())
+        })
+            .map_err(handle_err::<Forum>)?;
+
+        Forum::get_forums(conn)
+    }
 }
\ No newline at end of file
@@ -3,14 +3,26 @@
 pub mod auth;
 pub mod user;
 pub mod article;
+pub mod article_revision;
+pub mod article_author;
+pub mod article_review;
+pub mod article_view;
+pub mod tag;
 pub mod forum;
 pub mod thread;
+pub mod thread_read;
 pub mod post;
+pub mod reaction;
 pub mod bucket;
 pub mod question;
 pub mod answer;
 pub mod chat;
 pub mod message;
+pub mod notification;
+pub mod search;
+pub mod subscription;
+pub mod poll;
+pub mod upload;
 
 
 
@@ -0,0 +1,333 @@
+use crate::schema::notifications;
+use chrono::NaiveDateTime;
+use crate::user::User;
+use crate::thread::Thread;
+use crate::post::Post;
+use crate::subscription::Subscription;
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::PgConnection;
+use error::{BackendResult, Error};
+use uuid::Uuid;
+use identifiers::notification::NotificationUuid;
+use identifiers::post::PostUuid;
+use identifiers::user::UserUuid;
+use identifiers::thread::ThreadUuid;
+use wire::notification::NotificationKind;
+use crate::calls::prelude::*;
+use crate::schema;
+use typename::TypeName;
+
+#[derive(Debug, Clone, Identifiable, Associations, Queryable, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(User, foreign_key = "actor_uuid")]
+#[belongs_to(Thread, foreign_key = "thread_uuid")]
+#[belongs_to(Post, foreign_key = "post_uuid")]
+#[table_name = "notifications"]
+pub struct Notification {
+    /// Primary Key
+    pub uuid: Uuid,
+    /// Foreign Key of the user being notified.
+    pub user_uuid: Uuid,
+    /// Foreign Key of the user who wrote the post.
+    pub actor_uuid: Uuid,
+    /// Why the user is being notified, stored as the integer representation of a `NotificationKind`.
+    pub kind: i32,
+    /// Foreign Key of the post the user is being notified about.
+    pub post_uuid: Uuid,
+    /// Foreign Key of the thread containing the post.
+    pub thread_uuid: Uuid,
+    pub created_date: NaiveDateTime,
+    /// Whether the user has seen the notification.
+    pub read: bool,
+    /// When the notification was sent to the user as part of a digest.
+    pub digested_date: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "notifications"]
+pub struct NewNotification {
+    pub user_uuid: Uuid,
+    pub actor_uuid: Uuid,
+    pub kind: i32,
+    pub post_uuid: Uuid,
+    pub thread_uuid: Uuid,
+    pub created_date: NaiveDateTime,
+}
+
+pub struct NotificationData {
+    pub notification: Notification,
+    pub actor: User,
+    pub thread: Thread,
+    /// The post that caused the notification.
+    pub post: Post,
+}
+
+/// The notifications a user hasn't seen yet, to be delivered together.
+pub struct Digest {
+    pub user: User,
+    pub notifications: Vec<NotificationData>,
+}
+
+/// Finds the user names mentioned in the content, in the order they first appear.
+///
+/// A mention is an `@` followed by the user name,
+/// as long as the `@` isn't part of a preceding word, like it would be in an email address.
+pub fn parse_mentions(content: &str) -> Vec<String> {
+    fn is_user_name_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_' || c == '-'
+    }
+
+    let mut mentions: Vec<String> = vec![];
+    let mut previous: Option<char> = None;
+    let mut chars = content.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let starts_mention = c == '@' && !previous.map(is_user_name_char).unwrap_or(false);
+        previous = Some(c);
+        if !starts_mention {
+            continue;
+        }
+        let start = index + c.len_utf8();
+        let mut end = start;
+        while let Some(&(next_index, next)) = chars.peek() {
+            if !is_user_name_char(next) {
+                break;
+            }
+            end = next_index + next.len_utf8();
+            previous = Some(next);
+            chars.next();
+        }
+        let user_name = &content[start..end];
+        if !user_name.is_empty() && !mentions.iter().any(|x| x == user_name) {
+            mentions.push(user_name.to_string());
+        }
+    }
+    mentions
+}
+
+impl Notification {
+
+    /// Notifies the author of the post's parent of the reply, anyone mentioned in the post,
+    /// and anyone subscribed to the post's thread or forum.
+    ///
+    /// Users are never notified about their own posts,
+    /// and each user is only notified once per post, preferring replies over mentions over subscriptions.
+    pub fn notify_for_new_post(post: &Post, conn: &PgConnection) -> BackendResult<Vec<Notification>> {
+        let mut new_notifications: Vec<NewNotification> = vec![];
+
+        let mut reply_recipient: Option<Uuid> = None;
+        if let Some(parent_uuid) = post.parent_uuid {
+            let parent: Post = Post::get_post(PostUuid(parent_uuid), conn)?;
+            if parent.author_uuid != post.author_uuid {
+                reply_recipient = Some(parent.author_uuid);
+                new_notifications.push(NewNotification::for_post(post, parent.author_uuid, NotificationKind::Reply));
+            }
+        }
+
+        let mentioned: Vec<Uuid> = Notification::get_mentioned_users(post, conn)?;
+        new_notifications.extend(
+            mentioned
+                .iter()
+                .cloned()
+                .filter(|user_uuid| Some(*user_uuid) != reply_recipient)
+                .map(|user_uuid| NewNotification::for_post(post, user_uuid, NotificationKind::Mention))
+        );
+
+        let thread: Thread = Thread::get_thread(ThreadUuid(post.thread_uuid), conn)?;
+        let subscribers: Vec<Uuid> = Subscription::get_subscribers(&thread, conn)?;
+        new_notifications.extend(
+            subscribers
+                .into_iter()
+                .filter(|user_uuid| {
+                    *user_uuid != post.author_uuid
+                        && Some(*user_uuid) != reply_recipient
+                        && !mentioned.contains(user_uuid)
+                })
+                .map(|user_uuid| NewNotification::for_post(post, user_uuid, NotificationKind::Subscription))
+        );
+
+        Notification::create_notifications(new_notifications, conn)
+    }
+
+    /// Notifies anyone newly mentioned in an edited post.
+    /// Users who were already notified about the post aren't notified again.
+    pub fn notify_for_edited_post(post: &Post, conn: &PgConnection) -> BackendResult<Vec<Notification>> {
+        use crate::schema::notifications::dsl::*;
+
+        let already_notified: Vec<Uuid> = notifications
+            .filter(post_uuid.eq(post.uuid))
+            .select(user_uuid)
+            .load::<Uuid>(conn)
+            .map_err(handle_err::<Notification>)?;
+
+        let new_notifications: Vec<NewNotification> = Notification::get_mentioned_users(post, conn)?
+            .into_iter()
+            .filter(|mentioned_uuid| !already_notified.contains(mentioned_uuid))
+            .map(|mentioned_uuid| NewNotification::for_post(post, mentioned_uuid, NotificationKind::Mention))
+            .collect();
+
+        Notification::create_notifications(new_notifications, conn)
+    }
+
+    /// Gets the users mentioned in the post, other than its author.
+    fn get_mentioned_users(post: &Post, conn: &PgConnection) -> BackendResult<Vec<Uuid>> {
+        use crate::schema::users::dsl::*;
+
+        let mentions: Vec<String> = parse_mentions(&post.content);
+        if mentions.is_empty() {
+            return Ok(vec![])
+        }
+
+        let mentioned: Vec<Uuid> = users
+            .filter(user_name.eq_any(mentions))
+            .select(uuid)
+            .load::<Uuid>(conn)
+            .map_err(handle_err::<User>)?;
+
+        Ok(mentioned
+            .into_iter()
+            .filter(|mentioned_uuid| *mentioned_uuid != post.author_uuid)
+            .collect())
+    }
+
+    fn create_notifications(new_notifications: Vec<NewNotification>, conn: &PgConnection) -> BackendResult<Vec<Notification>> {
+        if new_notifications.is_empty() {
+            return Ok(vec![])
+        }
+        diesel::insert_into(notifications::table)
+            .values(&new_notifications)
+            .on_conflict_do_nothing()
+            .get_results(conn)
+            .map_err(handle_err::<Notification>)
+    }
+
+    /// Gets the user's most recent notifications, newest first.
+    pub fn get_notifications_for_user(user_uuid: UserUuid, limit: i64, conn: &PgConnection) -> BackendResult<Vec<NotificationData>> {
+        use crate::schema::notifications as notifications_table;
+        use crate::schema::users;
+        use crate::schema::threads;
+        use crate::schema::posts;
+
+        let notifications: Vec<(Notification, User, Thread, Post)> = notifications_table::table
+            .inner_join(users::table)
+            .inner_join(threads::table)
+            .inner_join(posts::table)
+            .filter(notifications_table::user_uuid.eq(user_uuid.0))
+            .order(notifications_table::created_date.desc())
+            .limit(limit)
+            .load::<(Notification, User, Thread, Post)>(conn)
+            .map_err(handle_err::<Notification>)?;
+
+        let notifications = notifications
+            .into_iter()
+            .map(|(notification, actor, thread, post)| NotificationData { notification, actor, thread, post })
+            .collect();
+        Ok(notifications)
+    }
+
+    /// Marks the notification as read.
+    /// Users can only mark their own notifications.
+    pub fn mark_read(notification_uuid: NotificationUuid, requesting_user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<NotificationData> {
+        use crate::schema::notifications::dsl::*;
+
+        let notification: Notification = get_row::<Notification, _>(schema::notifications::table, notification_uuid.0, conn)?;
+        if notification.user_uuid != requesting_user_uuid.0 {
+            return Err(Error::NotAuthorized { reason: "Notification belongs to another user." })
+        }
+
+        let notification: Notification = diesel::update(notifications.find(notification_uuid.0))
+            .set(read.eq(true))
+            .get_result(conn)
+            .map_err(handle_err::<Notification>)?;
+
+        let actor: User = User::get_user(UserUuid(notification.actor_uuid), conn)?;
+        let thread: Thread = Thread::get_thread(ThreadUuid(notification.thread_uuid), conn)?;
+        let post: Post = Post::get_post(PostUuid(notification.post_uuid), conn)?;
+        Ok(NotificationData { notification, actor, thread, post })
+    }
+
+    /// Gathers the unread notifications created since the provided date that haven't been digested yet,
+    /// grouped by the user they are for.
+    pub fn get_digests(since: NaiveDateTime, conn: &PgConnection) -> BackendResult<Vec<Digest>> {
+        use crate::schema::notifications as notifications_table;
+        use crate::schema::users;
+        use crate::schema::threads;
+        use crate::schema::posts;
+
+        let notifications: Vec<(Notification, User, Thread, Post)> = notifications_table::table
+            .inner_join(users::table)
+            .inner_join(threads::table)
+            .inner_join(posts::table)
+            .filter(notifications_table::read.eq(false))
+            .filter(notifications_table::digested_date.is_null())
+            .filter(notifications_table::created_date.ge(since))
+            .order((notifications_table::user_uuid, notifications_table::created_date))
+            .load::<(Notification, User, Thread, Post)>(conn)
+            .map_err(handle_err::<Notification>)?;
+
+        let mut grouped: Vec<(Uuid, Vec<NotificationData>)> = vec![];
+        for (notification, actor, thread, post) in notifications {
+            let recipient_uuid = notification.user_uuid;
+            let data = NotificationData { notification, actor, thread, post };
+            match grouped.last_mut() {
+                Some((user_uuid, ref mut group)) if *user_uuid == recipient_uuid => group.push(data),
+                _ => grouped.push((recipient_uuid, vec![data])),
+            }
+        }
+
+        let recipient_uuids: Vec<Uuid> = grouped.iter().map(|(user_uuid, _)| *user_uuid).collect();
+        let recipients: Vec<User> = schema::users::table
+            .filter(schema::users::uuid.eq_any(recipient_uuids))
+            .load(conn)
+            .map_err(handle_err::<User>)?;
+
+        grouped
+            .into_iter()
+            .map(|(user_uuid, notifications)| {
+                let user: User = recipients
+                    .iter()
+                    .find(|user| user.uuid == user_uuid)
+                    .cloned()
+                    .ok_or(Error::NotFound { type_name: User::type_name() })?;
+                Ok(Digest { user, notifications })
+            })
+            .collect()
+    }
+
+    /// Records that the notifications were delivered in a digest, so they aren't included in another one.
+    pub fn mark_digested(notification_uuids: Vec<Uuid>, date: NaiveDateTime, conn: &PgConnection) -> BackendResult<()> {
+        use crate::schema::notifications::dsl::*;
+
+        diesel::update(notifications.filter(uuid.eq_any(notification_uuids)))
+            .set(digested_date.eq(date))
+            .execute(conn)
+            .map_err(handle_err::<Notification>)?;
+        Ok(())
+    }
+
+    /// Marks all of the user's notifications as read.
+    pub fn mark_all_read(requesting_user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<()> {
+        use crate::schema::notifications::dsl::*;
+
+        diesel::update(notifications.filter(user_uuid.eq(requesting_user_uuid.0)).filter(read.eq(false)))
+            .set(read.eq(true))
+            .execute(conn)
+            .map_err(handle_err::<Notification>)?;
+        Ok(())
+    }
+}
+
+impl NewNotification {
+    fn for_post(post: &Post, recipient_uuid: Uuid, kind: NotificationKind) -> NewNotification {
+        NewNotification {
+            user_uuid: recipient_uuid,
+            actor_uuid: post.author_uuid,
+            kind: kind.into(),
+            post_uuid: post.uuid,
+            thread_uuid: post.thread_uuid,
+            created_date: post.modified_date.unwrap_or(post.created_date),
+        }
+    }
+}
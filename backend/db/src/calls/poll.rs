@@ -0,0 +1,341 @@
+use crate::schema::polls;
+use crate::schema::poll_options;
+use crate::schema::poll_votes;
+use chrono::NaiveDateTime;
+use chrono::Utc;
+use crate::thread::Thread;
+use crate::user::User;
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::OptionalExtension;
+use diesel::Connection;
+use diesel::PgConnection;
+use diesel::result::Error as DieselError;
+use error::{BackendResult, Error};
+use uuid::Uuid;
+use identifiers::poll::PollUuid;
+use identifiers::poll_option::PollOptionUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::user::UserUuid;
+use crate::calls::prelude::*;
+use crate::schema;
+
+#[derive(Debug, Clone, Identifiable, Associations, Queryable, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(Thread, foreign_key = "thread_uuid")]
+#[table_name = "polls"]
+pub struct Poll {
+    /// Primary Key
+    pub uuid: Uuid,
+    /// Foreign Key of the thread the poll is attached to.
+    pub thread_uuid: Uuid,
+    pub question: String,
+    /// Whether users can vote for more than one option.
+    pub multiple_choice: bool,
+    /// Votes are no longer accepted after this date.
+    pub close_date: Option<NaiveDateTime>,
+    /// If set, nobody can see the results until the poll closes.
+    /// Otherwise, users can see the results once they have voted.
+    pub hide_results_until_closed: bool,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "polls"]
+pub struct NewPoll {
+    pub thread_uuid: Uuid,
+    pub question: String,
+    pub multiple_choice: bool,
+    pub close_date: Option<NaiveDateTime>,
+    pub hide_results_until_closed: bool,
+}
+
+#[derive(Debug, Clone, Identifiable, Associations, Queryable, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(Poll, foreign_key = "poll_uuid")]
+#[table_name = "poll_options"]
+pub struct PollOption {
+    /// Primary Key
+    pub uuid: Uuid,
+    /// Foreign Key of the poll the option belongs to.
+    pub poll_uuid: Uuid,
+    /// Options are listed in ascending order of their position.
+    pub position: i32,
+    pub content: String,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "poll_options"]
+pub struct NewPollOption {
+    pub poll_uuid: Uuid,
+    pub position: i32,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Identifiable, Associations, Queryable, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(Poll, foreign_key = "poll_uuid")]
+#[belongs_to(PollOption, foreign_key = "option_uuid")]
+#[belongs_to(User, foreign_key = "user_uuid")]
+#[table_name = "poll_votes"]
+pub struct PollVote {
+    /// Primary Key
+    pub uuid: Uuid,
+    pub poll_uuid: Uuid,
+    pub option_uuid: Uuid,
+    pub user_uuid: Uuid,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "poll_votes"]
+pub struct NewPollVote {
+    pub poll_uuid: Uuid,
+    pub option_uuid: Uuid,
+    pub user_uuid: Uuid,
+}
+
+/// Everything needed to attach a poll to a thread.
+#[derive(Debug, Clone)]
+pub struct NewPollData {
+    pub question: String,
+    /// The options, in the order they should be listed.
+    pub options: Vec<String>,
+    pub multiple_choice: bool,
+    pub close_date: Option<NaiveDateTime>,
+    pub hide_results_until_closed: bool,
+}
+
+pub struct PollOptionData {
+    pub option: PollOption,
+    /// The number of votes for the option, if the results are visible to the user.
+    pub votes: Option<i64>,
+    /// Whether the requesting user voted for the option.
+    pub voted: bool,
+}
+
+pub struct PollData {
+    pub poll: Poll,
+    pub options: Vec<PollOptionData>,
+    pub closed: bool,
+    pub results_visible: bool,
+}
+
+impl NewPollData {
+    /// Polls need a question and at least two options, none of which may be blank.
+    pub fn is_valid(&self) -> bool {
+        !self.question.trim().is_empty()
+            && self.options.len() >= 2
+            && self.options.iter().all(|option| !option.trim().is_empty())
+    }
+}
+
+impl Poll {
+    pub fn get_poll(uuid: PollUuid, conn: &PgConnection) -> BackendResult<Poll> {
+        get_row::<Poll,_>(schema::polls::table, uuid.0, conn)
+    }
+
+    /// Whether the poll has stopped accepting votes.
+    pub fn is_closed(&self) -> bool {
+        self.close_date
+            .map(|close_date| close_date <= Utc::now().naive_utc())
+            .unwrap_or(false)
+    }
+
+    /// Attaches a poll to the thread.
+    pub fn create_poll(thread_uuid: ThreadUuid, new_poll: NewPollData, conn: &PgConnection) -> BackendResult<PollData> {
+        if !new_poll.is_valid() {
+            return Err(Error::BadRequest)
+        }
+
+        let poll: Poll = conn.transaction::<_, DieselError, _>(|| {
+            let poll: Poll = diesel::insert_into(polls::table)
+                .values(&NewPoll {
+                    thread_uuid: thread_uuid.0,
+                    question: new_poll.question,
+                    multiple_choice: new_poll.multiple_choice,
+                    close_date: new_poll.close_date,
+                    hide_results_until_closed: new_poll.hide_results_until_closed,
+                })
+                .get_result(conn)?;
+
+            let new_options: Vec<NewPollOption> = new_poll.options
+                .into_iter()
+                .enumerate()
+                .map(|(index, content)| NewPollOption {
+                    poll_uuid: poll.uuid,
+                    position: index as i32,
+                    content,
+                })
+                .collect();
+            diesel::insert_into(poll_options::table)
+                .values(&new_options)
+                .execute(conn)?;
+            Ok(poll)
+        })
+            .map_err(handle_err::<Poll>)?;
+
+        Poll::get_poll_data(poll, None, conn)
+    }
+
+    /// Gets the poll attached to the thread, if there is one.
+    pub fn get_poll_for_thread(thread_uuid: ThreadUuid, user_uuid: Option<UserUuid>, conn: &PgConnection) -> BackendResult<Option<PollData>> {
+        use crate::schema::polls::dsl;
+
+        let poll: Option<Poll> = dsl::polls
+            .filter(dsl::thread_uuid.eq(thread_uuid.0))
+            .first(conn)
+            .optional()
+            .map_err(handle_err::<Poll>)?;
+
+        match poll {
+            Some(poll) => Poll::get_poll_data(poll, user_uuid, conn).map(Some),
+            None => Ok(None)
+        }
+    }
+
+    /// Gets the options of the poll, with their vote counts if the user is allowed to see them.
+    ///
+    /// Results are visible to everyone once the poll closes.
+    /// Before then, they are only visible to users who have voted, unless they are hidden until the poll closes.
+    pub fn get_poll_data(poll: Poll, user_uuid: Option<UserUuid>, conn: &PgConnection) -> BackendResult<PollData> {
+        use crate::schema::poll_options::dsl as options_dsl;
+        use crate::schema::poll_votes::dsl as votes_dsl;
+
+        let options: Vec<PollOption> = options_dsl::poll_options
+            .filter(options_dsl::poll_uuid.eq(poll.uuid))
+            .order(options_dsl::position.asc())
+            .load(conn)
+            .map_err(handle_err::<PollOption>)?;
+
+        let votes: Vec<PollVote> = votes_dsl::poll_votes
+            .filter(votes_dsl::poll_uuid.eq(poll.uuid))
+            .load(conn)
+            .map_err(handle_err::<PollVote>)?;
+
+        let voted_options: Vec<Uuid> = votes
+            .iter()
+            .filter(|vote| Some(vote.user_uuid) == user_uuid.map(|x| x.0))
+            .map(|vote| vote.option_uuid)
+            .collect();
+
+        let closed: bool = poll.is_closed();
+        let results_visible: bool = closed || (!poll.hide_results_until_closed && !voted_options.is_empty());
+
+        let options: Vec<PollOptionData> = options
+            .into_iter()
+            .map(|option| {
+                let vote_count = votes.iter().filter(|vote| vote.option_uuid == option.uuid).count() as i64;
+                PollOptionData {
+                    votes: if results_visible { Some(vote_count) } else { None },
+                    voted: voted_options.contains(&option.uuid),
+                    option,
+                }
+            })
+            .collect();
+
+        Ok(PollData {
+            poll,
+            options,
+            closed,
+            results_visible,
+        })
+    }
+
+    /// Votes for the options, replacing any votes the user previously made in the poll.
+    ///
+    /// Single choice polls only accept one option,
+    /// and votes can't be made once the poll has closed or its thread is locked or archived.
+    pub fn vote(poll_uuid: PollUuid, user_uuid: UserUuid, option_uuids: Vec<PollOptionUuid>, conn: &PgConnection) -> BackendResult<PollData> {
+        use crate::schema::poll_options::dsl as options_dsl;
+        use crate::schema::poll_votes::dsl as votes_dsl;
+
+        let mut option_uuids: Vec<Uuid> = option_uuids.into_iter().map(|x| x.0).collect();
+        option_uuids.sort();
+        option_uuids.dedup();
+
+        let poll: Poll = conn.transaction::<_, Error, _>(|| {
+            let poll: Poll = Poll::lock_open_poll(poll_uuid, conn)?;
+            if option_uuids.is_empty() || (!poll.multiple_choice && option_uuids.len() > 1) {
+                return Err(Error::BadRequest)
+            }
+
+            let poll_option_uuids: Vec<Uuid> = options_dsl::poll_options
+                .filter(options_dsl::poll_uuid.eq(poll.uuid))
+                .select(options_dsl::uuid)
+                .load(conn)
+                .map_err(handle_err::<PollOption>)?;
+            if option_uuids.iter().any(|option_uuid| !poll_option_uuids.contains(option_uuid)) {
+                return Err(Error::BadRequest)
+            }
+
+            let new_votes: Vec<NewPollVote> = option_uuids
+                .iter()
+                .map(|option_uuid| NewPollVote {
+                    poll_uuid: poll.uuid,
+                    option_uuid: *option_uuid,
+                    user_uuid: user_uuid.0,
+                })
+                .collect();
+
+            diesel::delete(
+                votes_dsl::poll_votes
+                    .filter(votes_dsl::poll_uuid.eq(poll.uuid))
+                    .filter(votes_dsl::user_uuid.eq(user_uuid.0))
+            )
+                .execute(conn)
+                .map_err(handle_err::<PollVote>)?;
+            diesel::insert_into(poll_votes::table)
+                .values(&new_votes)
+                .execute(conn)
+                .map_err(handle_err::<PollVote>)?;
+            Ok(poll)
+        })?;
+
+        Poll::get_poll_data(poll, Some(user_uuid), conn)
+    }
+
+    /// Removes all of the user's votes in the poll.
+    pub fn retract_vote(poll_uuid: PollUuid, user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<PollData> {
+        use crate::schema::poll_votes::dsl as votes_dsl;
+
+        let poll: Poll = conn.transaction::<_, Error, _>(|| {
+            let poll: Poll = Poll::lock_open_poll(poll_uuid, conn)?;
+            diesel::delete(
+                votes_dsl::poll_votes
+                    .filter(votes_dsl::poll_uuid.eq(poll.uuid))
+                    .filter(votes_dsl::user_uuid.eq(user_uuid.0))
+            )
+                .execute(conn)
+                .map_err(handle_err::<PollVote>)?;
+            Ok(poll)
+        })?;
+
+        Poll::get_poll_data(poll, Some(user_uuid), conn)
+    }
+
+    /// Locks the poll for the rest of the transaction, making sure it is open.
+    ///
+    /// Votes can only be changed while the poll is open and its thread can be modified.
+    /// The thread is share locked, so it can't be locked or archived until the transaction ends,
+    /// and locking the poll makes a user's concurrent votes happen one after the other.
+    fn lock_open_poll(poll_uuid: PollUuid, conn: &PgConnection) -> BackendResult<Poll> {
+        let poll: Poll = polls::table
+            .find(poll_uuid.0)
+            .for_update()
+            .first(conn)
+            .map_err(handle_err::<Poll>)?;
+        if poll.is_closed() {
+            return Err(Error::BadRequest)
+        }
+        let thread: Thread = schema::threads::table
+            .find(poll.thread_uuid)
+            .for_share()
+            .first(conn)
+            .map_err(handle_err::<Thread>)?;
+        if thread.locked || thread.archived {
+            return Err(Error::ThreadImmutable)
+        }
+        Ok(poll)
+    }
+}
@@ -1,9 +1,10 @@
 use crate::schema::posts;
-use crate::schema::post_upvotes;
-use crate::schema::post_downvotes;
+use crate::schema::post_censor_actions;
 use chrono::NaiveDateTime;
 use crate::user::User;
 use crate::thread::Thread;
+use crate::notification::Notification;
+use crate::reaction::{PostReaction, ReactionCounts, UP, DOWN};
 use error::*;
 use diesel;
 use diesel::RunQueryDsl;
@@ -13,6 +14,8 @@ use diesel::QueryDsl;
 use error::BackendResult;
 use diesel::SaveChangesDsl;
 use diesel::PgConnection;
+use diesel::Connection;
+use diesel::result::Error as DieselError;
 use identifiers::post::PostUuid;
 use identifiers::thread::ThreadUuid;
 use identifiers::user::UserUuid;
@@ -24,6 +27,47 @@ use crate::schema;
 
 use std::collections::HashMap;
 
+/// Casts a vote, replacing the user's opposing vote in the same statement.
+/// The partial unique index on votes resolves concurrent requests to a single vote,
+/// and casting the vote the user already has leaves the row untouched.
+///
+/// The post author's reputation is adjusted by the change in score:
+/// a new vote counts once, while flipping an existing vote counts twice.
+/// A row that was inserted, rather than updated, has no `xmax`.
+const UPSERT_VOTE_QUERY: &str = "
+    WITH vote AS (
+        INSERT INTO post_reactions (post_uuid, user_uuid, reaction)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (post_uuid, user_uuid) WHERE reaction IN ('up', 'down')
+        DO UPDATE SET reaction = EXCLUDED.reaction, created_date = now()
+        WHERE post_reactions.reaction <> EXCLUDED.reaction
+        RETURNING (xmax = 0) AS inserted
+    )
+    UPDATE users
+    SET reputation = reputation
+        + (CASE WHEN vote.inserted THEN 1 ELSE 2 END) * (CASE WHEN $3 = 'up' THEN 1 ELSE -1 END)
+    FROM vote, posts
+    WHERE posts.uuid = $1
+        AND users.uuid = posts.author_uuid
+";
+
+/// Removes the user's vote if it is one of the given reactions,
+/// taking its score back off of the post author's reputation.
+const REMOVE_VOTE_QUERY: &str = "
+    WITH removed AS (
+        DELETE FROM post_reactions
+        WHERE post_uuid = $1
+            AND user_uuid = $2
+            AND reaction = ANY($3)
+        RETURNING reaction
+    )
+    UPDATE users
+    SET reputation = reputation - (CASE WHEN removed.reaction = 'up' THEN 1 ELSE -1 END)
+    FROM removed, posts
+    WHERE posts.uuid = $1
+        AND users.uuid = posts.author_uuid
+";
+
 #[derive(Debug, Clone, PartialEq, Identifiable, Associations, Queryable, TypeName)]
 #[primary_key(uuid)]
 #[belongs_to(User, foreign_key = "author_uuid")]
@@ -47,6 +91,12 @@ pub struct Post {
     pub content: String,
     /// If the post has been censored, it will not be immediately viewable by people viewing the thread.
     pub censored: bool,
+    /// The reason given by the moderator that most recently censored or uncensored the post.
+    pub censor_reason: Option<String>,
+    /// The moderator that most recently censored or uncensored the post.
+    pub censor_changed_by: Option<Uuid>,
+    /// When the censor state of the post was most recently changed.
+    pub censor_changed_date: Option<NaiveDateTime>,
 }
 
 
@@ -75,63 +125,49 @@ pub struct EditPostChangeset {
 pub struct PostData {
     pub post: Post,
     pub user: User,
-    pub votes: VoteCounts,
+    pub reactions: ReactionCounts,
     pub children: Vec<PostData>,
 }
 
-#[derive(Debug, Clone)]
-pub struct ChildlessPostData {
-    pub post: Post,
-    pub user: User,
-    pub votes: VoteCounts
-}
-
-
-
-
-
-
-#[derive(Debug, Clone, PartialEq, Identifiable, Associations, Queryable)]
+/// A record of a moderator censoring or uncensoring a post.
+#[derive(Debug, Clone, Identifiable, Queryable, Associations, TypeName, PartialEq)]
 #[primary_key(uuid)]
-#[belongs_to(User, foreign_key = "user_uuid")]
 #[belongs_to(Post, foreign_key = "post_uuid")]
-#[table_name = "post_upvotes"]
-struct PostUpvote {
-    /// Primary Key
+#[table_name = "post_censor_actions"]
+pub struct PostCensorAction {
     pub uuid: Uuid,
-    /// Foreign Key
     pub post_uuid: Uuid,
-    /// Foreign Key
-    pub user_uuid: Uuid
+    /// The moderator that made the change.
+    pub moderator_uuid: Option<Uuid>,
+    /// Whether the post was censored or uncensored.
+    pub censored: bool,
+    pub reason: String,
+    /// The content of the post at the time of the change.
+    pub content: String,
+    pub created_date: NaiveDateTime,
 }
 
 #[derive(Insertable, Debug, Clone)]
-#[table_name = "post_upvotes"]
-struct NewUpvote {
-    pub post_uuid: Uuid,
-    pub user_uuid: Uuid
+#[table_name = "post_censor_actions"]
+struct NewPostCensorAction {
+    post_uuid: Uuid,
+    moderator_uuid: Option<Uuid>,
+    censored: bool,
+    reason: String,
+    content: String,
+    created_date: NaiveDateTime,
 }
 
-#[derive(Debug, Clone, PartialEq, Identifiable, Associations, Queryable)]
-#[primary_key(uuid)]
-#[belongs_to(User, foreign_key = "user_uuid")]
-#[belongs_to(Post, foreign_key = "post_uuid")]
-#[table_name = "post_downvotes"]
-struct PostDownvote {
-    /// Primary Key
-    pub uuid: Uuid,
-    /// Foreign Key
-    pub post_uuid: Uuid,
-    /// Foreign Key
-    pub user_uuid: Uuid
+#[derive(Debug, Clone)]
+pub struct ChildlessPostData {
+    pub post: Post,
+    pub user: User,
+    pub reactions: ReactionCounts
 }
 
-#[derive(Insertable, Debug, Clone)]
-#[table_name = "post_downvotes"]
-struct NewDownvote {
-    pub post_uuid: Uuid,
-    pub user_uuid: Uuid
-}
+
+
+
 
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -145,27 +181,17 @@ pub enum PostVote {
     Down(Vote)
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct VoteCounts {
-    pub up: i64,
-    pub down: i64,
-    pub user_voted_up: bool,
-    pub user_voted_down: bool
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VoteDirection {
+    Up,
+    Down
 }
 
-impl From<Vote> for NewDownvote {
-    fn from(f: Vote) -> Self {
-        NewDownvote {
-            post_uuid: f.post_uuid.0,
-            user_uuid: f.user_uuid.0
-        }
-    }
-}
-impl From<Vote> for NewUpvote {
-    fn from(f: Vote) -> Self {
-        NewUpvote {
-            post_uuid: f.post_uuid.0,
-            user_uuid: f.user_uuid.0
+impl VoteDirection {
+    fn reaction(self) -> &'static str {
+        match self {
+            VoteDirection::Up => UP,
+            VoteDirection::Down => DOWN
         }
     }
 }
@@ -189,7 +215,7 @@ impl From<ChildlessPostData> for PostData {
         PostData {
             post: childless.post,
             user: childless.user,
-            votes: childless.votes,
+            reactions: childless.reactions,
             children: vec![],
         }
     }
@@ -223,15 +249,16 @@ impl Post {
         let modified_post: Post = edit_post_changeset
             .save_changes(conn)
             .map_err(handle_err::<Post>)?;
+        Notification::notify_for_edited_post(&modified_post, conn)?;
 
-        let votes: VoteCounts = Post::get_vote_counts(&modified_post, user_uuid, conn)?;
+        let reactions: ReactionCounts = Post::get_reaction_counts(&modified_post, Some(user_uuid), conn)?;
 
         let author_uuid = UserUuid(modified_post.author_uuid);
         let user = User::get_user(author_uuid, conn)?;
         Ok(ChildlessPostData {
             post: modified_post,
             user,
-            votes
+            reactions
         })
     }
 
@@ -256,41 +283,84 @@ impl Post {
         };
 
         let post: Post = Post::create_post(new_post, conn)?;
+        Thread::record_post(&post, conn)?;
+        Notification::notify_for_new_post(&post, conn)?;
         let author_uuid = UserUuid(post.author_uuid);
         let user: User = User::get_user(author_uuid, conn)?;
         let user_uuid = UserUuid(post.author_uuid);
-        let votes: VoteCounts = Post::get_vote_counts(&post, user_uuid, conn)?;
+        let reactions: ReactionCounts = Post::get_reaction_counts(&post, Some(user_uuid), conn)?;
         let post_data = ChildlessPostData {
             post,
             user,
-            votes
+            reactions
         };
         Ok(post_data)
     }
 
     /// Censors the post, preventing users from seeing it by default.
-    pub fn censor_post(post_uuid: PostUuid, conn: &PgConnection) -> BackendResult<ChildlessPostData> {
+    /// The moderator performing the action and their reason are recorded with the post.
+    pub fn censor_post(post_uuid: PostUuid, reason: String, moderator_uuid: UserUuid, conn: &PgConnection) -> BackendResult<ChildlessPostData> {
+        Post::set_censor_status(post_uuid, true, reason, moderator_uuid, conn)
+    }
+
+    /// Reverses the censoring of a post, allowing users to see it again.
+    /// The moderator performing the action and their reason are recorded with the post.
+    pub fn uncensor_post(post_uuid: PostUuid, reason: String, moderator_uuid: UserUuid, conn: &PgConnection) -> BackendResult<ChildlessPostData> {
+        Post::set_censor_status(post_uuid, false, reason, moderator_uuid, conn)
+    }
+
+    /// Sets the censored flag for the post, and records who changed it, when, and why.
+    ///
+    /// The change is also added to the post's censor history, along with the post's content at the time.
+    fn set_censor_status(post_uuid: PostUuid, is_censored: bool, reason: String, moderator_uuid: UserUuid, conn: &PgConnection) -> BackendResult<ChildlessPostData> {
         use crate::schema::posts::dsl::*;
         use crate::schema::posts;
 
         let m_post_uuid: Uuid = post_uuid.0;
-
-        let censored_post: Post = diesel::update(posts::table)
-            .filter(posts::uuid.eq(m_post_uuid))
-            .set(censored.eq(true))
-            .get_result(conn)
+        let now: NaiveDateTime = Utc::now().naive_utc();
+
+        let changed_post: Post = conn.transaction::<_, DieselError, _>(|| {
+            let changed_post: Post = diesel::update(posts::table)
+                .filter(posts::uuid.eq(m_post_uuid))
+                .set((
+                    censored.eq(is_censored),
+                    censor_reason.eq(Some(reason.clone())),
+                    censor_changed_by.eq(Some(moderator_uuid.0)),
+                    censor_changed_date.eq(Some(now))
+                ))
+                .get_result(conn)?;
+            let action = NewPostCensorAction {
+                post_uuid: changed_post.uuid,
+                moderator_uuid: Some(moderator_uuid.0),
+                censored: is_censored,
+                reason,
+                content: changed_post.content.clone(),
+                created_date: now,
+            };
+            diesel::insert_into(post_censor_actions::table)
+                .values(&action)
+                .execute(conn)?;
+            Ok(changed_post)
+        })
             .map_err(handle_err::<Post>)?;
-        let author_uuid_a = UserUuid(censored_post.author_uuid);
+        let author_uuid_a = UserUuid(changed_post.author_uuid);
         let user = User::get_user(author_uuid_a, conn)?;
-        let user_uuid = UserUuid(user.uuid);
-        let votes = Post::get_vote_counts(&censored_post, user_uuid,conn)?;
+        let reactions = Post::get_reaction_counts(&changed_post, Some(moderator_uuid), conn)?;
 
         Ok(ChildlessPostData {
-            post: censored_post,
+            post: changed_post,
             user,
-            votes
+            reactions
         })
+    }
 
+    /// Gets every time the post was censored or uncensored, oldest first.
+    pub fn get_censor_history(post_uuid: PostUuid, conn: &PgConnection) -> BackendResult<Vec<PostCensorAction>> {
+        post_censor_actions::table
+            .filter(post_censor_actions::post_uuid.eq(post_uuid.0))
+            .order(post_censor_actions::created_date)
+            .load::<PostCensorAction>(conn)
+            .map_err(handle_err::<PostCensorAction>)
     }
 
     /// Gets all of the posts associated with a given user.
@@ -303,18 +373,18 @@ impl Post {
             .load::<Post>(conn)
             .map_err(handle_err::<Post>)?;
 
-        let votes = Post::get_votes_for_posts(&user_posts, Some(user_uuid), conn)?;
-        let posts_and_votes: Vec<(Post, VoteCounts)> = user_posts.into_iter().zip(votes.into_iter()).collect();
+        let reactions = Post::get_reactions_for_posts(&user_posts, Some(user_uuid), conn)?;
+        let posts_and_reactions: Vec<(Post, ReactionCounts)> = user_posts.into_iter().zip(reactions.into_iter()).collect();
 
         return Ok(
-            posts_and_votes
+            posts_and_reactions
                 .into_iter()
-                .map(|p_and_c: (Post, VoteCounts)| {
-                    let (post, votes) = p_and_c;
+                .map(|p_and_c: (Post, ReactionCounts)| {
+                    let (post, reactions) = p_and_c;
                     ChildlessPostData {
                         post,
                         user: user.clone(),
-                        votes
+                        reactions
                     }
                 })
                 .collect(),
@@ -340,6 +410,17 @@ impl Post {
             .map_err(handle_err::<User>)
     }
 
+    /// Gets the root posts of the threads, regardless of whether the threads are locked.
+    pub fn get_root_posts(thread_uuids: Vec<Uuid>, conn: &PgConnection) -> BackendResult<Vec<Post>> {
+        use crate::schema::posts::dsl::*;
+
+        posts
+            .filter(thread_uuid.eq_any(thread_uuids))
+            .filter(parent_uuid.is_null())
+            .load::<Post>(conn)
+            .map_err(handle_err::<Post>)
+    }
+
     /// Gets the first post associated with a thread.
     /// This post is identifed by it not having a parent id.
     /// All posts in a given thread that aren't root posts will have non-null parent ids.
@@ -363,12 +444,42 @@ impl Post {
             .map_err(handle_err::<Post>)
     }
 
+    /// Gets the uuids of the post and every post that replies to it, directly or indirectly.
+    pub fn get_descendant_uuids(post: &Post, conn: &PgConnection) -> BackendResult<Vec<Uuid>> {
+        Post::load_descendant_uuids(post, conn)
+            .map_err(handle_err::<Post>)
+    }
+
+    /// Gets the descendants like `get_descendant_uuids`, for use within a transaction.
+    pub(crate) fn load_descendant_uuids(post: &Post, conn: &PgConnection) -> Result<Vec<Uuid>, DieselError> {
+        use crate::schema::posts::dsl::*;
+
+        let thread_posts: Vec<(Uuid, Option<Uuid>)> = posts
+            .filter(thread_uuid.eq(post.thread_uuid))
+            .select((uuid, parent_uuid))
+            .load(conn)?;
+
+        let mut descendants: Vec<Uuid> = vec![post.uuid];
+        let mut index = 0;
+        while index < descendants.len() {
+            let current: Uuid = descendants[index];
+            descendants.extend(
+                thread_posts
+                    .iter()
+                    .filter(|(_, parent)| *parent == Some(current))
+                    .map(|(child, _)| *child)
+            );
+            index += 1;
+        }
+        Ok(descendants)
+    }
+
     pub fn get_individual_post(post_uuid: PostUuid, user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<ChildlessPostData> {
         let post = Post::get_post(post_uuid, conn)?;
         let author_uuid = UserUuid(post.author_uuid);
         let user = User::get_user(author_uuid, conn)?;
-        let votes = Post::get_vote_counts(&post, user_uuid,conn)?;
-        Ok(ChildlessPostData { post, user, votes })
+        let reactions = Post::get_reaction_counts(&post, Some(user_uuid), conn)?;
+        Ok(ChildlessPostData { post, user, reactions })
     }
 
 
@@ -389,10 +500,10 @@ impl Post {
         }
         // We now know that there is at least one post.
 
-        let votes = Post::get_votes_for_posts(&posts, user_uuid, conn)?;
-        let mut posts_and_votes: Vec<(Post, VoteCounts)> = posts.into_iter().zip(votes.into_iter()).collect();
+        let reactions = Post::get_reactions_for_posts(&posts, user_uuid, conn)?;
+        let mut posts_and_reactions: Vec<(Post, ReactionCounts)> = posts.into_iter().zip(reactions.into_iter()).collect();
 
-        let mut user_uuids: Vec<Uuid> = posts_and_votes.iter()
+        let mut user_uuids: Vec<Uuid> = posts_and_reactions.iter()
             .map(|post| post.0.author_uuid)
             .collect();
 
@@ -410,9 +521,9 @@ impl Post {
         let users: HashMap<Uuid, User> = users.into_iter().map(|u| (u.uuid, u)).collect();
 
         // Remove the root from the list.
-        let root: Vec<(Post, VoteCounts)> = posts_and_votes
-            .drain_filter(|post_and_votes| {
-                let post: &Post = &post_and_votes.0;
+        let root: Vec<(Post, ReactionCounts)> = posts_and_reactions
+            .drain_filter(|post_and_reactions| {
+                let post: &Post = &post_and_reactions.0;
                 if let None = post.parent_uuid {
                     true
                 } else {
@@ -423,46 +534,50 @@ impl Post {
         // We are making the assumption that there is at least one post that meets the root criteria.
         // Practically speaking, there should be exactly one, but we rely on reasonable insertions
         // and modifications to enforce that.
-        let root: (Post, VoteCounts) = root.into_iter().next().ok_or(Error::InternalServerError)?;
+        let root: (Post, ReactionCounts) = root.into_iter().next().ok_or(Error::InternalServerError)?;
 
 
         /// Recursive function to assemble posts out of the list of post data.
         /// It has a time complexity of O: n * log_n, but that is still better than talking to the database,
         /// as the constant time is too great there.
-        fn assemble_posts (post_and_votes: (Post, VoteCounts), posts_and_votes: &mut Vec<(Post, VoteCounts)>, users: &HashMap<Uuid, User>) -> PostData {
+        fn assemble_posts (post_and_reactions: (Post, ReactionCounts), posts_and_reactions: &mut Vec<(Post, ReactionCounts)>, users: &HashMap<Uuid, User>) -> PostData {
 
-            let children: Vec<(Post, VoteCounts)> = posts_and_votes
-                .drain_filter(|child_post_and_votes: &mut (Post, VoteCounts)| {
-                    let child_post: &Post = &child_post_and_votes.0;
+            let children: Vec<(Post, ReactionCounts)> = posts_and_reactions
+                .drain_filter(|child_post_and_reactions: &mut (Post, ReactionCounts)| {
+                    let child_post: &Post = &child_post_and_reactions.0;
                     if let Some(parent_uuid) = child_post.parent_uuid {
-                        parent_uuid == post_and_votes.0.uuid
+                        parent_uuid == post_and_reactions.0.uuid
                     } else {
                         false
                     }
                 })
                 .collect();
 
+            // Replies with the best score come first, with ties going to the oldest reply.
+            let mut children = children;
+            children.sort_by_key(|child: &(Post, ReactionCounts)| (-child.1.score(), child.0.created_date));
+
             // Recurse
-            let children: Vec<PostData> = children.into_iter().map(|child_post: (Post, VoteCounts)| {
-                assemble_posts(child_post, posts_and_votes, users)
+            let children: Vec<PostData> = children.into_iter().map(|child_post: (Post, ReactionCounts)| {
+                assemble_posts(child_post, posts_and_reactions, users)
             }).collect();
 
             let user: User = users
-                .get(&post_and_votes.0.author_uuid)
+                .get(&post_and_reactions.0.author_uuid)
                 .cloned()
                 .expect("The user at the uuid should exist");
 
-            let (post, votes) = post_and_votes;
+            let (post, reactions) = post_and_reactions;
 
             PostData {
                 post,
                 user,
-                votes,
+                reactions,
                 children,
             }
         }
 
-        Ok(assemble_posts(root, &mut posts_and_votes, &users))
+        Ok(assemble_posts(root, &mut posts_and_reactions, &users))
     }
 
     /// Gets the post at the given UUID and all of its children.
@@ -496,231 +611,63 @@ impl Post {
 
     }
 
-    /// Add a vote record to a post.
+    /// Up or down votes a post, replacing an opposing vote.
+    /// Casting the same vote twice is rejected.
     pub fn vote(vote: PostVote, conn: &PgConnection) -> BackendResult<()> {
-        use crate::schema::post_upvotes;
-        use crate::schema::post_downvotes;
-        use diesel::dsl::exists;
-        use diesel::select;
-
-        let v: &Vote = match vote {
-            PostVote::Up(ref vote) => vote,
-            PostVote::Down(ref vote) => vote
+        let (vote, direction) = match vote {
+            PostVote::Up(vote) => (vote, VoteDirection::Up),
+            PostVote::Down(vote) => (vote, VoteDirection::Down)
         };
+        if Post::upsert_vote(vote, direction, conn)? {
+            Ok(())
+        } else {
+            Err(Error::BadRequest)
+        }
+    }
 
-        let upvote_exists: bool = select(
-            exists(
-                post_upvotes::table
-                    .filter(post_upvotes::user_uuid.eq(v.user_uuid.0))
-                    .filter(post_upvotes::post_uuid.eq(v.post_uuid.0))
-                )
-            )
-            .get_result(conn)
-            .map_err(handle_err::<Post>)?;
-
-
-        let downvote_exists: bool = select(
-            exists(
-                post_downvotes::table
-                    .filter(post_downvotes::user_uuid.eq(v.user_uuid.0))
-                    .filter(post_downvotes::post_uuid.eq(v.post_uuid.0))
-                )
-            )
-            .get_result(conn)
-            .map_err(handle_err::<Post>)?;
-
-        match vote {
-            PostVote::Up(vote) => {
-                if downvote_exists {
-                    Post::remove_downvote(vote.user_uuid, vote.post_uuid, conn)?
-                }
-                if !upvote_exists {
-                    // Insert the new upvote
-                    let upvote: NewUpvote = vote.into();
-                    return diesel::insert_into(post_upvotes::table)
-                        .values(upvote)
-                        .execute(conn)
-                        .map_err(handle_err::<Post>)
-                        .map(|_| ())
-                } else {
-                    return Err(Error::BadRequest)
-                }
-            },
-            PostVote::Down(vote) => {
-                if upvote_exists {
-                    Post::remove_upvote(vote.user_uuid, vote.post_uuid, conn)?
-                }
-                if !downvote_exists {
-                    // Add the new downvote
-                    let downvote: NewDownvote = vote.into();
-                    return diesel::insert_into(post_downvotes::table)
-                        .values(downvote)
-                        .execute(conn)
-                        .map_err(handle_err::<Post>)
-                        .map(|_| ());
-                } else {
-                    return  Err(Error::BadRequest)
-                }
+    /// Sets the user's vote on the post, removing it if no direction is given.
+    /// Unlike `vote`, setting the vote the user already has is not an error.
+    pub fn change_vote(vote: Vote, direction: Option<VoteDirection>, conn: &PgConnection) -> BackendResult<ReactionCounts> {
+        let post: Post = Post::get_post(vote.post_uuid, conn)?;
+        match direction {
+            Some(direction) => {
+                Post::upsert_vote(vote, direction, conn)?;
             }
-        };
-
+            None => Post::revoke_vote(vote.user_uuid, vote.post_uuid, conn)?
+        }
+        Post::get_reaction_counts(&post, Some(vote.user_uuid), conn)
     }
 
-    /// Removes an upvote from a post.
-    fn remove_upvote(user_uuid: UserUuid, post_uuid: PostUuid, conn: &PgConnection) -> BackendResult<()> {
-        use crate::schema::post_upvotes;
+    /// Casts the vote, returning false if the user had already cast it.
+    fn upsert_vote(vote: Vote, direction: VoteDirection, conn: &PgConnection) -> BackendResult<bool> {
+        use diesel::sql_types::Uuid as SqlUuid;
+        use diesel::sql_types::Text;
 
-        let target = post_upvotes::table
-            .filter(post_upvotes::user_uuid.eq(user_uuid.0))
-            .filter(post_upvotes::post_uuid.eq(post_uuid.0));
-        diesel::delete(target)
+        diesel::sql_query(UPSERT_VOTE_QUERY)
+            .bind::<SqlUuid, _>(vote.post_uuid.0)
+            .bind::<SqlUuid, _>(vote.user_uuid.0)
+            .bind::<Text, _>(direction.reaction())
             .execute(conn)
-            .map_err(handle_err::<Post>)
-            .map(|_| ())
-    }
-    /// Removes a downvote from a post.
-    fn remove_downvote(user_uuid: UserUuid, post_uuid: PostUuid, conn: &PgConnection) -> BackendResult<()> {
-        use crate::schema::post_downvotes;
-
-        let target = post_downvotes::table
-            .filter(post_downvotes::user_uuid.eq(user_uuid.0))
-            .filter(post_downvotes::post_uuid.eq(post_uuid.0));
-        diesel::delete(target)
-            .execute(conn)
-            .map_err(handle_err::<Post>)
-            .map(|_| ())
+            .map(|rows| rows > 0)
+            .map_err(handle_err::<PostReaction>)
     }
 
-
     /// Remove any vote for the post
     pub fn revoke_vote(user_uuid: UserUuid, post_uuid: PostUuid, conn: &PgConnection) -> BackendResult<()> {
-        let x = Post::remove_upvote(user_uuid, post_uuid, conn);
-        let y = Post::remove_downvote(user_uuid, post_uuid, conn);
-
-        x?;
-        y
-    }
-
-    /// Gets the vote counts for a single post.
-    pub fn get_vote_counts(post: &Post, user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<VoteCounts> {
-        use crate::schema::post_upvotes;
-        use crate::schema::post_downvotes;
-        use diesel::dsl::exists;
-        use diesel::select;
-
-        let up: i64 = PostUpvote::belonging_to(post)
-            .count()
-            .get_result(conn)
-            .map_err(handle_err::<Post>)?;
-        let down: i64 = PostDownvote::belonging_to(post)
-            .count()
-            .get_result(conn)
-            .map_err(handle_err::<Post>)?;
-
-
-        let user_voted_up: bool = select(
-            exists(
-                post_upvotes::table
-                    .filter(post_upvotes::user_uuid.eq(user_uuid.0))
-                    .filter(post_upvotes::post_uuid.eq(post.uuid))
-                )
-            )
-            .get_result(conn)
-            .map_err(handle_err::<Post>)?;
-
-        let user_voted_down: bool = select(
-            exists(
-                post_downvotes::table
-                    .filter(post_downvotes::user_uuid.eq(user_uuid.0))
-                    .filter(post_downvotes::post_uuid.eq(post.uuid))
-                )
-            )
-            .get_result(conn)
-            .map_err(handle_err::<Post>)?;
-
-        let counts: VoteCounts = VoteCounts {
-            up,
-            down,
-            user_voted_up,
-            user_voted_down
-        };
-        Ok(counts)
+        Post::remove_vote(user_uuid, post_uuid, &[UP, DOWN], conn)
     }
 
-    // TODO make this take a reference to a vector of posts, then only return the votes, not a tuple.
-    /// Given a vector of posts, make a request to get the vote counts for each and associate the counts with the posts.
-    pub fn get_votes_for_posts(posts: &Vec<Post>, user_uuid: Option<UserUuid>, conn: &PgConnection) -> BackendResult<Vec<VoteCounts>> {
-        use diesel::GroupedBy;
-
-        match user_uuid {
-            Some(user_uuid) => {
-                let up_counts: Vec<(i64, bool)> = PostUpvote::belonging_to(posts)
-                    .load(conn)
-                    .map_err(handle_err::<Post>)?
-                    .grouped_by(&posts)
-                    .into_iter()
-                    .map(|l: Vec<PostUpvote>| {
-                        let user_voted_up: bool = l.iter().any(|x| x.user_uuid == user_uuid.0 );
-                        let count = l.len() as i64;
-                        (count, user_voted_up)
-                    })
-                    .collect();
-                let down_counts: Vec<(i64, bool)> = PostDownvote::belonging_to(posts)
-                    .load(conn)
-                    .map_err(handle_err::<Post>)?
-                    .grouped_by(&posts)
-                    .into_iter()
-                    .map(|l: Vec<PostDownvote>| {
-                        let user_voted_up: bool = l.iter().any(|x| x.user_uuid == user_uuid.0 );
-                        let count = l.len() as i64;
-                        (count, user_voted_up)
-                    })
-                    .collect();
-
-                let counts: Vec<VoteCounts> = up_counts
-                    .into_iter()
-                    .zip(down_counts.into_iter())
-                    .map(|x: ((i64, bool), (i64, bool))| VoteCounts {
-                        up: (x.0).0,
-                        down: (x.1).0,
-                        user_voted_up: (x.0).1,
-                        user_voted_down: (x.1).1
-                    })
-                    .collect();
-                Ok(counts)
-            }
-            None => {
-                let up_counts: Vec<i64> = PostUpvote::belonging_to(posts)
-                    .load(conn)
-                    .map_err(handle_err::<Post>)?
-                    .grouped_by(&posts)
-                    .into_iter()
-                    .map(|l: Vec<PostUpvote>| l.len() as i64)
-                    .collect();
-                let down_counts: Vec<i64> = PostDownvote::belonging_to(posts)
-                    .load(conn)
-                    .map_err(handle_err::<Post>)?
-                    .grouped_by(&posts)
-                    .into_iter()
-                    .map(|l: Vec<PostDownvote>| l.len() as i64)
-                    .collect();
-                let counts: Vec<VoteCounts> = up_counts
-                    .into_iter()
-                    .zip(down_counts.into_iter())
-                    .map(|x: (i64, i64)| VoteCounts {
-                        up: x.0,
-                        down: x.1,
-                        user_voted_up: false,
-                        user_voted_down: false
-                    })
-                    .collect();
-                Ok(counts)
-            }
-        }
-
+    /// Removes the user's vote, if it is one of the given directions.
+    pub(crate) fn remove_vote(user_uuid: UserUuid, post_uuid: PostUuid, reactions: &[&str], conn: &PgConnection) -> BackendResult<()> {
+        use diesel::sql_types::Uuid as SqlUuid;
+        use diesel::sql_types::{Array, Text};
 
-//        let posts_and_vote_counts: Vec<(Post, VoteCounts)> = posts.into_iter().zip(counts.into_iter()).collect();
+        diesel::sql_query(REMOVE_VOTE_QUERY)
+            .bind::<SqlUuid, _>(post_uuid.0)
+            .bind::<SqlUuid, _>(user_uuid.0)
+            .bind::<Array<Text>, _>(reactions)
+            .execute(conn)
+            .map_err(handle_err::<PostReaction>)
+            .map(|_| ())
     }
-
-
 }
@@ -0,0 +1,238 @@
+use crate::schema::post_reactions;
+use chrono::NaiveDateTime;
+use crate::user::User;
+use crate::post::{Post, PostVote, Vote};
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::ExpressionMethods;
+use diesel::BelongingToDsl;
+use diesel::QueryDsl;
+use diesel::PgConnection;
+use error::{BackendResult, Error};
+use identifiers::post::PostUuid;
+use identifiers::user::UserUuid;
+use uuid::Uuid;
+use crate::calls::prelude::*;
+
+/// The reaction used for up votes.
+pub const UP: &str = "up";
+/// The reaction used for down votes.
+pub const DOWN: &str = "down";
+
+/// A single reaction left on a post by a user.
+#[derive(Debug, Clone, PartialEq, Identifiable, Associations, Queryable, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(User, foreign_key = "user_uuid")]
+#[belongs_to(Post, foreign_key = "post_uuid")]
+#[table_name = "post_reactions"]
+pub struct PostReaction {
+    /// Primary Key
+    pub uuid: Uuid,
+    /// Foreign Key
+    pub post_uuid: Uuid,
+    /// Foreign Key
+    pub user_uuid: Uuid,
+    pub reaction: String,
+    pub created_date: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "post_reactions"]
+pub struct NewPostReaction {
+    pub post_uuid: Uuid,
+    pub user_uuid: Uuid,
+    pub reaction: String,
+}
+
+/// The reactions users are allowed to leave on posts.
+/// Up and down votes are always allowed, as scores are derived from them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReactionSet(Vec<String>);
+
+impl Default for ReactionSet {
+    fn default() -> Self {
+        ReactionSet::new(
+            vec!["heart", "laugh", "hooray", "confused"]
+                .into_iter()
+                .map(String::from)
+                .collect()
+        )
+    }
+}
+
+impl ReactionSet {
+    /// Creates a set made up of up and down votes, as well as the provided reactions.
+    pub fn new(reactions: Vec<String>) -> ReactionSet {
+        let mut set: Vec<String> = vec![UP.to_string(), DOWN.to_string()];
+        for reaction in reactions {
+            let reaction = reaction.trim().to_lowercase();
+            if !reaction.is_empty() && !set.contains(&reaction) {
+                set.push(reaction);
+            }
+        }
+        ReactionSet(set)
+    }
+
+    pub fn contains(&self, reaction: &str) -> bool {
+        self.0.iter().any(|x| x == reaction)
+    }
+
+    pub fn reactions(&self) -> &[String] {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReactionCount {
+    pub reaction: String,
+    pub count: i64,
+    /// Whether the requesting user left this reaction.
+    pub user_reacted: bool,
+}
+
+/// The aggregated reactions for a post.
+/// Only reactions that have been left at least once are included.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ReactionCounts {
+    pub reactions: Vec<ReactionCount>,
+}
+
+impl ReactionCounts {
+    /// Aggregates the reactions for a single post.
+    fn from_reactions(reactions: &[PostReaction], user_uuid: Option<UserUuid>) -> ReactionCounts {
+        let mut counts: Vec<ReactionCount> = vec![];
+        for reaction in reactions {
+            let user_reacted: bool = Some(reaction.user_uuid) == user_uuid.map(|x| x.0);
+            if let Some(count) = counts.iter_mut().find(|x| x.reaction == reaction.reaction) {
+                count.count += 1;
+                count.user_reacted |= user_reacted;
+                continue;
+            }
+            counts.push(ReactionCount {
+                reaction: reaction.reaction.clone(),
+                count: 1,
+                user_reacted,
+            });
+        }
+        // Up and down votes come first, followed by the most popular reactions.
+        counts.sort_by_key(|x| (x.reaction != UP, x.reaction != DOWN, -x.count, x.reaction.clone()));
+        ReactionCounts { reactions: counts }
+    }
+
+    pub fn count(&self, reaction: &str) -> i64 {
+        self.reactions
+            .iter()
+            .find(|x| x.reaction == reaction)
+            .map(|x| x.count)
+            .unwrap_or(0)
+    }
+
+    pub fn user_reacted(&self, reaction: &str) -> bool {
+        self.reactions
+            .iter()
+            .any(|x| x.reaction == reaction && x.user_reacted)
+    }
+
+    pub fn up(&self) -> i64 {
+        self.count(UP)
+    }
+
+    pub fn down(&self) -> i64 {
+        self.count(DOWN)
+    }
+
+    pub fn user_voted_up(&self) -> bool {
+        self.user_reacted(UP)
+    }
+
+    pub fn user_voted_down(&self) -> bool {
+        self.user_reacted(DOWN)
+    }
+
+    /// The score used when ranking posts: up votes less down votes.
+    pub fn score(&self) -> i64 {
+        self.up() - self.down()
+    }
+}
+
+impl Post {
+    /// Leaves a reaction on a post.
+    /// The reaction must be part of the set, and users can only leave each reaction once.
+    /// Up and down votes replace each other.
+    pub fn react(post_uuid: PostUuid, user_uuid: UserUuid, reaction: String, set: &ReactionSet, conn: &PgConnection) -> BackendResult<ReactionCounts> {
+        if !set.contains(&reaction) {
+            return Err(Error::BadRequest)
+        }
+        let post: Post = Post::get_post(post_uuid, conn)?;
+        let vote = Vote { post_uuid, user_uuid };
+        match reaction.as_str() {
+            UP => Post::vote(PostVote::Up(vote), conn)?,
+            DOWN => Post::vote(PostVote::Down(vote), conn)?,
+            _ => Post::add_reaction(post_uuid, user_uuid, reaction, conn)?
+        }
+        Post::get_reaction_counts(&post, Some(user_uuid), conn)
+    }
+
+    /// Removes the user's reaction from a post.
+    pub fn unreact(post_uuid: PostUuid, user_uuid: UserUuid, reaction: String, conn: &PgConnection) -> BackendResult<ReactionCounts> {
+        let post: Post = Post::get_post(post_uuid, conn)?;
+        match reaction.as_str() {
+            UP | DOWN => Post::remove_vote(user_uuid, post_uuid, &[reaction.as_str()], conn)?,
+            _ => Post::remove_reaction(post_uuid, user_uuid, &reaction, conn)?
+        }
+        Post::get_reaction_counts(&post, Some(user_uuid), conn)
+    }
+
+    /// Adds the reaction, failing if the user already left it.
+    fn add_reaction(post_uuid: PostUuid, user_uuid: UserUuid, reaction: String, conn: &PgConnection) -> BackendResult<()> {
+        let new_reaction = NewPostReaction {
+            post_uuid: post_uuid.0,
+            user_uuid: user_uuid.0,
+            reaction,
+        };
+        let inserted: usize = diesel::insert_into(post_reactions::table)
+            .values(&new_reaction)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .map_err(handle_err::<PostReaction>)?;
+        if inserted == 0 {
+            return Err(Error::BadRequest)
+        }
+        Ok(())
+    }
+
+    fn remove_reaction(post_uuid: PostUuid, user_uuid: UserUuid, reaction: &str, conn: &PgConnection) -> BackendResult<()> {
+        use crate::schema::post_reactions::dsl;
+
+        let target = dsl::post_reactions
+            .filter(dsl::post_uuid.eq(post_uuid.0))
+            .filter(dsl::user_uuid.eq(user_uuid.0))
+            .filter(dsl::reaction.eq(reaction));
+        diesel::delete(target)
+            .execute(conn)
+            .map_err(handle_err::<PostReaction>)
+            .map(|_| ())
+    }
+
+    /// Gets the reaction counts for a single post.
+    pub fn get_reaction_counts(post: &Post, user_uuid: Option<UserUuid>, conn: &PgConnection) -> BackendResult<ReactionCounts> {
+        let reactions: Vec<PostReaction> = PostReaction::belonging_to(post)
+            .load(conn)
+            .map_err(handle_err::<PostReaction>)?;
+        Ok(ReactionCounts::from_reactions(&reactions, user_uuid))
+    }
+
+    /// Gets the reaction counts for each of the posts, in the same order as the posts.
+    pub fn get_reactions_for_posts(posts: &[Post], user_uuid: Option<UserUuid>, conn: &PgConnection) -> BackendResult<Vec<ReactionCounts>> {
+        use diesel::GroupedBy;
+
+        let counts: Vec<ReactionCounts> = PostReaction::belonging_to(posts)
+            .load(conn)
+            .map_err(handle_err::<PostReaction>)?
+            .grouped_by(posts)
+            .into_iter()
+            .map(|reactions: Vec<PostReaction>| ReactionCounts::from_reactions(&reactions, user_uuid))
+            .collect();
+        Ok(counts)
+    }
+}
@@ -0,0 +1,130 @@
+//! Full-text search over the forums.
+//!
+//! Diesel has no support for `tsvector`s, so the search itself is written as a raw query
+//! against the `search_vector` columns added to the `threads` and `posts` tables.
+
+use crate::user::User;
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::PgConnection;
+use diesel::sql_types::{BigInt, Float, Nullable, Text, Timestamp};
+use diesel::sql_types::Uuid as SqlUuid;
+use error::{BackendResult, Error};
+use uuid::Uuid;
+use identifiers::forum::ForumUuid;
+use identifiers::user::UserUuid;
+use crate::calls::prelude::*;
+use crate::schema;
+use typename::TypeName;
+
+/// Parameters that narrow down a forum search.
+#[derive(Debug, Clone)]
+pub struct ForumSearch {
+    /// Search terms, interpreted with `websearch_to_tsquery`.
+    pub query: String,
+    pub forum_uuid: Option<ForumUuid>,
+    pub author_uuid: Option<UserUuid>,
+    pub before: Option<NaiveDateTime>,
+    pub after: Option<NaiveDateTime>,
+}
+
+/// A post that matched a search, along with the thread it belongs to.
+#[derive(Debug, Clone, QueryableByName, TypeName)]
+pub struct SearchHit {
+    #[sql_type = "SqlUuid"]
+    pub post_uuid: Uuid,
+    #[sql_type = "SqlUuid"]
+    pub thread_uuid: Uuid,
+    #[sql_type = "SqlUuid"]
+    pub forum_uuid: Uuid,
+    #[sql_type = "SqlUuid"]
+    pub author_uuid: Uuid,
+    /// The title of the thread, with matching terms highlighted.
+    #[sql_type = "Text"]
+    pub title: String,
+    /// An excerpt of the post's content, with matching terms highlighted.
+    #[sql_type = "Text"]
+    pub snippet: String,
+    #[sql_type = "Timestamp"]
+    pub created_date: NaiveDateTime,
+    #[sql_type = "Float"]
+    pub rank: f32,
+}
+
+pub struct SearchHitData {
+    pub hit: SearchHit,
+    pub user: User,
+}
+
+/// Matches posts by their content, and the root post of each thread by the thread's title as well.
+/// Posts in archived threads and censored posts are never returned.
+const SEARCH_QUERY: &str = "
+    SELECT
+        p.uuid AS post_uuid,
+        t.uuid AS thread_uuid,
+        t.forum_uuid AS forum_uuid,
+        p.author_uuid AS author_uuid,
+        ts_headline('english', t.title, q, 'StartSel=<mark>, StopSel=</mark>, HighlightAll=true') AS title,
+        ts_headline('english', p.content, q, 'StartSel=<mark>, StopSel=</mark>, MaxWords=35, MinWords=15') AS snippet,
+        p.created_date AS created_date,
+        ts_rank(p.search_vector, q)
+            + CASE WHEN p.parent_uuid IS NULL THEN ts_rank(t.search_vector, q) ELSE 0 END AS rank
+    FROM posts p
+    INNER JOIN threads t ON p.thread_uuid = t.uuid,
+    websearch_to_tsquery('english', $1) q
+    WHERE (p.search_vector @@ q OR (p.parent_uuid IS NULL AND t.search_vector @@ q))
+        AND NOT t.archived
+        AND NOT p.censored
+        AND ($2::UUID IS NULL OR t.forum_uuid = $2)
+        AND ($3::UUID IS NULL OR p.author_uuid = $3)
+        AND ($4::TIMESTAMP IS NULL OR p.created_date < $4)
+        AND ($5::TIMESTAMP IS NULL OR p.created_date > $5)
+    ORDER BY rank DESC, p.created_date DESC
+    LIMIT $6 OFFSET $7
+";
+
+impl ForumSearch {
+    /// Gets a page of posts matching the search, best matches first.
+    ///
+    /// Pages start at 1, in keeping with the other paginated calls.
+    pub fn search(self, page_index: i32, page_size: i32, conn: &PgConnection) -> BackendResult<Vec<SearchHitData>> {
+        use crate::schema::users::dsl::*;
+
+        if self.query.trim().is_empty() || page_index < 1 || page_size < 1 {
+            return Err(Error::BadRequest)
+        }
+        let offset: i64 = i64::from(page_index - 1) * i64::from(page_size);
+
+        let hits: Vec<SearchHit> = diesel::sql_query(SEARCH_QUERY)
+            .bind::<Text, _>(self.query)
+            .bind::<Nullable<SqlUuid>, _>(self.forum_uuid.map(|x| x.0))
+            .bind::<Nullable<SqlUuid>, _>(self.author_uuid.map(|x| x.0))
+            .bind::<Nullable<Timestamp>, _>(self.before)
+            .bind::<Nullable<Timestamp>, _>(self.after)
+            .bind::<BigInt, _>(i64::from(page_size))
+            .bind::<BigInt, _>(offset)
+            .load(conn)
+            .map_err(handle_err::<SearchHit>)?;
+
+        let author_uuids: Vec<Uuid> = hits.iter().map(|hit| hit.author_uuid).collect();
+        let authors: Vec<User> = schema::users::table
+            .filter(uuid.eq_any(author_uuids))
+            .load(conn)
+            .map_err(handle_err::<User>)?;
+
+        hits
+            .into_iter()
+            .map(|hit| {
+                let user: User = authors
+                    .iter()
+                    .find(|user| user.uuid == hit.author_uuid)
+                    .cloned()
+                    .ok_or(Error::NotFound { type_name: User::type_name() })?;
+                Ok(SearchHitData { hit, user })
+            })
+            .collect()
+    }
+}
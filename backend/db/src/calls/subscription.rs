@@ -0,0 +1,154 @@
+use crate::schema::subscriptions;
+use chrono::NaiveDateTime;
+use chrono::Utc;
+use crate::user::User;
+use crate::thread::Thread;
+use crate::forum::Forum;
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::BoolExpressionMethods;
+use diesel::PgConnection;
+use error::BackendResult;
+use uuid::Uuid;
+use identifiers::forum::ForumUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::user::UserUuid;
+use crate::calls::prelude::*;
+
+/// A user following either a single thread, or every thread in a forum.
+#[derive(Debug, Clone, Identifiable, Associations, Queryable, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(User, foreign_key = "user_uuid")]
+#[belongs_to(Thread, foreign_key = "thread_uuid")]
+#[belongs_to(Forum, foreign_key = "forum_uuid")]
+#[table_name = "subscriptions"]
+pub struct Subscription {
+    /// Primary Key
+    pub uuid: Uuid,
+    /// Foreign Key of the subscribed user.
+    pub user_uuid: Uuid,
+    /// Foreign Key of the followed thread, if the subscription is to a thread.
+    pub thread_uuid: Option<Uuid>,
+    /// Foreign Key of the followed forum, if the subscription is to a forum.
+    pub forum_uuid: Option<Uuid>,
+    pub created_date: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "subscriptions"]
+pub struct NewSubscription {
+    pub user_uuid: Uuid,
+    pub thread_uuid: Option<Uuid>,
+    pub forum_uuid: Option<Uuid>,
+    pub created_date: NaiveDateTime,
+}
+
+impl Subscription {
+    /// Subscribes the user to the thread.
+    /// Subscribing to a thread the user already follows returns the existing subscription.
+    pub fn subscribe_to_thread(user_uuid: UserUuid, thread_uuid: ThreadUuid, conn: &PgConnection) -> BackendResult<Subscription> {
+        use crate::schema::subscriptions::dsl;
+
+        // Make sure the thread exists, so a missing thread is reported as such.
+        Thread::get_thread(thread_uuid, conn)?;
+
+        let new_subscription = NewSubscription {
+            user_uuid: user_uuid.0,
+            thread_uuid: Some(thread_uuid.0),
+            forum_uuid: None,
+            created_date: Utc::now().naive_utc(),
+        };
+        Subscription::create_subscription(new_subscription, conn)?;
+
+        dsl::subscriptions
+            .filter(dsl::user_uuid.eq(user_uuid.0))
+            .filter(dsl::thread_uuid.eq(thread_uuid.0))
+            .first(conn)
+            .map_err(handle_err::<Subscription>)
+    }
+
+    /// Subscribes the user to every thread in the forum.
+    /// Subscribing to a forum the user already follows returns the existing subscription.
+    pub fn subscribe_to_forum(user_uuid: UserUuid, forum_uuid: ForumUuid, conn: &PgConnection) -> BackendResult<Subscription> {
+        use crate::schema::subscriptions::dsl;
+
+        Forum::get_forum(forum_uuid, conn)?;
+
+        let new_subscription = NewSubscription {
+            user_uuid: user_uuid.0,
+            thread_uuid: None,
+            forum_uuid: Some(forum_uuid.0),
+            created_date: Utc::now().naive_utc(),
+        };
+        Subscription::create_subscription(new_subscription, conn)?;
+
+        dsl::subscriptions
+            .filter(dsl::user_uuid.eq(user_uuid.0))
+            .filter(dsl::forum_uuid.eq(forum_uuid.0))
+            .first(conn)
+            .map_err(handle_err::<Subscription>)
+    }
+
+    fn create_subscription(new_subscription: NewSubscription, conn: &PgConnection) -> BackendResult<()> {
+        diesel::insert_into(subscriptions::table)
+            .values(&new_subscription)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .map_err(handle_err::<Subscription>)?;
+        Ok(())
+    }
+
+    /// Removes the user's subscription to the thread.
+    pub fn unsubscribe_from_thread(user_uuid: UserUuid, thread_uuid: ThreadUuid, conn: &PgConnection) -> BackendResult<Subscription> {
+        use crate::schema::subscriptions::dsl;
+
+        diesel::delete(
+            dsl::subscriptions
+                .filter(dsl::user_uuid.eq(user_uuid.0))
+                .filter(dsl::thread_uuid.eq(thread_uuid.0))
+        )
+            .get_result(conn)
+            .map_err(handle_err::<Subscription>)
+    }
+
+    /// Removes the user's subscription to the forum.
+    pub fn unsubscribe_from_forum(user_uuid: UserUuid, forum_uuid: ForumUuid, conn: &PgConnection) -> BackendResult<Subscription> {
+        use crate::schema::subscriptions::dsl;
+
+        diesel::delete(
+            dsl::subscriptions
+                .filter(dsl::user_uuid.eq(user_uuid.0))
+                .filter(dsl::forum_uuid.eq(forum_uuid.0))
+        )
+            .get_result(conn)
+            .map_err(handle_err::<Subscription>)
+    }
+
+    /// Gets all of the user's subscriptions, newest first.
+    pub fn get_subscriptions_for_user(user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<Vec<Subscription>> {
+        use crate::schema::subscriptions::dsl;
+
+        dsl::subscriptions
+            .filter(dsl::user_uuid.eq(user_uuid.0))
+            .order(dsl::created_date.desc())
+            .load(conn)
+            .map_err(handle_err::<Subscription>)
+    }
+
+    /// Gets the users following the thread, either directly or through its forum.
+    pub fn get_subscribers(thread: &Thread, conn: &PgConnection) -> BackendResult<Vec<Uuid>> {
+        use crate::schema::subscriptions::dsl;
+
+        dsl::subscriptions
+            .filter(
+                dsl::thread_uuid.eq(thread.uuid)
+                    .or(dsl::forum_uuid.eq(thread.forum_uuid))
+            )
+            .select(dsl::user_uuid)
+            .distinct()
+            .load(conn)
+            .map_err(handle_err::<Subscription>)
+    }
+}
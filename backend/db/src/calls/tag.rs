@@ -0,0 +1,208 @@
+use crate::schema::tags;
+use crate::schema::article_tags;
+use crate::article::{Article, NewArticle, ArticleChangeset};
+use std::collections::HashMap;
+use chrono::{NaiveDateTime, Utc};
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::PgConnection;
+use diesel::Connection;
+use diesel::result::Error as DieselError;
+use diesel::sql_types::{BigInt, Text, Timestamp};
+use error::{BackendResult, Error};
+use uuid::Uuid;
+use identifiers::article::ArticleUuid;
+use crate::calls::prelude::*;
+
+/// The longest a tag's name can be.
+pub const MAX_TAG_LENGTH: usize = 32;
+/// The most tags an article can have.
+pub const MAX_TAGS_PER_ARTICLE: usize = 10;
+
+/// A label that articles can be grouped by.
+#[derive(Clone, Queryable, Identifiable, Debug, PartialEq, TypeName)]
+#[primary_key(uuid)]
+#[table_name = "tags"]
+pub struct Tag {
+    pub uuid: Uuid,
+    /// The normalized name of the tag, which is unique.
+    pub name: String,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "tags"]
+pub struct NewTag {
+    pub name: String,
+}
+
+/// Marks an article with a tag.
+#[derive(Clone, Queryable, Identifiable, Associations, Debug, PartialEq, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(Article, foreign_key = "article_uuid")]
+#[belongs_to(Tag, foreign_key = "tag_uuid")]
+#[table_name = "article_tags"]
+pub struct ArticleTag {
+    pub uuid: Uuid,
+    pub article_uuid: Uuid,
+    pub tag_uuid: Uuid,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "article_tags"]
+pub struct NewArticleTag {
+    pub article_uuid: Uuid,
+    pub tag_uuid: Uuid,
+}
+
+/// A tag, and how many published articles have it.
+#[derive(Debug, Clone, QueryableByName, TypeName)]
+pub struct TagCount {
+    #[sql_type = "Text"]
+    pub name: String,
+    #[sql_type = "BigInt"]
+    pub article_count: i64,
+}
+
+/// Counts the published articles with each tag, most used tags first.
+/// Tags that only unpublished articles have are left out.
+const TAG_COUNT_QUERY: &str = "
+    SELECT
+        t.name AS name,
+        COUNT(*) AS article_count
+    FROM tags t
+    INNER JOIN article_tags at ON at.tag_uuid = t.uuid
+    INNER JOIN articles a ON a.uuid = at.article_uuid
+    WHERE a.publish_date <= $1
+    GROUP BY t.name
+    ORDER BY article_count DESC, t.name
+";
+
+impl Tag {
+    /// Lowercases the name, and joins its words with hyphens, so that `Rust Lang` and `rust-lang` are the same tag.
+    ///
+    /// Returns None if the name is empty, or too long.
+    pub fn normalize(name: &str) -> Option<String> {
+        let normalized: String = name
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join("-")
+            .to_lowercase();
+        let length: usize = normalized.chars().count();
+        if length == 0 || length > MAX_TAG_LENGTH || normalized.chars().any(char::is_control) {
+            None
+        } else {
+            Some(normalized)
+        }
+    }
+
+    /// Normalizes each of the names, removing duplicates.
+    ///
+    /// Fails if any of the names aren't valid, or there are too many of them for a single article.
+    pub fn normalize_names(names: &[String]) -> BackendResult<Vec<String>> {
+        let mut normalized: Vec<String> = vec![];
+        for name in names {
+            let name: String = Tag::normalize(name).ok_or(Error::BadRequest)?;
+            if !normalized.contains(&name) {
+                normalized.push(name);
+            }
+        }
+        if normalized.len() > MAX_TAGS_PER_ARTICLE {
+            return Err(Error::BadRequest)
+        }
+        Ok(normalized)
+    }
+
+    /// Replaces the article's tags, creating any tags that don't exist yet.
+    pub fn set_tags_for_article(article_uuid: ArticleUuid, names: Vec<String>, conn: &PgConnection) -> BackendResult<Vec<Tag>> {
+        let names: Vec<String> = Tag::normalize_names(&names)?;
+        let new_tags: Vec<NewTag> = names
+            .iter()
+            .map(|name| NewTag { name: name.clone() })
+            .collect();
+
+        conn.transaction::<_, DieselError, _>(|| {
+            diesel::delete(article_tags::table.filter(article_tags::article_uuid.eq(article_uuid.0)))
+                .execute(conn)?;
+            if new_tags.is_empty() {
+                return Ok(vec![])
+            }
+
+            diesel::insert_into(tags::table)
+                .values(&new_tags)
+                .on_conflict(tags::name)
+                .do_nothing()
+                .execute(conn)?;
+            let tags: Vec<Tag> = tags::table
+                .filter(tags::name.eq_any(&names))
+                .order(tags::name)
+                .load(conn)?;
+
+            let new_article_tags: Vec<NewArticleTag> = tags
+                .iter()
+                .map(|tag| NewArticleTag {
+                    article_uuid: article_uuid.0,
+                    tag_uuid: tag.uuid,
+                })
+                .collect();
+            diesel::insert_into(article_tags::table)
+                .values(&new_article_tags)
+                .execute(conn)?;
+            Ok(tags)
+        })
+            .map_err(handle_err::<Tag>)
+    }
+
+    /// Gets the names of the tags of each of the articles, in alphabetical order.
+    /// Articles without tags are left out of the map.
+    pub fn get_tags_for_articles(article_uuids: Vec<Uuid>, conn: &PgConnection) -> BackendResult<HashMap<Uuid, Vec<String>>> {
+        let tag_names: Vec<(Uuid, String)> = article_tags::table
+            .inner_join(tags::table)
+            .filter(article_tags::article_uuid.eq_any(article_uuids))
+            .order(tags::name)
+            .select((article_tags::article_uuid, tags::name))
+            .load(conn)
+            .map_err(handle_err::<Tag>)?;
+
+        let mut tags_by_article: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for (article_uuid, name) in tag_names {
+            tags_by_article
+                .entry(article_uuid)
+                .or_insert_with(Vec::new)
+                .push(name);
+        }
+        Ok(tags_by_article)
+    }
+
+    /// Gets every tag that a published article has, along with how many published articles have it.
+    pub fn get_tag_counts(conn: &PgConnection) -> BackendResult<Vec<TagCount>> {
+        let now: NaiveDateTime = Utc::now().naive_utc();
+        diesel::sql_query(TAG_COUNT_QUERY)
+            .bind::<Timestamp, _>(now)
+            .load(conn)
+            .map_err(handle_err::<TagCount>)
+    }
+}
+
+impl Article {
+    /// Creates the article along with its tags, so bad tags don't leave an article behind.
+    pub fn create_article_with_tags(new: NewArticle, tag_names: Vec<String>, conn: &PgConnection) -> BackendResult<Article> {
+        conn.transaction::<_, Error, _>(|| {
+            let article: Article = Article::create_article(new, conn)?;
+            Tag::set_tags_for_article(ArticleUuid(article.uuid), tag_names, conn)?;
+            Ok(article)
+        })
+    }
+
+    /// Updates the article, also replacing its tags if any are provided.
+    pub fn update_article_with_tags(changeset: ArticleChangeset, tag_names: Option<Vec<String>>, conn: &PgConnection) -> BackendResult<Article> {
+        conn.transaction::<_, Error, _>(|| {
+            let article: Article = Article::update_article(changeset, conn)?;
+            if let Some(tag_names) = tag_names {
+                Tag::set_tags_for_article(ArticleUuid(article.uuid), tag_names, conn)?;
+            }
+            Ok(article)
+        })
+    }
+}
@@ -7,17 +7,27 @@ use diesel::RunQueryDsl;
 use diesel::QueryDsl;
 use diesel::BelongingToDsl;
 use diesel::ExpressionMethods;
-use error::BackendResult;
+use diesel::OptionalExtension;
+use diesel::Connection;
+use diesel::result::Error as DieselError;
+use error::{BackendResult, Error};
 use diesel::PgConnection;
 use uuid::Uuid;
 use identifiers::thread::ThreadUuid;
 use identifiers::user::UserUuid;
+use identifiers::post::PostUuid;
 
 use crate::post::{Post, NewPost};
 use crate::post::{PostData, ChildlessPostData};
+use crate::thread_read::{ThreadRead, UnreadPosts};
+use crate::poll::{Poll, NewPollData, PollData};
 use identifiers::forum::ForumUuid;
 use crate::calls::prelude::*;
 use crate::schema;
+use std::collections::HashMap;
+use diesel::dsl::sql;
+use diesel::sql_types::{Nullable, Timestamp};
+use diesel::sql_types::Uuid as SqlUuid;
 
 
 #[derive(Debug, Clone, Identifiable, Associations, Queryable, TypeName)]
@@ -40,6 +50,15 @@ pub struct Thread {
     pub archived: bool,
     /// The title of the thread will be shown on think in the forum that will take you to the thread, as well as at the top of the thread's page.
     pub title: String,
+    /// Pinned threads are listed before all other threads in their forum, in ascending order of this position.
+    /// Threads that aren't pinned don't have a position.
+    pub pinned_position: Option<i32>,
+    /// Timestamp of the most recent post in the thread, or of the thread's creation if it has no posts.
+    pub last_post_date: NaiveDateTime,
+    /// Foreign Key of the user who wrote the most recent post in the thread.
+    pub last_post_author_uuid: Option<Uuid>,
+    /// The number of posts in the thread, not counting the original post.
+    pub reply_count: i32,
 }
 
 
@@ -52,19 +71,58 @@ pub struct NewThread {
     pub locked: bool,
     pub archived: bool,
     pub title: String,
+    pub last_post_date: NaiveDateTime,
 }
 
 pub struct ThreadData {
     pub thread: Thread,
     pub post: PostData,
     pub user: User,
+    pub last_post_author: Option<User>,
+    pub poll: Option<PollData>,
 }
 
 pub struct MinimalThreadData {
     pub thread: Thread,
     pub user: User,
+    pub last_post_author: Option<User>,
+    /// The number of posts the requesting user hasn't read, if the thread was requested by a user.
+    pub unread_count: Option<i64>,
+    /// The oldest post the requesting user hasn't read.
+    pub first_unread_post_uuid: Option<Uuid>,
 }
 
+/// The orders in which the threads in a forum can be listed.
+/// Pinned threads are always listed first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThreadOrder {
+    /// Oldest threads first.
+    Created,
+    /// Threads that were most recently posted in first.
+    LastActivity,
+}
+
+/// Subscribes the subscribers of the source thread ($1) to the target thread ($2),
+/// leaving those already subscribed to the target alone.
+const MERGE_SUBSCRIPTIONS_QUERY: &str = "
+    INSERT INTO subscriptions (user_uuid, thread_uuid, created_date)
+    SELECT user_uuid, $2, created_date
+    FROM subscriptions
+    WHERE thread_uuid = $1
+    ON CONFLICT DO NOTHING
+";
+
+/// Moves the read dates of the target thread ($2) back to those of the source thread ($1), where they are earlier.
+/// Readers that only read the source thread haven't read the target thread's posts, so they don't get a read date.
+const MERGE_READS_QUERY: &str = "
+    UPDATE thread_reads target
+    SET last_read_date = LEAST(target.last_read_date, source.last_read_date)
+    FROM thread_reads source
+    WHERE source.thread_uuid = $1
+        AND target.thread_uuid = $2
+        AND target.user_uuid = source.user_uuid
+";
+
 impl Thread {
 
     pub fn get_thread(uuid: ThreadUuid,conn: &PgConnection) -> BackendResult<Thread> {
@@ -77,6 +135,314 @@ impl Thread {
         create_row::<Thread, NewThread,_>(schema::threads::table, new, conn)
     }
 
+    /// Gets the users associated with the thread.
+    fn get_minimal_data(thread: Thread, conn: &PgConnection) -> BackendResult<MinimalThreadData> {
+        let user: User = User::get_user(UserUuid(thread.author_uuid), conn)?;
+        let last_post_author: Option<User> = match thread.last_post_author_uuid {
+            Some(last_post_author_uuid) => Some(User::get_user(UserUuid(last_post_author_uuid), conn)?),
+            None => None
+        };
+        Ok(MinimalThreadData {
+            thread,
+            user,
+            last_post_author,
+            unread_count: None,
+            first_unread_post_uuid: None,
+        })
+    }
+
+    /// Gets the authors of the latest posts of the threads, and combines them with the threads and their authors.
+    fn attach_last_post_authors(threads_and_users: Vec<(Thread, User)>, conn: &PgConnection) -> BackendResult<Vec<MinimalThreadData>> {
+        use crate::schema::users::dsl::*;
+
+        let last_post_author_uuids: Vec<Uuid> = threads_and_users
+            .iter()
+            .filter_map(|x| x.0.last_post_author_uuid)
+            .collect();
+        let last_post_authors: Vec<User> = users
+            .filter(uuid.eq_any(last_post_author_uuids))
+            .load::<User>(conn)
+            .map_err(handle_err::<User>)?;
+
+        let minimal_threads = threads_and_users
+            .into_iter()
+            .map(|x| {
+                let last_post_author: Option<User> = last_post_authors
+                    .iter()
+                    .find(|author| Some(author.uuid) == x.0.last_post_author_uuid)
+                    .cloned();
+                MinimalThreadData {
+                    thread: x.0,
+                    user: x.1,
+                    last_post_author,
+                    unread_count: None,
+                    first_unread_post_uuid: None,
+                }
+            })
+            .collect();
+        Ok(minimal_threads)
+    }
+
+    /// Sets how many posts in each thread the user hasn't read, and where they should start reading.
+    fn attach_unread_posts(minimal_threads: &mut Vec<MinimalThreadData>, user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<()> {
+        let thread_uuids: Vec<Uuid> = minimal_threads.iter().map(|x| x.thread.uuid).collect();
+        let unread_posts: Vec<UnreadPosts> = ThreadRead::get_unread_posts(user_uuid, thread_uuids, conn)?;
+
+        for minimal_thread in minimal_threads.iter_mut() {
+            let unread: Option<&UnreadPosts> = unread_posts
+                .iter()
+                .find(|x| x.thread_uuid == minimal_thread.thread.uuid);
+            minimal_thread.unread_count = Some(unread.map(|x| x.unread_count).unwrap_or(0));
+            minimal_thread.first_unread_post_uuid = unread.map(|x| x.first_unread_post_uuid);
+        }
+        Ok(())
+    }
+
+    /// Records a newly created post as the latest activity in its thread.
+    /// Posts other than the original post count as replies.
+    ///
+    /// A post written before the thread's latest post, that only got saved after it,
+    /// leaves the latest post in place.
+    pub fn record_post(post: &Post, conn: &PgConnection) -> BackendResult<Thread> {
+        use crate::schema::threads;
+        use crate::schema::threads::dsl::*;
+
+        let reply_increment: i32 = if post.parent_uuid.is_some() { 1 } else { 0 };
+
+        // Both columns are set from the values the row had before the update.
+        let latest_date = sql::<Timestamp>("GREATEST(last_post_date, ")
+            .bind::<Timestamp, _>(post.created_date)
+            .sql(")");
+        let latest_author = sql::<Nullable<SqlUuid>>("CASE WHEN last_post_date > ")
+            .bind::<Timestamp, _>(post.created_date)
+            .sql(" THEN last_post_author_uuid ELSE ")
+            .bind::<SqlUuid, _>(post.author_uuid)
+            .sql(" END");
+
+        diesel::update(threads::table)
+            .filter(threads::uuid.eq(post.thread_uuid))
+            .set((
+                last_post_date.eq(latest_date),
+                last_post_author_uuid.eq(latest_author),
+                reply_count.eq(reply_count + reply_increment)
+            ))
+            .get_result(conn)
+            .map_err(handle_err::<Thread>)
+    }
+
+
+    /// Recalculates the thread's activity from the posts it currently contains.
+    /// This is needed whenever posts are moved between threads.
+    fn recalculate_activity(thread_uuid: Uuid, conn: &PgConnection) -> Result<Thread, DieselError> {
+        use crate::schema::threads;
+        use crate::schema::posts;
+
+        let thread: Thread = threads::table.find(thread_uuid).first(conn)?;
+        let latest_post: Option<Post> = posts::table
+            .filter(posts::thread_uuid.eq(thread_uuid))
+            .order(posts::created_date.desc())
+            .first(conn)
+            .optional()?;
+        let replies: i64 = posts::table
+            .filter(posts::thread_uuid.eq(thread_uuid))
+            .filter(posts::parent_uuid.is_not_null())
+            .count()
+            .get_result(conn)?;
+
+        diesel::update(threads::table.find(thread_uuid))
+            .set((
+                threads::last_post_date.eq(latest_post.as_ref().map(|x| x.created_date).unwrap_or(thread.created_date)),
+                threads::last_post_author_uuid.eq(latest_post.map(|x| x.author_uuid)),
+                threads::reply_count.eq(replies as i32)
+            ))
+            .get_result(conn)
+    }
+
+    /// Changes the title of the thread.
+    ///
+    /// Only the thread's author and moderators can change the title,
+    /// and not while the thread is locked or archived.
+    pub fn edit_title(thread_uuid: ThreadUuid, new_title: String, editor_uuid: UserUuid, is_moderator: bool, conn: &PgConnection) -> BackendResult<MinimalThreadData> {
+        use crate::schema::threads;
+
+        let thread: Thread = Thread::get_thread(thread_uuid, conn)?;
+        if thread.author_uuid != editor_uuid.0 && !is_moderator {
+            return Err(Error::NotAuthorized { reason: "Only the author or a moderator can edit the thread." })
+        }
+        if thread.locked || thread.archived {
+            return Err(Error::ThreadImmutable)
+        }
+        if new_title.trim().is_empty() {
+            return Err(Error::BadRequest)
+        }
+
+        let thread: Thread = diesel::update(threads::table.find(thread_uuid.0))
+            .set(threads::title.eq(new_title))
+            .get_result(conn)
+            .map_err(handle_err::<Thread>)?;
+        Thread::get_minimal_data(thread, conn)
+    }
+
+    /// Moves the thread to another forum.
+    pub fn move_thread(thread_uuid: ThreadUuid, forum_uuid: ForumUuid, conn: &PgConnection) -> BackendResult<MinimalThreadData> {
+        use crate::schema::threads;
+
+        // Make sure the destination exists, so a missing forum is reported as such.
+        Forum::get_forum(forum_uuid, conn)?;
+
+        let thread: Thread = diesel::update(threads::table.find(thread_uuid.0))
+            .set(threads::forum_uuid.eq(forum_uuid.0))
+            .get_result(conn)
+            .map_err(handle_err::<Thread>)?;
+
+        Thread::get_minimal_data(thread, conn)
+    }
+
+    /// Merges the source thread into the target thread.
+    ///
+    /// The source thread's original post becomes a reply to the target thread's original post,
+    /// bringing all of its replies along with it, and the source thread is deleted.
+    /// Subscribers of the source thread are subscribed to the target thread,
+    /// and readers of both threads keep the earlier of their read dates, so the moved posts they haven't read stay unread.
+    /// Threads with a poll and the comment threads of articles can't be merged into another thread.
+    pub fn merge_threads(source_uuid: ThreadUuid, target_uuid: ThreadUuid, conn: &PgConnection) -> BackendResult<MinimalThreadData> {
+        use crate::schema::threads;
+        use crate::schema::posts;
+        use crate::schema::notifications;
+
+        if source_uuid == target_uuid {
+            return Err(Error::BadRequest)
+        }
+        Thread::get_thread(source_uuid, conn)?;
+        Thread::get_thread(target_uuid, conn)?;
+        // A thread only has one poll, so the source's poll would have nowhere to go.
+        // Polls are only made along with their thread, so this doesn't need to be checked again in the transaction.
+        if Poll::get_poll_for_thread(source_uuid, None, conn)?.is_some() {
+            return Err(Error::BadRequest)
+        }
+        // Merging away an article's comment thread would leave the article without its comments.
+        let is_comment_thread: bool = diesel::select(diesel::dsl::exists(
+            schema::articles::table.filter(schema::articles::comment_thread_uuid.eq(source_uuid.0))
+        ))
+            .get_result(conn)
+            .map_err(handle_err::<Thread>)?;
+        if is_comment_thread {
+            return Err(Error::BadRequest)
+        }
+
+        let target: Thread = conn.transaction::<_, DieselError, _>(|| {
+            // Lock both threads, so nothing is added to the source while its rows are moved.
+            threads::table
+                .filter(threads::uuid.eq_any(vec![source_uuid.0, target_uuid.0]))
+                .order(threads::uuid)
+                .for_update()
+                .load::<Thread>(conn)?;
+            let root_of = |thread_uuid: Uuid| -> Result<Post, DieselError> {
+                posts::table
+                    .filter(posts::thread_uuid.eq(thread_uuid))
+                    .filter(posts::parent_uuid.is_null())
+                    .first(conn)
+            };
+            let source_root: Post = root_of(source_uuid.0)?;
+            let target_root: Post = root_of(target_uuid.0)?;
+
+            diesel::update(posts::table.filter(posts::thread_uuid.eq(source_uuid.0)))
+                .set(posts::thread_uuid.eq(target_uuid.0))
+                .execute(conn)?;
+            diesel::update(posts::table.find(source_root.uuid))
+                .set(posts::parent_uuid.eq(Some(target_root.uuid)))
+                .execute(conn)?;
+            diesel::update(notifications::table.filter(notifications::thread_uuid.eq(source_uuid.0)))
+                .set(notifications::thread_uuid.eq(target_uuid.0))
+                .execute(conn)?;
+            diesel::sql_query(MERGE_SUBSCRIPTIONS_QUERY)
+                .bind::<SqlUuid, _>(source_uuid.0)
+                .bind::<SqlUuid, _>(target_uuid.0)
+                .execute(conn)?;
+            diesel::sql_query(MERGE_READS_QUERY)
+                .bind::<SqlUuid, _>(source_uuid.0)
+                .bind::<SqlUuid, _>(target_uuid.0)
+                .execute(conn)?;
+            diesel::delete(threads::table.find(source_uuid.0))
+                .execute(conn)?;
+
+            Thread::recalculate_activity(target_uuid.0, conn)
+        })
+            .map_err(handle_err::<Thread>)?;
+
+        Thread::get_minimal_data(target, conn)
+    }
+
+    /// Splits the post and all of its replies out into a new thread in the same forum,
+    /// with the post becoming the new thread's original post.
+    ///
+    /// The original post of a thread can't be split off, as that would leave the thread empty.
+    pub fn split_thread(post_uuid: PostUuid, title: String, conn: &PgConnection) -> BackendResult<MinimalThreadData> {
+        use crate::schema::threads;
+        use crate::schema::posts;
+        use crate::schema::notifications;
+
+        let post: Post = Post::get_post(post_uuid, conn)?;
+        if post.parent_uuid.is_none() {
+            return Err(Error::BadRequest)
+        }
+        let thread: Thread = Thread::get_thread(ThreadUuid(post.thread_uuid), conn)?;
+
+        let new_thread = NewThread {
+            forum_uuid: thread.forum_uuid,
+            author_uuid: post.author_uuid,
+            created_date: post.created_date,
+            locked: false,
+            archived: false,
+            title,
+            last_post_date: post.created_date,
+        };
+
+        let split_thread: Thread = conn.transaction::<_, DieselError, _>(|| {
+            // Lock the thread, so posts can't be added to or moved out of it while the replies are gathered.
+            threads::table
+                .find(thread.uuid)
+                .for_update()
+                .first::<Thread>(conn)?;
+            let post: Post = posts::table
+                .find(post.uuid)
+                .filter(posts::thread_uuid.eq(thread.uuid))
+                .first(conn)?;
+            let split_uuids: Vec<Uuid> = Post::load_descendant_uuids(&post, conn)?;
+
+            let split_thread: Thread = diesel::insert_into(threads::table)
+                .values(&new_thread)
+                .get_result(conn)?;
+
+            diesel::update(posts::table.filter(posts::uuid.eq_any(&split_uuids)))
+                .set(posts::thread_uuid.eq(split_thread.uuid))
+                .execute(conn)?;
+            diesel::update(posts::table.find(post.uuid))
+                .set(posts::parent_uuid.eq(None::<Uuid>))
+                .execute(conn)?;
+            diesel::update(notifications::table.filter(notifications::post_uuid.eq_any(&split_uuids)))
+                .set(notifications::thread_uuid.eq(split_thread.uuid))
+                .execute(conn)?;
+
+            Thread::recalculate_activity(thread.uuid, conn)?;
+            Thread::recalculate_activity(split_thread.uuid, conn)
+        })
+            .map_err(handle_err::<Thread>)?;
+
+        Thread::get_minimal_data(split_thread, conn)
+    }
+
+    /// Gets the number of replies in each of the threads.
+    pub fn get_reply_counts(thread_uuids: Vec<Uuid>, conn: &PgConnection) -> BackendResult<HashMap<Uuid, i32>> {
+        use crate::schema::threads::dsl::*;
+
+        threads
+            .filter(uuid.eq_any(thread_uuids))
+            .select((uuid, reply_count))
+            .load::<(Uuid, i32)>(conn)
+            .map(|counts| counts.into_iter().collect())
+            .map_err(handle_err::<Thread>)
+    }
 
     /// Locks or unlocks the thread, preventing posting and editing if locked
     pub fn set_lock_status(thread_uuid: ThreadUuid, is_locked: bool, conn: &PgConnection) -> BackendResult<MinimalThreadData> {
@@ -89,10 +455,21 @@ impl Thread {
             .get_result(conn)
             .map_err(handle_err::<Thread>)?;
 
-        let author_uuid_a = UserUuid(thread.author_uuid);
-        let user: User = User::get_user(author_uuid_a, conn)?;
+        Thread::get_minimal_data(thread, conn)
+    }
 
-        Ok(MinimalThreadData { thread, user })
+    /// Pins the thread at the given position, or unpins it if no position is provided.
+    pub fn set_pinned_position(thread_uuid: ThreadUuid, position: Option<i32>, conn: &PgConnection) -> BackendResult<MinimalThreadData> {
+        use crate::schema::threads;
+        use crate::schema::threads::dsl::*;
+
+        let thread: Thread = diesel::update(threads::table)
+            .filter(threads::uuid.eq(thread_uuid.0))
+            .set(pinned_position.eq(position))
+            .get_result(conn)
+            .map_err(handle_err::<Thread>)?;
+
+        Thread::get_minimal_data(thread, conn)
     }
 
     /// Archives the thread, preventing it from being seen in typical requests.
@@ -109,13 +486,46 @@ impl Thread {
             .set(archived.eq(true))
             .get_result(conn)
             .map_err(handle_err::<Thread>)?;
-        let author_uuid_a = UserUuid(thread.author_uuid);
-        let user: User = User::get_user(author_uuid_a, conn)?;
+        Thread::get_minimal_data(thread, conn)
+    }
+
+    /// Restores an archived thread, making it visible to everyone again.
+    ///
+    /// The thread keeps its lock status, so it has to be unlocked separately to be modified.
+    pub fn unarchive_thread(thread_uuid: ThreadUuid, conn: &PgConnection) -> BackendResult<MinimalThreadData> {
+        use crate::schema::threads;
+        use crate::schema::threads::dsl::*;
+
+        let thread: Thread = diesel::update(threads::table)
+            .filter(threads::uuid.eq(thread_uuid.0))
+            .set(archived.eq(false))
+            .get_result(conn)
+            .map_err(handle_err::<Thread>)?;
+        Thread::get_minimal_data(thread, conn)
+    }
+
+    /// Gets the archived threads in a forum based on page size and index, most recently active first.
+    /// This is intended for moderators only.
+    pub fn get_archived_paginated(requested_forum_uuid: ForumUuid, page_index: i32, page_size: i32, conn: &PgConnection) -> BackendResult<Vec<MinimalThreadData>> {
+        use crate::schema::threads::dsl::*;
+        use crate::diesel_extensions::pagination::*;
+        use crate::schema::users;
+
+        let forum: Forum = Forum::get_forum(requested_forum_uuid, conn)?;
+
+        let (thread_users, _count) = Thread::belonging_to(&forum)
+            .inner_join(users::table)
+            .filter(archived.eq(true))
+            .order(last_post_date.desc())
+            .paginate(page_index.into())
+            .per_page(page_size.into())
+            .load_and_count_pages::<(Thread, User)>(conn)
+            .map_err(handle_err::<Thread>)?;
 
-        Ok(MinimalThreadData { thread, user })
+        Thread::attach_last_post_authors(thread_users, conn)
     }
 
-    /// Gets all of the most recent threads in a forum.
+    /// Gets all of the most recent threads in a forum, with pinned threads first.
     /// Archived threads will not be included.
     #[deprecated]
     pub fn get_threads_in_forum(requested_forum_uuid: ForumUuid, num_threads: i64, conn: &PgConnection) -> BackendResult<Vec<MinimalThreadData>> {
@@ -128,27 +538,26 @@ impl Thread {
         // Get the threads that belong to the forum, and then get the users that are associated with the threads.
         let threads_and_users: Vec<(Thread, User)> = Thread::belonging_to(&forum)
             .filter(archived.eq(false))
-            .order(created_date)
+            .order((pinned_position.asc(), created_date))
             .limit(num_threads)
             .inner_join(users)
             .load::<(Thread, User)>(conn)
             .map_err(handle_err::<Thread>)?;
 
-
-        let min_threads = threads_and_users
-            .into_iter()
-            .map(|x| {
-                MinimalThreadData {
-                    thread: x.0,
-                    user: x.1,
-                }
-            })
-            .collect();
-        Ok(min_threads)
+        Thread::attach_last_post_authors(threads_and_users, conn)
     }
 
-    /// Gets threads based on page size and index.
+    /// Gets threads based on page size and index, oldest first.
+    /// Pinned threads are ordered before all others, so they appear at the start of the first page.
     pub fn get_paginated(requested_forum_uuid: ForumUuid, page_index: i32, page_size: i32, conn: &PgConnection) -> BackendResult<Vec<MinimalThreadData>> {
+        Thread::get_paginated_ordered(requested_forum_uuid, ThreadOrder::Created, None, page_index, page_size, conn)
+    }
+
+    /// Gets threads based on page size and index, in the provided order.
+    /// Pinned threads are ordered before all others, so they appear at the start of the first page.
+    ///
+    /// If a user is provided, the threads will indicate which of their posts the user hasn't read.
+    pub fn get_paginated_ordered(requested_forum_uuid: ForumUuid, order: ThreadOrder, user_uuid: Option<UserUuid>, page_index: i32, page_size: i32, conn: &PgConnection) -> BackendResult<Vec<MinimalThreadData>> {
         use crate::schema::threads::dsl::*;
         use crate::forum::Forum;
         use crate::diesel_extensions::pagination::*;
@@ -156,52 +565,72 @@ impl Thread {
 
         let forum: Forum = Forum::get_forum(requested_forum_uuid, conn)?;
 
-        let (thread_users, _count) = Thread::belonging_to(&forum)
+        let query = Thread::belonging_to(&forum)
             .inner_join(users::table)
-            .order(created_date)
             .filter(archived.eq(false))
+            .into_boxed();
+
+        // Postgres sorts NULLs last in ascending order, so unpinned threads follow the pinned ones.
+        let query = match order {
+            ThreadOrder::Created => query.order((pinned_position.asc(), created_date.asc())),
+            ThreadOrder::LastActivity => query.order((pinned_position.asc(), last_post_date.desc())),
+        };
+
+        let (thread_users, _count) = query
             .paginate(page_index.into())
             .per_page(page_size.into())
             .load_and_count_pages::<(Thread, User)>(conn)
             .map_err(handle_err::<Thread>)?;
 
-        let minimal_threads = thread_users
-            .into_iter()
-            .map(|x| {
-                MinimalThreadData {
-                    thread: x.0,
-                    user: x.1,
-                }
-            })
-            .collect();
-
+        let mut minimal_threads = Thread::attach_last_post_authors(thread_users, conn)?;
+        if let Some(user_uuid) = user_uuid {
+            Thread::attach_unread_posts(&mut minimal_threads, user_uuid, conn)?;
+        }
         Ok(minimal_threads)
-
-
     }
 
 
-    /// Creates a thread with an initial post.
-    pub fn create_thread_with_initial_post(new_thread: NewThread, post_content: String, conn: &PgConnection) -> BackendResult<ThreadData> {
-        let thread: Thread = Thread::create_thread(new_thread, conn)?;
-
-        let new_post: NewPost = NewPost::from((thread.clone(), post_content));
-
-        let post_data: ChildlessPostData = Post::create_and_get_user(new_post, conn)?;
-        let user: User = post_data.user.clone();
-        Ok(ThreadData {
-            thread,
-            post: PostData::from(post_data),
-            user,
+    /// Creates a thread with an initial post, and optionally a poll.
+    pub fn create_thread_with_initial_post(new_thread: NewThread, post_content: String, poll: Option<NewPollData>, conn: &PgConnection) -> BackendResult<ThreadData> {
+        // The thread is created along with its poll and post, or not at all.
+        conn.transaction::<_, Error, _>(|| {
+            let thread: Thread = Thread::create_thread(new_thread, conn)?;
+            let poll: Option<PollData> = match poll {
+                Some(poll) => Some(Poll::create_poll(ThreadUuid(thread.uuid), poll, conn)?),
+                None => None
+            };
+
+            let new_post: NewPost = NewPost::from((thread.clone(), post_content));
+
+            let post_data: ChildlessPostData = Post::create_and_get_user(new_post, conn)?;
+            let user: User = post_data.user.clone();
+            // Creating the post updated the thread's activity.
+            let thread: Thread = Thread::get_thread(ThreadUuid(thread.uuid), conn)?;
+            Ok(ThreadData {
+                thread,
+                post: PostData::from(post_data),
+                last_post_author: Some(user.clone()),
+                user,
+                poll,
+            })
         })
     }
 
     /// Gets every bit of data related to a thread.
-    pub fn get_full_thread(thread_uuid: ThreadUuid, user_uuid: Option<UserUuid>, conn: &PgConnection) -> BackendResult<ThreadData> {
+    /// Archived threads can only be gotten by moderators.
+    ///
+    /// If a user is provided, every post in the thread is marked as read by them.
+    pub fn get_full_thread(thread_uuid: ThreadUuid, user_uuid: Option<UserUuid>, is_moderator: bool, conn: &PgConnection) -> BackendResult<ThreadData> {
         let thread: Thread = Thread::get_thread(thread_uuid, conn)?;
+        if thread.archived && !is_moderator {
+            return Err(Error::NotAuthorized { reason: "Archived threads can only be viewed by moderators." })
+        }
         let post: PostData = Post::get_posts_in_thread(thread_uuid, user_uuid, conn)?;
-        let author_uuid = UserUuid(thread.author_uuid);
-        let user = User::get_user(author_uuid, conn)?;
-        Ok(ThreadData { thread, post, user })
+        if let Some(user_uuid) = user_uuid {
+            ThreadRead::mark_read(user_uuid, thread_uuid, thread.last_post_date, conn)?;
+        }
+        let poll: Option<PollData> = Poll::get_poll_for_thread(thread_uuid, user_uuid, conn)?;
+        let MinimalThreadData { thread, user, last_post_author, .. } = Thread::get_minimal_data(thread, conn)?;
+        Ok(ThreadData { thread, post, user, last_post_author, poll })
     }
 }
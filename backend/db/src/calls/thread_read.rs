@@ -0,0 +1,103 @@
+use crate::schema::thread_reads;
+use chrono::NaiveDateTime;
+use crate::user::User;
+use crate::thread::Thread;
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::ExpressionMethods;
+use diesel::PgConnection;
+use diesel::dsl::sql;
+use diesel::sql_types::{Array, BigInt, Timestamp};
+use diesel::sql_types::Uuid as SqlUuid;
+use error::BackendResult;
+use uuid::Uuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::user::UserUuid;
+use crate::calls::prelude::*;
+
+/// Records how far into a thread a user has read.
+#[derive(Debug, Clone, Identifiable, Associations, Queryable, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(User, foreign_key = "user_uuid")]
+#[belongs_to(Thread, foreign_key = "thread_uuid")]
+#[table_name = "thread_reads"]
+pub struct ThreadRead {
+    /// Primary Key
+    pub uuid: Uuid,
+    /// Foreign Key of the user who read the thread.
+    pub user_uuid: Uuid,
+    /// Foreign Key of the thread that was read.
+    pub thread_uuid: Uuid,
+    /// The creation date of the most recent post in the thread at the time the user read it.
+    pub last_read_date: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "thread_reads"]
+pub struct NewThreadRead {
+    pub user_uuid: Uuid,
+    pub thread_uuid: Uuid,
+    pub last_read_date: NaiveDateTime,
+}
+
+/// The posts in a thread that a user hasn't read yet.
+#[derive(Debug, Clone, QueryableByName, TypeName)]
+pub struct UnreadPosts {
+    #[sql_type = "SqlUuid"]
+    pub thread_uuid: Uuid,
+    #[sql_type = "BigInt"]
+    pub unread_count: i64,
+    /// The oldest post in the thread that the user hasn't read.
+    #[sql_type = "SqlUuid"]
+    pub first_unread_post_uuid: Uuid,
+}
+
+/// Posts that were created after the user last read the thread are unread.
+/// If the user has never read the thread, all of its posts are unread.
+/// Users have always read their own posts, and censored posts are never unread.
+const UNREAD_POSTS_QUERY: &str = "
+    SELECT
+        p.thread_uuid AS thread_uuid,
+        COUNT(*) AS unread_count,
+        (ARRAY_AGG(p.uuid ORDER BY p.created_date))[1] AS first_unread_post_uuid
+    FROM posts p
+    LEFT JOIN thread_reads r ON r.thread_uuid = p.thread_uuid AND r.user_uuid = $1
+    WHERE p.thread_uuid = ANY($2)
+        AND p.author_uuid <> $1
+        AND NOT p.censored
+        AND (r.last_read_date IS NULL OR p.created_date > r.last_read_date)
+    GROUP BY p.thread_uuid
+";
+
+impl ThreadRead {
+    /// Records that the user has read every post in the thread created up to and including the read date.
+    /// Reading the thread up to an earlier date, say from a stale page, leaves the later date in place.
+    pub fn mark_read(user_uuid: UserUuid, thread_uuid: ThreadUuid, read_date: NaiveDateTime, conn: &PgConnection) -> BackendResult<ThreadRead> {
+        use crate::schema::thread_reads::dsl;
+
+        let new_thread_read = NewThreadRead {
+            user_uuid: user_uuid.0,
+            thread_uuid: thread_uuid.0,
+            last_read_date: read_date,
+        };
+
+        diesel::insert_into(thread_reads::table)
+            .values(&new_thread_read)
+            .on_conflict((dsl::user_uuid, dsl::thread_uuid))
+            .do_update()
+            // Diesel can't pass excluded columns to functions, so GREATEST is written out.
+            .set(dsl::last_read_date.eq(sql::<Timestamp>("GREATEST(thread_reads.last_read_date, EXCLUDED.last_read_date)")))
+            .get_result(conn)
+            .map_err(handle_err::<ThreadRead>)
+    }
+
+    /// Gets the unread posts for each of the provided threads.
+    /// Threads without any unread posts are left out.
+    pub fn get_unread_posts(user_uuid: UserUuid, thread_uuids: Vec<Uuid>, conn: &PgConnection) -> BackendResult<Vec<UnreadPosts>> {
+        diesel::sql_query(UNREAD_POSTS_QUERY)
+            .bind::<SqlUuid, _>(user_uuid.0)
+            .bind::<Array<SqlUuid>, _>(thread_uuids)
+            .load(conn)
+            .map_err(handle_err::<UnreadPosts>)
+    }
+}
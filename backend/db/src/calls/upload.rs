@@ -0,0 +1,232 @@
+use crate::schema::uploads;
+use crate::user::User;
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::RunQueryDsl;
+use diesel::QueryDsl;
+use diesel::ExpressionMethods;
+use diesel::OptionalExtension;
+use diesel::Connection;
+use diesel::PgConnection;
+use diesel::sql_types::Text;
+use error::{BackendResult, Error};
+use uuid::Uuid;
+use identifiers::upload::UploadUuid;
+use identifiers::user::UserUuid;
+use crate::calls::prelude::*;
+use crate::schema;
+
+/// The largest file that can be uploaded, in bytes.
+pub const MAX_UPLOAD_SIZE: u64 = 10 * 1024 * 1024;
+/// The longest that the name of an uploaded file can be.
+pub const MAX_FILE_NAME_LENGTH: usize = 128;
+/// The path that uploaded files are served under.
+pub const UPLOADS_PATH: &str = "uploads";
+
+/// The types of files that can be uploaded, and the extension they are stored with.
+///
+/// SVGs are left out because they can carry scripts.
+const UPLOAD_TYPES: &[(&str, &str)] = &[
+    ("image/png", "png"),
+    ("image/jpeg", "jpg"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("application/pdf", "pdf"),
+    ("text/plain", "txt"),
+];
+
+/// A file that a user has uploaded.
+#[derive(Clone, Queryable, Identifiable, Associations, Debug, PartialEq, TypeName)]
+#[primary_key(uuid)]
+#[belongs_to(User, foreign_key = "owner_uuid")]
+#[table_name = "uploads"]
+pub struct Upload {
+    pub uuid: Uuid,
+    /// The user that uploaded the file.
+    pub owner_uuid: Uuid,
+    /// The hex encoded SHA-1 hash of the file's content, which determines where it is stored.
+    pub content_hash: String,
+    /// The name of the file on the uploader's computer.
+    pub file_name: String,
+    pub mime_type: String,
+    /// The size of the file in bytes.
+    pub size: i64,
+    /// Whether a thumbnail was made for the file, which is only attempted for images.
+    pub has_thumbnail: bool,
+    pub created_date: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "uploads"]
+pub struct NewUpload {
+    pub owner_uuid: Uuid,
+    pub content_hash: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub has_thumbnail: bool,
+}
+
+impl Upload {
+    /// Gets the extension that files of the type are stored with,
+    /// or None if files of the type can't be uploaded.
+    pub fn extension_for(mime_type: &str) -> Option<&'static str> {
+        UPLOAD_TYPES
+            .iter()
+            .find(|(allowed, _)| *allowed == mime_type)
+            .map(|(_, extension)| *extension)
+    }
+
+    /// Gets the type of the files stored with the extension.
+    pub fn mime_type_for(extension: &str) -> Option<&'static str> {
+        UPLOAD_TYPES
+            .iter()
+            .find(|(_, allowed)| *allowed == extension)
+            .map(|(mime_type, _)| *mime_type)
+    }
+
+    /// Checks that the file can be uploaded, returning its type.
+    ///
+    /// The type the uploader declared has to be one that can be uploaded,
+    /// and the start of the file has to match what files of that type start with,
+    /// so that the file will be served with a type that browsers treat it as.
+    pub fn check_content(declared_mime_type: &str, data: &[u8]) -> BackendResult<&'static str> {
+        if data.len() as u64 > MAX_UPLOAD_SIZE {
+            return Err(Error::PayloadTooLarge { limit: MAX_UPLOAD_SIZE })
+        }
+        // Parameters, like the charset of text, aren't kept.
+        let declared: String = declared_mime_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        let mime_type: &'static str = UPLOAD_TYPES
+            .iter()
+            .map(|(allowed, _)| *allowed)
+            .find(|allowed| *allowed == declared)
+            .ok_or(Error::UnsupportedMediaType)?;
+
+        let matches_content: bool = match mime_type {
+            "image/png" => data.starts_with(b"\x89PNG\r\n\x1a\n"),
+            "image/jpeg" => data.starts_with(b"\xff\xd8\xff"),
+            "image/gif" => data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a"),
+            "image/webp" => data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP",
+            "application/pdf" => data.starts_with(b"%PDF-"),
+            _ => ::std::str::from_utf8(data).is_ok(),
+        };
+        if matches_content {
+            Ok(mime_type)
+        } else {
+            Err(Error::UnsupportedMediaType)
+        }
+    }
+
+    /// Whether the file is an image, and can be shown inline.
+    pub fn is_image(&self) -> bool {
+        self.mime_type.starts_with("image/")
+    }
+
+    /// Takes the name of the file from the path the uploader's browser sent,
+    /// removing anything that couldn't be shown as part of a name.
+    pub fn clean_file_name(name: &str) -> String {
+        let name: String = name
+            .rsplit(|c| c == '/' || c == '\\')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(MAX_FILE_NAME_LENGTH)
+            .collect();
+        let name: &str = name.trim();
+        if name.is_empty() {
+            "upload".to_string()
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Where the content with the given hash is stored, relative to the upload directory.
+    ///
+    /// Files are spread across directories named after the first two characters of their hash,
+    /// so no one directory gets too large.
+    pub fn content_path(content_hash: &str, extension: &str) -> String {
+        format!("{}/{}.{}", &content_hash[..2], content_hash, extension)
+    }
+
+    /// Where the thumbnail of the content with the given hash is stored, relative to the upload directory.
+    /// Thumbnails are always PNGs.
+    pub fn thumbnail_path(content_hash: &str) -> String {
+        Upload::content_path(content_hash, "thumb.png")
+    }
+
+    /// Where the file is stored, relative to the upload directory.
+    pub fn file_path(&self) -> String {
+        let extension: &str = Upload::extension_for(&self.mime_type).unwrap_or("bin");
+        Upload::content_path(&self.content_hash, extension)
+    }
+
+    pub fn get_upload(uuid: UploadUuid, conn: &PgConnection) -> BackendResult<Upload> {
+        get_row::<Upload,_>(schema::uploads::table, uuid.0, conn)
+    }
+
+    pub fn create_upload(new: NewUpload, conn: &PgConnection) -> BackendResult<Upload> {
+        create_row::<Upload, NewUpload,_>(schema::uploads::table, new, conn)
+    }
+
+    pub fn delete_upload(uuid: UploadUuid, conn: &PgConnection) -> BackendResult<Upload> {
+        delete_row::<Upload,_>(schema::uploads::table, uuid.0, conn)
+    }
+
+    /// Gets the files the user has uploaded, most recent first.
+    pub fn get_uploads_for_user(user_uuid: UserUuid, conn: &PgConnection) -> BackendResult<Vec<Upload>> {
+        use crate::schema::uploads::dsl::*;
+
+        uploads
+            .filter(owner_uuid.eq(user_uuid.0))
+            .order(created_date.desc())
+            .load::<Upload>(conn)
+            .map_err(handle_err::<Upload>)
+    }
+
+    /// Gets an existing upload with the same content, so its file and thumbnail can be reused.
+    pub fn get_by_content_hash(hash: &str, conn: &PgConnection) -> BackendResult<Option<Upload>> {
+        use crate::schema::uploads::dsl::*;
+
+        uploads
+            .filter(content_hash.eq(hash))
+            .order(has_thumbnail.desc())
+            .first::<Upload>(conn)
+            .optional()
+            .map_err(handle_err::<Upload>)
+    }
+
+    /// Gets an existing upload with the same content and type, which is stored in the same file.
+    pub fn get_by_content_hash_and_type(hash: &str, content_type: &str, conn: &PgConnection) -> BackendResult<Option<Upload>> {
+        use crate::schema::uploads::dsl::*;
+
+        uploads
+            .filter(content_hash.eq(hash))
+            .filter(mime_type.eq(content_type))
+            .first::<Upload>(conn)
+            .optional()
+            .map_err(handle_err::<Upload>)
+    }
+
+    /// Runs the function in a transaction that holds a lock on the content with the hash.
+    ///
+    /// Recording an upload and deleting one both take the lock,
+    /// so the files of the content aren't removed while another upload of it is being recorded.
+    pub fn with_content_lock<T, F>(hash: &str, conn: &PgConnection, f: F) -> BackendResult<T>
+    where
+        F: FnOnce() -> BackendResult<T>
+    {
+        conn.transaction::<_, Error, _>(|| {
+            diesel::sql_query("SELECT pg_advisory_xact_lock(hashtext($1))")
+                .bind::<Text, _>(hash)
+                .execute(conn)
+                .map_err(handle_err::<Upload>)?;
+            f()
+        })
+    }
+}
@@ -17,6 +17,16 @@ use log::info;
 // TODO, I don't think that this file should have wire types
 use wire::user::*;
 
+/// Sets every user's reputation to the net score of the votes left on their posts.
+const RECOMPUTE_REPUTATION_QUERY: &str = "
+    UPDATE users SET reputation = COALESCE((
+        SELECT SUM(CASE WHEN r.reaction = 'up' THEN 1 ELSE -1 END)::INTEGER
+        FROM post_reactions r
+        JOIN posts p ON p.uuid = r.post_uuid
+        WHERE p.author_uuid = users.uuid
+            AND r.reaction IN ('up', 'down')
+    ), 0)
+";
 
 /// The database's representation of a user.
 #[derive(Debug, Clone, Identifiable, Queryable, PartialEq, TypeName)]
@@ -39,6 +49,8 @@ pub struct User {
     pub banned: bool,
     /// The roles of the user.
     pub roles: Vec<i32>, // currently this is stored as an int. It would be better to store it as an enum, if diesel-enum serialization can be made to work.
+    /// The net score of the votes left on the user's posts.
+    pub reputation: i32,
 }
 
 
@@ -287,5 +299,28 @@ impl User {
             .get_result(conn)
             .map_err(handle_err::<User>)
     }
-}
 
+    /// Gets the users with the highest reputation.
+    /// Banned users are left off of the leaderboard.
+    pub fn get_leaderboard(num_users: i64, conn: &PgConnection) -> BackendResult<Vec<User>> {
+        use crate::schema::users::dsl::*;
+
+        users
+            .filter(banned.eq(false))
+            .order((reputation.desc(), user_name.asc()))
+            .limit(num_users)
+            .load::<User>(conn)
+            .map_err(handle_err::<User>)
+    }
+
+    /// Recomputes every user's reputation from the votes on their posts.
+    /// Reputation is kept up to date as votes are cast, so this is only needed to repair drift,
+    /// such as when posts are deleted along with their votes.
+    /// Returns the number of users updated.
+    pub fn recompute_reputation(conn: &PgConnection) -> BackendResult<usize> {
+        info!("Recomputing user reputation");
+        diesel::sql_query(RECOMPUTE_REPUTATION_QUERY)
+            .execute(conn)
+            .map_err(handle_err::<User>)
+    }
+}
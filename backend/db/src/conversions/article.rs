@@ -1,20 +1,31 @@
 use crate::article::*;
 use wire::article::*;
+use wire::user::UserResponse;
 
 use slug;
 use rand;
 use rand::Rng;
 use identifiers::article::ArticleUuid;
 use identifiers::user::UserUuid;
+use identifiers::article_revision::ArticleRevisionUuid;
+use identifiers::thread::ThreadUuid;
+
+/// The number of characters of the body shown in article previews.
+const EXCERPT_LENGTH: usize = 200;
 
 impl From<Article> for MinimalArticleResponse {
     fn from(article: Article) -> MinimalArticleResponse {
+        let review_state: ReviewState = article.state();
         MinimalArticleResponse {
             uuid: ArticleUuid(article.uuid),
             author_uuid: UserUuid(article.author_uuid),
             title: article.title,
+            slug: article.slug,
             body: article.body,
             publish_date: article.publish_date,
+            published_revision_uuid: article.published_revision_uuid.map(ArticleRevisionUuid),
+            comments_enabled: article.comments_enabled,
+            review_state,
         }
     }
 }
@@ -24,9 +35,15 @@ impl From<ArticleData> for FullArticleResponse {
         FullArticleResponse {
             id: ArticleUuid(data.article.uuid),
             author: data.user.into(),
+            co_authors: data.co_authors.into_iter().map(UserResponse::from).collect(),
+            review_state: data.article.state(),
             title: data.article.title,
+            slug: data.article.slug,
             body: data.article.body,
+            tags: data.tags,
             publish_date: data.article.publish_date,
+            comment_thread_uuid: data.article.comment_thread_uuid.map(ThreadUuid),
+            comment_count: data.comment_count,
         }
     }
 }
@@ -37,6 +54,9 @@ impl From<ArticleData> for ArticlePreviewResponse {
             uuid: ArticleUuid(data.article.uuid),
             author: data.user.into(),
             title: data.article.title,
+            slug: data.article.slug,
+            excerpt: markdown::excerpt(&data.article.body, EXCERPT_LENGTH),
+            tags: data.tags,
             publish_date: data.article.publish_date,
         }
     }
@@ -66,7 +86,8 @@ impl From<NewArticleRequest> for NewArticle {
 
 const SUFFIX_LEN: usize = 6;
 
-fn slugify(title: &str) -> String {
+/// Converts the title into a url-friendly slug, with a random suffix to keep it unique.
+pub(crate) fn slugify(title: &str) -> String {
     // if cfg!(feature = "random_suffix") {
     format!("{}-{}", slug::slugify(title), generate_suffix(SUFFIX_LEN))
     // } else {
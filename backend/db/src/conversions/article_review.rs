@@ -0,0 +1,18 @@
+use crate::article_review::*;
+use wire::article::*;
+
+use identifiers::article::ArticleUuid;
+use identifiers::article_review::ArticleReviewUuid;
+
+impl From<ReviewData> for ArticleReviewResponse {
+    fn from(data: ReviewData) -> ArticleReviewResponse {
+        ArticleReviewResponse {
+            uuid: ArticleReviewUuid(data.review.uuid),
+            article_uuid: ArticleUuid(data.review.article_uuid),
+            reviewer: data.reviewer.into(),
+            decision: ReviewDecision::from(data.review.decision),
+            comment: data.review.comment,
+            created_date: data.review.created_date,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::article_revision::*;
+use wire::article::*;
+
+use identifiers::article::ArticleUuid;
+use identifiers::article_revision::ArticleRevisionUuid;
+
+impl From<ArticleRevision> for ArticleRevisionResponse {
+    fn from(revision: ArticleRevision) -> ArticleRevisionResponse {
+        ArticleRevisionResponse {
+            uuid: ArticleRevisionUuid(revision.uuid),
+            article_uuid: ArticleUuid(revision.article_uuid),
+            title: revision.title,
+            body: revision.body,
+            autosave: revision.autosave,
+            created_date: revision.created_date,
+        }
+    }
+}
+
+impl From<DiffLine> for DiffLineResponse {
+    fn from(line: DiffLine) -> DiffLineResponse {
+        match line {
+            DiffLine::Unchanged(text) => DiffLineResponse::Unchanged(text),
+            DiffLine::Added(text) => DiffLineResponse::Added(text),
+            DiffLine::Removed(text) => DiffLineResponse::Removed(text),
+        }
+    }
+}
+
+impl From<RevisionDiff> for RevisionDiffResponse {
+    fn from(diff: RevisionDiff) -> RevisionDiffResponse {
+        RevisionDiffResponse {
+            from: diff.from.into(),
+            to: diff.to.into(),
+            title: diff.title.into_iter().map(DiffLineResponse::from).collect(),
+            body: diff.body.into_iter().map(DiffLineResponse::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::article_view::*;
+use wire::article::*;
+
+use identifiers::article::ArticleUuid;
+
+impl From<ArticleDailyViews> for DailyViewsResponse {
+    fn from(day: ArticleDailyViews) -> DailyViewsResponse {
+        DailyViewsResponse {
+            date: day.view_date,
+            views: i64::from(day.views),
+        }
+    }
+}
+
+impl From<ArticleStats> for ArticleStatsResponse {
+    fn from(stats: ArticleStats) -> ArticleStatsResponse {
+        ArticleStatsResponse {
+            article_uuid: ArticleUuid(stats.article_uuid),
+            total_views: stats.total_views,
+            daily_views: stats.daily_views.into_iter().map(DailyViewsResponse::from).collect(),
+        }
+    }
+}
+
+impl From<MostReadArticle> for MostReadArticleResponse {
+    fn from(most_read: MostReadArticle) -> MostReadArticleResponse {
+        MostReadArticleResponse {
+            article: most_read.data.into(),
+            views: most_read.views,
+        }
+    }
+}
@@ -8,6 +8,7 @@ impl From<Forum> for ForumResponse {
             uuid: ForumUuid(forum.uuid),
             title: forum.title,
             description: forum.description,
+            position: forum.position,
         }
     }
 }
@@ -20,3 +21,12 @@ impl From<NewForumRequest> for NewForum {
         }
     }
 }
+
+impl From<EditForumRequest> for ForumChangeset {
+    fn from(request: EditForumRequest) -> ForumChangeset {
+        ForumChangeset {
+            title: request.title,
+            description: request.description,
+        }
+    }
+}
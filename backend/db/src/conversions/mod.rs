@@ -6,6 +6,10 @@
 
 pub mod user;
 pub mod article;
+pub mod article_revision;
+pub mod article_review;
+pub mod article_view;
+pub mod tag;
 pub mod forum;
 pub mod thread;
 pub mod post;
@@ -14,3 +18,14 @@ pub mod question;
 pub mod answer;
 pub mod chat;
 pub mod message;
+pub mod notification;
+pub mod search;
+pub mod subscription;
+pub mod poll;
+pub mod upload;
+
+
+/// Wraps data that is being converted for a moderator.
+/// Conversions from the wrapped type may include information that is otherwise withheld from users,
+/// such as the content of censored posts.
+pub struct ModeratorView<T>(pub T);
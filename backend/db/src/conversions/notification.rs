@@ -0,0 +1,41 @@
+use crate::notification::*;
+use wire::notification::*;
+use wire::subscription::DigestResponse;
+use identifiers::notification::NotificationUuid;
+use identifiers::post::PostUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::forum::ForumUuid;
+
+/// The number of characters of the post shown in notifications.
+const EXCERPT_LENGTH: usize = 140;
+
+impl From<Digest> for DigestResponse {
+    fn from(digest: Digest) -> DigestResponse {
+        DigestResponse {
+            user: digest.user.into(),
+            notifications: digest.notifications.into_iter().map(NotificationResponse::from).collect(),
+        }
+    }
+}
+
+impl From<NotificationData> for NotificationResponse {
+    fn from(data: NotificationData) -> NotificationResponse {
+        NotificationResponse {
+            uuid: NotificationUuid(data.notification.uuid),
+            kind: NotificationKind::from(data.notification.kind),
+            actor: data.actor.into(),
+            post_uuid: PostUuid(data.notification.post_uuid),
+            thread_uuid: ThreadUuid(data.notification.thread_uuid),
+            forum_uuid: ForumUuid(data.thread.forum_uuid),
+            thread_title: data.thread.title,
+            // Censored posts don't have their content shown.
+            excerpt: if data.post.censored {
+                String::new()
+            } else {
+                markdown::excerpt(&data.post.content, EXCERPT_LENGTH)
+            },
+            created_date: data.notification.created_date,
+            read: data.notification.read,
+        }
+    }
+}
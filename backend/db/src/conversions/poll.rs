@@ -0,0 +1,44 @@
+use crate::poll::*;
+use wire::poll::*;
+use identifiers::poll::PollUuid;
+use identifiers::poll_option::PollOptionUuid;
+use identifiers::thread::ThreadUuid;
+
+impl From<NewPollRequest> for NewPollData {
+    fn from(request: NewPollRequest) -> NewPollData {
+        NewPollData {
+            question: request.question,
+            options: request.options,
+            multiple_choice: request.multiple_choice,
+            close_date: request.close_date,
+            hide_results_until_closed: request.hide_results_until_closed,
+        }
+    }
+}
+
+impl From<PollOptionData> for PollOptionResponse {
+    fn from(data: PollOptionData) -> PollOptionResponse {
+        PollOptionResponse {
+            uuid: PollOptionUuid(data.option.uuid),
+            content: data.option.content,
+            votes: data.votes,
+            voted: data.voted,
+        }
+    }
+}
+
+impl From<PollData> for PollResponse {
+    fn from(data: PollData) -> PollResponse {
+        PollResponse {
+            uuid: PollUuid(data.poll.uuid),
+            thread_uuid: ThreadUuid(data.poll.thread_uuid),
+            question: data.poll.question,
+            multiple_choice: data.poll.multiple_choice,
+            close_date: data.poll.close_date,
+            closed: data.closed,
+            hide_results_until_closed: data.poll.hide_results_until_closed,
+            results_visible: data.results_visible,
+            options: data.options.into_iter().map(PollOptionResponse::from).collect(),
+        }
+    }
+}
@@ -3,6 +3,11 @@ use wire::post::*;
 use chrono::Utc;
 //use thread::Thread;
 use identifiers::post::PostUuid;
+use identifiers::user::UserUuid;
+use crate::conversions::ModeratorView;
+use crate::reaction::ReactionCounts;
+use wire::post::VoteDirection as WireVoteDirection;
+use crate::post::VoteDirection;
 
 
 impl From<NewPostRequest> for NewPost {
@@ -35,22 +40,47 @@ impl From<EditPostRequest> for EditPostChangeset {
 
 impl From<ChildlessPostData> for PostResponse {
     fn from(data: ChildlessPostData) -> PostResponse {
+        PostResponse::from(PostData::from(data))
+    }
+}
+
+impl From<ModeratorView<ChildlessPostData>> for PostResponse {
+    fn from(data: ModeratorView<ChildlessPostData>) -> PostResponse {
+        PostResponse::from(ModeratorView(PostData::from(data.0)))
+    }
+}
+
+
+/// Censored posts have their content removed.
+impl From<PostData> for PostResponse {
+    fn from(data: PostData) -> PostResponse {
+        let content = if data.post.censored {
+            String::new()
+        } else {
+            data.post.content
+        };
         PostResponse {
             uuid: PostUuid(data.post.uuid),
             author: data.user.into(),
             created_date: data.post.created_date,
             modified_date: data.post.modified_date,
-            content: data.post.content,
+            content,
             censored: data.post.censored,
-            children: vec![],
+            censor_reason: data.post.censor_reason,
+            score: data.reactions.score(),
+            reactions: data.reactions.into(),
+            children: data.children
+                .into_iter()
+                .map(PostResponse::from)
+                .collect(),
         }
     }
 }
 
-
-
-impl From<PostData> for PostResponse {
-    fn from(data: PostData) -> PostResponse {
+/// Moderators can see the content of censored posts.
+impl From<ModeratorView<PostData>> for PostResponse {
+    fn from(data: ModeratorView<PostData>) -> PostResponse {
+        let data: PostData = data.0;
         PostResponse {
             uuid: PostUuid(data.post.uuid),
             author: data.user.into(),
@@ -58,12 +88,66 @@ impl From<PostData> for PostResponse {
             modified_date: data.post.modified_date,
             content: data.post.content,
             censored: data.post.censored,
+            censor_reason: data.post.censor_reason,
+            score: data.reactions.score(),
+            reactions: data.reactions.into(),
             children: data.children
                 .into_iter()
-                .map(PostResponse::from)
+                .map(|child| PostResponse::from(ModeratorView(child)))
                 .collect(),
         }
     }
 }
 
+impl From<ReactionCounts> for Vec<ReactionResponse> {
+    fn from(counts: ReactionCounts) -> Vec<ReactionResponse> {
+        counts.reactions
+            .into_iter()
+            .map(|count| ReactionResponse {
+                reaction: count.reaction,
+                count: count.count,
+                user_reacted: count.user_reacted,
+            })
+            .collect()
+    }
+}
+
+impl From<WireVoteDirection> for VoteDirection {
+    fn from(direction: WireVoteDirection) -> VoteDirection {
+        match direction {
+            WireVoteDirection::Up => VoteDirection::Up,
+            WireVoteDirection::Down => VoteDirection::Down,
+        }
+    }
+}
+
+impl From<ReactionCounts> for VoteCountsResponse {
+    fn from(counts: ReactionCounts) -> VoteCountsResponse {
+        let user_vote = if counts.user_voted_up() {
+            Some(WireVoteDirection::Up)
+        } else if counts.user_voted_down() {
+            Some(WireVoteDirection::Down)
+        } else {
+            None
+        };
+        VoteCountsResponse {
+            up: counts.up(),
+            down: counts.down(),
+            score: counts.score(),
+            user_vote,
+        }
+    }
+}
 
+impl From<PostCensorAction> for PostCensorActionResponse {
+    fn from(action: PostCensorAction) -> PostCensorActionResponse {
+        PostCensorActionResponse {
+            post_uuid: PostUuid(action.post_uuid),
+            moderator_uuid: action.moderator_uuid.map(UserUuid),
+            censored: action.censored,
+            reason: action.reason,
+            content: action.content,
+            created_date: action.created_date,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::search::*;
+use wire::forum::*;
+use identifiers::forum::ForumUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::post::PostUuid;
+use identifiers::user::UserUuid;
+
+impl From<ForumSearchQuery> for ForumSearch {
+    fn from(query: ForumSearchQuery) -> ForumSearch {
+        ForumSearch {
+            query: query.q,
+            forum_uuid: query.forum.map(ForumUuid),
+            author_uuid: query.author.map(UserUuid),
+            before: query.before,
+            after: query.after,
+        }
+    }
+}
+
+impl From<SearchHitData> for ForumSearchResultResponse {
+    fn from(data: SearchHitData) -> ForumSearchResultResponse {
+        ForumSearchResultResponse {
+            forum_uuid: ForumUuid(data.hit.forum_uuid),
+            thread_uuid: ThreadUuid(data.hit.thread_uuid),
+            post_uuid: PostUuid(data.hit.post_uuid),
+            title: data.hit.title,
+            snippet: data.hit.snippet,
+            author: data.user.into(),
+            created_date: data.hit.created_date,
+            rank: data.hit.rank,
+        }
+    }
+}
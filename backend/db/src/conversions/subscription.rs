@@ -0,0 +1,16 @@
+use crate::subscription::Subscription;
+use wire::subscription::SubscriptionResponse;
+use identifiers::subscription::SubscriptionUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::forum::ForumUuid;
+
+impl From<Subscription> for SubscriptionResponse {
+    fn from(subscription: Subscription) -> SubscriptionResponse {
+        SubscriptionResponse {
+            uuid: SubscriptionUuid(subscription.uuid),
+            thread_uuid: subscription.thread_uuid.map(ThreadUuid),
+            forum_uuid: subscription.forum_uuid.map(ForumUuid),
+            created_date: subscription.created_date,
+        }
+    }
+}
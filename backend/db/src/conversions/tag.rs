@@ -0,0 +1,11 @@
+use crate::tag::TagCount;
+use wire::article::TagCountResponse;
+
+impl From<TagCount> for TagCountResponse {
+    fn from(count: TagCount) -> TagCountResponse {
+        TagCountResponse {
+            name: count.name,
+            article_count: count.article_count,
+        }
+    }
+}
@@ -3,16 +3,23 @@ use wire::thread::*;
 use chrono::Utc;
 use identifiers::thread::ThreadUuid;
 use identifiers::forum::ForumUuid;
+use identifiers::post::PostUuid;
+use wire::post::PostResponse;
+use wire::user::UserResponse;
+use wire::poll::PollResponse;
+use crate::conversions::ModeratorView;
 
 impl From<NewThreadRequest> for NewThread {
     fn from(request: NewThreadRequest) -> NewThread {
+        let now = Utc::now().naive_utc();
         NewThread {
             forum_uuid: request.forum_uuid.0,
             author_uuid: request.author_uuid.0,
-            created_date: Utc::now().naive_utc(),
+            created_date: now,
             locked: false,
             archived: false,
             title: request.title,
+            last_post_date: now,
         }
     }
 }
@@ -29,6 +36,29 @@ impl From<ThreadData> for ThreadResponse {
             posts: data.post.into(),
             created_date: data.thread.created_date,
             locked: data.thread.locked,
+            last_post_date: data.thread.last_post_date,
+            last_post_author: data.last_post_author.map(UserResponse::from),
+            reply_count: data.thread.reply_count,
+            poll: data.poll.map(PollResponse::from),
+        }
+    }
+}
+
+impl From<ModeratorView<ThreadData>> for ThreadResponse {
+    fn from(data: ModeratorView<ThreadData>) -> ThreadResponse {
+        let data: ThreadData = data.0;
+        ThreadResponse {
+            uuid: ThreadUuid(data.thread.uuid),
+            forum_uuid: ForumUuid(data.thread.forum_uuid),
+            title: data.thread.title,
+            author: data.user.into(),
+            posts: PostResponse::from(ModeratorView(data.post)),
+            created_date: data.thread.created_date,
+            locked: data.thread.locked,
+            last_post_date: data.thread.last_post_date,
+            last_post_author: data.last_post_author.map(UserResponse::from),
+            reply_count: data.thread.reply_count,
+            poll: data.poll.map(PollResponse::from),
         }
     }
 }
@@ -43,6 +73,21 @@ impl From<MinimalThreadData> for MinimalThreadResponse {
             author: data.user.into(),
             created_date: data.thread.created_date,
             locked: data.thread.locked,
+            last_post_date: data.thread.last_post_date,
+            last_post_author: data.last_post_author.map(UserResponse::from),
+            reply_count: data.thread.reply_count,
+            pinned_position: data.thread.pinned_position,
+            unread_count: data.unread_count,
+            first_unread_post_uuid: data.first_unread_post_uuid.map(PostUuid),
+        }
+    }
+}
+
+impl From<ThreadListOrder> for ThreadOrder {
+    fn from(order: ThreadListOrder) -> ThreadOrder {
+        match order {
+            ThreadListOrder::Created => ThreadOrder::Created,
+            ThreadListOrder::LastActivity => ThreadOrder::LastActivity,
         }
     }
 }
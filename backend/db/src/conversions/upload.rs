@@ -0,0 +1,37 @@
+use crate::upload::{Upload, UPLOADS_PATH};
+use wire::upload::UploadResponse;
+use identifiers::upload::UploadUuid;
+use identifiers::user::UserUuid;
+
+impl From<Upload> for UploadResponse {
+    fn from(upload: Upload) -> UploadResponse {
+        let url: String = format!("/{}/{}", UPLOADS_PATH, upload.file_path());
+        let thumbnail_url: Option<String> = if upload.has_thumbnail {
+            Some(format!("/{}/{}", UPLOADS_PATH, Upload::thumbnail_path(&upload.content_hash)))
+        } else {
+            None
+        };
+        // Brackets in the name would end the link text early.
+        let text: String = upload.file_name
+            .chars()
+            .filter(|c| !['[', ']'].contains(c))
+            .collect();
+        let markdown: String = if upload.is_image() {
+            format!("![{}]({})", text, url)
+        } else {
+            format!("[{}]({})", text, url)
+        };
+
+        UploadResponse {
+            uuid: UploadUuid(upload.uuid),
+            owner_uuid: UserUuid(upload.owner_uuid),
+            file_name: upload.file_name,
+            mime_type: upload.mime_type,
+            size: upload.size,
+            url,
+            thumbnail_url,
+            markdown,
+            created_date: upload.created_date,
+        }
+    }
+}
@@ -11,6 +11,7 @@ impl From<User> for UserResponse {
             user_name: user.user_name,
             display_name: user.display_name,
             uuid: UserUuid(user.uuid),
+            reputation: user.reputation,
         }
     }
 }
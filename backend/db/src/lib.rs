@@ -30,6 +30,7 @@ extern crate r2d2_diesel;
 extern crate r2d2;
 extern crate pool;
 extern crate identifiers;
+extern crate markdown;
 extern crate typename;
 #[macro_use]
 extern crate typename_derive;
@@ -56,3 +57,4 @@ pub use crate::question::Question;
 pub use crate::answer::Answer;
 pub use crate::chat::Chat;
 pub use crate::message::Message;
+pub use crate::conversions::ModeratorView;
@@ -7,6 +7,69 @@ table! {
     }
 }
 
+table! {
+    article_authors (uuid) {
+        uuid -> Uuid,
+        article_uuid -> Uuid,
+        user_uuid -> Uuid,
+    }
+}
+
+table! {
+    article_daily_views (article_uuid, view_date) {
+        article_uuid -> Uuid,
+        view_date -> Date,
+        views -> Int4,
+    }
+}
+
+table! {
+    article_reviews (uuid) {
+        uuid -> Uuid,
+        article_uuid -> Uuid,
+        reviewer_uuid -> Uuid,
+        decision -> Int4,
+        comment -> Varchar,
+        revision_uuid -> Nullable<Uuid>,
+        created_date -> Timestamp,
+    }
+}
+
+table! {
+    article_revisions (uuid) {
+        uuid -> Uuid,
+        article_uuid -> Uuid,
+        title -> Varchar,
+        body -> Text,
+        autosave -> Bool,
+        created_date -> Timestamp,
+    }
+}
+
+table! {
+    article_slugs (slug) {
+        slug -> Varchar,
+        article_uuid -> Uuid,
+        replaced_date -> Timestamp,
+    }
+}
+
+table! {
+    article_tags (uuid) {
+        uuid -> Uuid,
+        article_uuid -> Uuid,
+        tag_uuid -> Uuid,
+    }
+}
+
+table! {
+    article_viewers (article_uuid, view_date, viewer_hash) {
+        article_uuid -> Uuid,
+        view_date -> Date,
+        viewer_hash -> Varchar,
+    }
+}
+
 table! {
     articles (uuid) {
         uuid -> Uuid,
@@ -15,6 +78,12 @@ table! {
         slug -> Varchar,
         body -> Text,
         publish_date -> Nullable<Timestamp>,
+        announced_date -> Nullable<Timestamp>,
+        published_revision_uuid -> Nullable<Uuid>,
+        comments_enabled -> Bool,
+        comment_thread_uuid -> Nullable<Uuid>,
+        review_state -> Int4,
+        review_requested_date -> Nullable<Timestamp>,
     }
 }
 
@@ -39,6 +108,8 @@ table! {
         uuid -> Uuid,
         title -> Varchar,
         description -> Varchar,
+        position -> Int4,
+        article_comments -> Bool,
     }
 }
 
@@ -73,10 +144,67 @@ table! {
 }
 
 table! {
-    post_downvotes (uuid) {
+    notifications (uuid) {
+        uuid -> Uuid,
+        user_uuid -> Uuid,
+        actor_uuid -> Uuid,
+        kind -> Int4,
+        post_uuid -> Uuid,
+        thread_uuid -> Uuid,
+        created_date -> Timestamp,
+        read -> Bool,
+        digested_date -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    poll_options (uuid) {
+        uuid -> Uuid,
+        poll_uuid -> Uuid,
+        position -> Int4,
+        content -> Varchar,
+    }
+}
+
+table! {
+    polls (uuid) {
+        uuid -> Uuid,
+        thread_uuid -> Uuid,
+        question -> Varchar,
+        multiple_choice -> Bool,
+        close_date -> Nullable<Timestamp>,
+        hide_results_until_closed -> Bool,
+    }
+}
+
+table! {
+    poll_votes (uuid) {
+        uuid -> Uuid,
+        poll_uuid -> Uuid,
+        option_uuid -> Uuid,
+        user_uuid -> Uuid,
+    }
+}
+
+table! {
+    post_censor_actions (uuid) {
+        uuid -> Uuid,
+        post_uuid -> Uuid,
+        moderator_uuid -> Nullable<Uuid>,
+        censored -> Bool,
+        reason -> Varchar,
+        content -> Varchar,
+        created_date -> Timestamp,
+    }
+}
+
+table! {
+    post_reactions (uuid) {
         uuid -> Uuid,
         post_uuid -> Uuid,
         user_uuid -> Uuid,
+        reaction -> Varchar,
+        created_date -> Timestamp,
     }
 }
 
@@ -90,24 +218,45 @@ table! {
         modified_date -> Nullable<Timestamp>,
         content -> Varchar,
         censored -> Bool,
+        censor_reason -> Nullable<Varchar>,
+        censor_changed_by -> Nullable<Uuid>,
+        censor_changed_date -> Nullable<Timestamp>,
     }
 }
 
 table! {
-    post_upvotes (uuid) {
+    questions (uuid) {
+        uuid -> Uuid,
+        bucket_uuid -> Uuid,
+        author_uuid -> Uuid,
+        question_text -> Varchar,
+        on_floor -> Bool,
+    }
+}
+
+table! {
+    subscriptions (uuid) {
         uuid -> Uuid,
-        post_uuid -> Uuid,
         user_uuid -> Uuid,
+        thread_uuid -> Nullable<Uuid>,
+        forum_uuid -> Nullable<Uuid>,
+        created_date -> Timestamp,
     }
 }
 
 table! {
-    questions (uuid) {
+    tags (uuid) {
         uuid -> Uuid,
-        bucket_uuid -> Uuid,
-        author_uuid -> Uuid,
-        question_text -> Varchar,
-        on_floor -> Bool,
+        name -> Varchar,
+    }
+}
+
+table! {
+    thread_reads (uuid) {
+        uuid -> Uuid,
+        user_uuid -> Uuid,
+        thread_uuid -> Uuid,
+        last_read_date -> Timestamp,
     }
 }
 
@@ -120,6 +269,23 @@ table! {
         locked -> Bool,
         archived -> Bool,
         title -> Varchar,
+        pinned_position -> Nullable<Int4>,
+        last_post_date -> Timestamp,
+        last_post_author_uuid -> Nullable<Uuid>,
+        reply_count -> Int4,
+    }
+}
+
+table! {
+    uploads (uuid) {
+        uuid -> Uuid,
+        owner_uuid -> Uuid,
+        content_hash -> Varchar,
+        file_name -> Varchar,
+        mime_type -> Varchar,
+        size -> Int8,
+        has_thumbnail -> Bool,
+        created_date -> Timestamp,
     }
 }
 
@@ -133,11 +299,23 @@ table! {
         failed_login_count -> Int4,
         banned -> Bool,
         roles -> Array<Int4>,
+        reputation -> Int4,
     }
 }
 
 joinable!(answers -> questions (question_uuid));
 joinable!(answers -> users (author_uuid));
+joinable!(article_authors -> articles (article_uuid));
+joinable!(article_authors -> users (user_uuid));
+joinable!(article_daily_views -> articles (article_uuid));
+joinable!(article_reviews -> article_revisions (revision_uuid));
+joinable!(article_reviews -> articles (article_uuid));
+joinable!(article_reviews -> users (reviewer_uuid));
+joinable!(article_slugs -> articles (article_uuid));
+joinable!(article_tags -> articles (article_uuid));
+joinable!(article_tags -> tags (tag_uuid));
+joinable!(article_viewers -> articles (article_uuid));
+joinable!(articles -> threads (comment_thread_uuid));
 joinable!(articles -> users (author_uuid));
 joinable!(chats -> users (leader_uuid));
 joinable!(junction_bucket_users -> buckets (bucket_uuid));
@@ -146,19 +324,40 @@ joinable!(junction_chat_users -> chats (chat_uuid));
 joinable!(junction_chat_users -> users (user_uuid));
 joinable!(messages -> chats (chat_uuid));
 joinable!(messages -> users (author_uuid));
-joinable!(post_downvotes -> posts (post_uuid));
-joinable!(post_downvotes -> users (user_uuid));
-joinable!(post_upvotes -> posts (post_uuid));
-joinable!(post_upvotes -> users (user_uuid));
+joinable!(notifications -> posts (post_uuid));
+joinable!(notifications -> threads (thread_uuid));
+joinable!(notifications -> users (actor_uuid));
+joinable!(poll_options -> polls (poll_uuid));
+joinable!(polls -> threads (thread_uuid));
+joinable!(poll_votes -> poll_options (option_uuid));
+joinable!(poll_votes -> polls (poll_uuid));
+joinable!(poll_votes -> users (user_uuid));
+joinable!(post_censor_actions -> posts (post_uuid));
+joinable!(post_censor_actions -> users (moderator_uuid));
+joinable!(post_reactions -> posts (post_uuid));
+joinable!(post_reactions -> users (user_uuid));
 joinable!(posts -> threads (thread_uuid));
 joinable!(posts -> users (author_uuid));
 joinable!(questions -> buckets (bucket_uuid));
 joinable!(questions -> users (author_uuid));
+joinable!(subscriptions -> forums (forum_uuid));
+joinable!(subscriptions -> threads (thread_uuid));
+joinable!(subscriptions -> users (user_uuid));
+joinable!(thread_reads -> threads (thread_uuid));
+joinable!(thread_reads -> users (user_uuid));
 joinable!(threads -> forums (forum_uuid));
 joinable!(threads -> users (author_uuid));
+joinable!(uploads -> users (owner_uuid));
 
 allow_tables_to_appear_in_same_query!(
     answers,
+    article_authors,
+    article_daily_views,
+    article_reviews,
+    article_revisions,
+    article_slugs,
+    article_tags,
+    article_viewers,
     articles,
     buckets,
     chats,
@@ -166,10 +365,18 @@ allow_tables_to_appear_in_same_query!(
     junction_bucket_users,
     junction_chat_users,
     messages,
-    post_downvotes,
+    notifications,
+    poll_options,
+    polls,
+    poll_votes,
+    post_censor_actions,
+    post_reactions,
     posts,
-    post_upvotes,
     questions,
+    subscriptions,
+    tags,
+    thread_reads,
     threads,
+    uploads,
     users,
 );
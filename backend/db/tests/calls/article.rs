@@ -0,0 +1,448 @@
+use db::article::{Article, ArticleChangeset, ArticleData, ArticleFilter};
+use db::article_revision::{ArticleRevision, RevisionDiff, DiffLine, diff_lines, MAX_DIFF_LINES};
+use common::setup::*;
+use diesel::PgConnection;
+use error::Error;
+use identifiers::article::ArticleUuid;
+use identifiers::user::UserUuid;
+use identifiers::article_revision::ArticleRevisionUuid;
+use chrono::{Duration, Utc};
+use testing_fixtures::fixtures::article::{ArticleFixture, approve_article};
+use db::article_author::ArticleAuthor;
+use db::article_review::{ArticleReview, ReviewData};
+use wire::article::{ReviewState, ReviewDecision};
+use db::forum::{Forum, ARTICLE_COMMENTS_TITLE};
+use db::thread::Thread;
+use db::post::{Post, NewPost};
+use identifiers::thread::ThreadUuid;
+use identifiers::forum::ForumUuid;
+
+
+#[test]
+fn get_by_slug() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let data: ArticleData = Article::get_article_data_by_slug(&fixture.published_article.slug, conn)
+            .expect("Should get article");
+        assert_eq!(data.article, fixture.published_article);
+        assert_eq!(data.user.uuid, fixture.user_fixture.normal_user.uuid);
+
+        let error = Article::get_article_data_by_slug("not-a-slug", conn).expect_err("Should not find article");
+        match error {
+            Error::NotFound { .. } => {}
+            other => panic!("Expected the article to not be found, got: {:?}", other)
+        }
+    });
+}
+
+#[test]
+fn changing_title_regenerates_slug() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let original = &fixture.published_article;
+        let changeset = ArticleChangeset {
+            uuid: original.uuid,
+            title: Some("A New Title".to_string()),
+            body: None,
+        };
+        // The published article keeps its title until the draft is published.
+        let updated: Article = Article::update_article(changeset, conn).expect("Should update article");
+        assert_eq!(updated.title, original.title);
+        assert_eq!(updated.slug, original.slug);
+        approve_article(ArticleUuid(original.uuid), UserUuid(fixture.user_fixture.admin_user.uuid), conn);
+        let updated: Article = Article::publish_draft(ArticleUuid(original.uuid), conn).expect("Should publish draft");
+        assert_eq!(updated.title, "A New Title");
+        assert!(updated.slug.starts_with("a-new-title-"));
+        assert_eq!(updated.body, original.body);
+
+        // The old slug resolves to the current one, while the current slug has no history.
+        let current_slug = Article::get_current_slug(&original.slug, conn).expect("Should resolve old slug");
+        assert_eq!(current_slug, updated.slug);
+        Article::get_current_slug(&updated.slug, conn).expect_err("Current slug isn't in the history");
+
+        // Only the updated article is changed.
+        let other = Article::get_article(ArticleUuid(fixture.unpublished_article.uuid), conn).expect("Should get article");
+        assert_eq!(other, fixture.unpublished_article);
+    });
+}
+
+#[test]
+fn keeping_title_keeps_slug() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let original = &fixture.published_article;
+        let changeset = ArticleChangeset {
+            uuid: original.uuid,
+            title: Some(original.title.clone()),
+            body: Some("New body".to_string()),
+        };
+        Article::update_article(changeset, conn).expect("Should update article");
+        approve_article(ArticleUuid(original.uuid), UserUuid(fixture.user_fixture.admin_user.uuid), conn);
+        let updated: Article = Article::publish_draft(ArticleUuid(original.uuid), conn).expect("Should publish draft");
+        assert_eq!(updated.slug, original.slug);
+        assert_eq!(updated.body, "New body");
+        Article::get_current_slug(&original.slug, conn).expect_err("Slug shouldn't be in the history");
+    });
+}
+
+#[test]
+fn future_publish_date_is_embargoed() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let author = UserUuid(fixture.user_fixture.normal_user.uuid);
+        let article_uuid = ArticleUuid(fixture.unpublished_article.uuid);
+        let tomorrow = Utc::now().naive_utc() + Duration::days(1);
+        approve_article(article_uuid, UserUuid(fixture.user_fixture.admin_user.uuid), conn);
+        let scheduled: Article = Article::set_publish_date(article_uuid, Some(tomorrow), conn)
+            .expect("Should schedule article");
+
+        assert!(!scheduled.is_published());
+        let data: ArticleData = Article::get_article_data(article_uuid, conn).expect("Should get article");
+        assert!(data.is_visible_to(Some(author), false));
+        assert!(data.is_visible_to(None, true));
+        assert!(!data.is_visible_to(None, false));
+        assert!(!data.is_visible_to(Some(UserUuid(fixture.user_fixture.admin_user.uuid)), false));
+
+        let published: Vec<ArticleData> = Article::get_paginated(&ArticleFilter::default(), 1, 10, conn).expect("Should get articles");
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].article.uuid, fixture.published_article.uuid);
+
+        let unpublished: Vec<Article> = Article::get_unpublished_articles_for_user(author, conn)
+            .expect("Should get unpublished articles");
+        assert_eq!(unpublished, vec![scheduled]);
+    });
+}
+
+#[test]
+fn published_articles_get_comment_threads() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let published = &fixture.published_article;
+        let thread_uuid = published.comment_thread_uuid.expect("Publishing should open comments");
+        assert_eq!(fixture.unpublished_article.comment_thread_uuid, None);
+
+        let thread: Thread = Thread::get_thread(ThreadUuid(thread_uuid), conn).expect("Should get thread");
+        assert_eq!(thread.title, published.title);
+        assert_eq!(thread.author_uuid, published.author_uuid);
+        let forum: Forum = Forum::get_forum(ForumUuid(thread.forum_uuid), conn).expect("Should get forum");
+        assert!(forum.article_comments);
+        assert_eq!(forum.title, ARTICLE_COMMENTS_TITLE);
+
+        // Publishing another article reuses the forum, and republishing keeps the thread.
+        let article_uuid = ArticleUuid(fixture.unpublished_article.uuid);
+        approve_article(article_uuid, UserUuid(fixture.user_fixture.admin_user.uuid), conn);
+        let other: Article = Article::set_publish_status(article_uuid, true, conn).expect("Should publish article");
+        let other_thread: Thread = Thread::get_thread(ThreadUuid(other.comment_thread_uuid.unwrap()), conn)
+            .expect("Should get thread");
+        assert_eq!(other_thread.forum_uuid, forum.uuid);
+        let republished: Article = Article::set_publish_status(ArticleUuid(published.uuid), true, conn)
+            .expect("Should republish article");
+        assert_eq!(republished.comment_thread_uuid, Some(thread_uuid));
+
+        let root: Post = Post::get_root_post(ThreadUuid(thread_uuid), conn).expect("Should get root post");
+        let comment = NewPost {
+            thread_uuid,
+            author_uuid: fixture.user_fixture.admin_user.uuid,
+            parent_uuid: Some(root.uuid),
+            created_date: Utc::now().naive_utc(),
+            content: "A comment".to_string(),
+            censored: false,
+        };
+        Post::create_and_get_user(comment, conn).expect("Should comment");
+        let data: ArticleData = Article::get_article_data(ArticleUuid(published.uuid), conn).expect("Should get article");
+        assert_eq!(data.comment_count, 1);
+
+        match Thread::merge_threads(ThreadUuid(thread_uuid), ThreadUuid(other_thread.uuid), conn) {
+            Err(Error::BadRequest) => {}
+            _ => panic!("Comment threads should not be merged away")
+        }
+    });
+}
+
+#[test]
+fn disabling_comments_locks_the_thread() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let article_uuid = ArticleUuid(fixture.published_article.uuid);
+        let thread_uuid = ThreadUuid(fixture.published_article.comment_thread_uuid.unwrap());
+
+        let article: Article = Article::set_comments_enabled(article_uuid, false, conn).expect("Should disable comments");
+        assert!(!article.comments_enabled);
+        assert!(Thread::get_thread(thread_uuid, conn).expect("Should get thread").locked);
+        let article: Article = Article::set_comments_enabled(article_uuid, true, conn).expect("Should enable comments");
+        assert!(article.comments_enabled);
+        assert!(!Thread::get_thread(thread_uuid, conn).expect("Should get thread").locked);
+
+        // Articles published with comments disabled don't get a thread until they are enabled.
+        let article_uuid = ArticleUuid(fixture.unpublished_article.uuid);
+        Article::set_comments_enabled(article_uuid, false, conn).expect("Should disable comments");
+        approve_article(article_uuid, UserUuid(fixture.user_fixture.admin_user.uuid), conn);
+        let article: Article = Article::set_publish_status(article_uuid, true, conn).expect("Should publish article");
+        assert_eq!(article.comment_thread_uuid, None);
+        let article: Article = Article::set_comments_enabled(article_uuid, true, conn).expect("Should enable comments");
+        assert!(article.comment_thread_uuid.is_some());
+    });
+}
+
+#[test]
+fn unpublishing_hides_the_comment_thread() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let article_uuid = ArticleUuid(fixture.published_article.uuid);
+        let thread_uuid = ThreadUuid(fixture.published_article.comment_thread_uuid.unwrap());
+
+        Article::set_publish_status(article_uuid, false, conn).expect("Should unpublish article");
+        let thread: Thread = Thread::get_thread(thread_uuid, conn).expect("Should get thread");
+        assert!(thread.archived);
+        assert!(thread.locked);
+        // Comments can't be unlocked while the article is unpublished.
+        Article::set_comments_enabled(article_uuid, true, conn).expect("Should enable comments");
+        assert!(Thread::get_thread(thread_uuid, conn).expect("Should get thread").locked);
+
+        let republished: Article = Article::set_publish_status(article_uuid, true, conn).expect("Should republish article");
+        assert_eq!(republished.comment_thread_uuid, Some(thread_uuid.0));
+        let thread: Thread = Thread::get_thread(thread_uuid, conn).expect("Should get thread");
+        assert!(!thread.archived);
+        assert!(!thread.locked);
+    });
+}
+
+#[test]
+fn articles_must_be_approved_before_publishing() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let author = UserUuid(fixture.user_fixture.normal_user.uuid);
+        let publisher = UserUuid(fixture.user_fixture.admin_user.uuid);
+        let article_uuid = ArticleUuid(fixture.unpublished_article.uuid);
+        assert_eq!(fixture.unpublished_article.state(), ReviewState::Draft);
+        assert_eq!(fixture.published_article.state(), ReviewState::Published);
+
+        match Article::set_publish_status(article_uuid, true, conn).expect_err("Drafts shouldn't be published") {
+            Error::NotAuthorized { .. } => {}
+            other => panic!("Expected publishing to be refused, got: {:?}", other)
+        }
+        // Only articles in review can be approved.
+        ArticleReview::review(article_uuid, publisher, ReviewDecision::Approve, String::new(), conn)
+            .expect_err("Drafts shouldn't be approved");
+
+        let article: Article = Article::submit_for_review(article_uuid, conn).expect("Should submit article");
+        assert_eq!(article.state(), ReviewState::InReview);
+        match Article::submit_for_review(article_uuid, conn).expect_err("Articles in review shouldn't be submitted again") {
+            Error::BadRequest => {}
+            other => panic!("Expected the submission to be refused, got: {:?}", other)
+        }
+        let queue: Vec<ArticleData> = Article::get_review_queue(conn).expect("Should get review queue");
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].article.uuid, article_uuid.0);
+
+        // Authors can comment, but can't approve their own article.
+        ArticleReview::review(article_uuid, author, ReviewDecision::Comment, "Ready when you are".to_string(), conn)
+            .expect("Should comment");
+        ArticleReview::review(article_uuid, author, ReviewDecision::Approve, String::new(), conn)
+            .expect_err("Authors shouldn't approve their own article");
+
+        ArticleReview::review(article_uuid, publisher, ReviewDecision::RequestChanges, "Needs a better title".to_string(), conn)
+            .expect("Should request changes");
+        assert_eq!(Article::get_article(article_uuid, conn).unwrap().state(), ReviewState::Draft);
+
+        approve_article(article_uuid, publisher, conn);
+        // Changing an approved article sends it back for another review.
+        let changeset = ArticleChangeset {
+            uuid: article_uuid.0,
+            title: Some("A better title".to_string()),
+            body: None,
+        };
+        let changed: Article = Article::update_article(changeset, conn).expect("Should update article");
+        assert_eq!(changed.state(), ReviewState::Draft);
+
+        approve_article(article_uuid, publisher, conn);
+        let published: Article = Article::set_publish_status(article_uuid, true, conn).expect("Should publish article");
+        assert_eq!(published.state(), ReviewState::Published);
+        let unpublished: Article = Article::set_publish_status(article_uuid, false, conn).expect("Should unpublish article");
+        assert_eq!(unpublished.state(), ReviewState::Approved);
+
+        let reviews: Vec<ReviewData> = ArticleReview::get_reviews_for_article(article_uuid, conn).expect("Should get reviews");
+        let decisions: Vec<ReviewDecision> = reviews.iter().map(|data| ReviewDecision::from(data.review.decision)).collect();
+        assert_eq!(decisions, vec![ReviewDecision::Comment, ReviewDecision::RequestChanges, ReviewDecision::Approve, ReviewDecision::Approve]);
+        assert_eq!(reviews[0].reviewer.uuid, author.0);
+        assert!(Article::get_review_queue(conn).unwrap().is_empty());
+
+        // Approvals are of the revision that was latest at the time.
+        let latest: ArticleRevision = ArticleRevision::get_latest_revision(article_uuid, conn).expect("Should get revision");
+        assert_eq!(reviews[3].review.revision_uuid, Some(latest.uuid));
+        assert_eq!(ArticleReview::get_approved_revision_uuid(article_uuid, conn).unwrap(), Some(latest.uuid));
+        {
+            use db::schema::article_revisions::dsl;
+            use diesel::{RunQueryDsl, ExpressionMethods};
+            diesel::insert_into(dsl::article_revisions)
+                .values((
+                    dsl::article_uuid.eq(article_uuid.0),
+                    dsl::title.eq("Unreviewed"),
+                    dsl::body.eq("Unreviewed"),
+                    dsl::autosave.eq(false),
+                ))
+                .execute(conn)
+                .expect("Should add revision");
+        }
+        match Article::set_publish_status(article_uuid, true, conn).expect_err("Unapproved revisions shouldn't be published") {
+            Error::NotAuthorized { .. } => {}
+            other => panic!("Expected publishing to be refused, got: {:?}", other)
+        }
+    });
+}
+
+#[test]
+fn co_authors_share_the_article() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let author = UserUuid(fixture.user_fixture.normal_user.uuid);
+        let co_author = UserUuid(fixture.user_fixture.admin_user.uuid);
+        let article = &fixture.unpublished_article;
+        let article_uuid = ArticleUuid(article.uuid);
+        assert!(!article.is_author(co_author, conn).unwrap());
+
+        // The author is already credited, and duplicates are ignored.
+        let co_authors = ArticleAuthor::set_co_authors(article, vec![co_author, author, co_author], conn)
+            .expect("Should set co-authors");
+        assert_eq!(co_authors.len(), 1);
+        assert!(article.is_author(co_author, conn).unwrap());
+
+        let data: ArticleData = Article::get_article_data(article_uuid, conn).expect("Should get article");
+        assert_eq!(data.co_authors.len(), 1);
+        assert_eq!(data.co_authors[0].uuid, co_author.0);
+        assert!(data.is_visible_to(Some(co_author), false));
+        let unpublished: Vec<Article> = Article::get_unpublished_articles_for_user(co_author, conn)
+            .expect("Should get unpublished articles");
+        assert_eq!(unpublished.len(), 1);
+        assert_eq!(unpublished[0].uuid, article.uuid);
+
+        // Co-authors can't approve the article either.
+        Article::submit_for_review(article_uuid, conn).expect("Should submit article");
+        ArticleReview::review(article_uuid, co_author, ReviewDecision::Approve, String::new(), conn)
+            .expect_err("Co-authors shouldn't approve the article");
+
+        ArticleAuthor::set_co_authors(article, vec![], conn).expect("Should remove co-authors");
+        assert!(!article.is_author(co_author, conn).unwrap());
+    });
+}
+
+#[test]
+fn drafts_of_published_articles_wait_for_republishing() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let published = &fixture.published_article;
+        let article_uuid = ArticleUuid(published.uuid);
+        assert_eq!(published.get_draft(conn).expect("Should get draft"), None);
+
+        let changeset = ArticleChangeset {
+            uuid: published.uuid,
+            title: None,
+            body: Some("Draft body".to_string()),
+        };
+        let live: Article = Article::update_article(changeset, conn).expect("Should update article");
+        assert_eq!(live.body, published.body);
+        let draft: ArticleRevision = live.get_draft(conn)
+            .expect("Should get draft")
+            .expect("Should have a draft");
+        assert_eq!(draft.body, "Draft body");
+        assert_eq!(draft.title, published.title);
+
+        // The changes have to be reviewed before readers see them.
+        assert_eq!(live.state(), ReviewState::Draft);
+        assert!(live.is_published());
+        match Article::publish_draft(article_uuid, conn).expect_err("Unapproved drafts shouldn't be published") {
+            Error::NotAuthorized { .. } => {}
+            other => panic!("Expected publishing to be refused, got: {:?}", other)
+        }
+        Article::set_publish_status(article_uuid, true, conn).expect_err("Unapproved drafts shouldn't be republished");
+        assert_eq!(Article::get_article(article_uuid, conn).unwrap().body, published.body);
+
+        approve_article(article_uuid, UserUuid(fixture.user_fixture.admin_user.uuid), conn);
+        let live: Article = Article::publish_draft(article_uuid, conn).expect("Should publish draft");
+        assert_eq!(live.body, "Draft body");
+        assert_eq!(live.published_revision_uuid, Some(draft.uuid));
+        assert_eq!(live.state(), ReviewState::Published);
+        assert_eq!(live.get_draft(conn).expect("Should get draft"), None);
+
+        // Unpublished articles show their changes right away.
+        let changeset = ArticleChangeset {
+            uuid: fixture.unpublished_article.uuid,
+            title: None,
+            body: Some("Autosaved body".to_string()),
+        };
+        let autosave: ArticleRevision = Article::autosave(changeset, conn).expect("Should autosave");
+        assert!(autosave.autosave);
+        let unpublished: Article = Article::get_article(ArticleUuid(fixture.unpublished_article.uuid), conn)
+            .expect("Should get article");
+        assert_eq!(unpublished.body, "Autosaved body");
+        assert_eq!(unpublished.published_revision_uuid, Some(autosave.uuid));
+    });
+}
+
+#[test]
+fn restore_and_diff_revisions() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let article_uuid = ArticleUuid(fixture.unpublished_article.uuid);
+        let original: ArticleRevision = ArticleRevision::get_latest_revision(article_uuid, conn)
+            .expect("Should get revision");
+
+        let changeset = ArticleChangeset {
+            uuid: article_uuid.0,
+            title: None,
+            body: Some("Article body\nA second line".to_string()),
+        };
+        Article::update_article(changeset, conn).expect("Should update article");
+        let revisions: Vec<ArticleRevision> = ArticleRevision::get_revisions_for_article(article_uuid, conn)
+            .expect("Should get revisions");
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[1], original);
+
+        let diff: RevisionDiff = ArticleRevision::diff(ArticleRevisionUuid(original.uuid), ArticleRevisionUuid(revisions[0].uuid), conn)
+            .expect("Should diff revisions");
+        assert_eq!(diff.title, vec![DiffLine::Unchanged(original.title.clone())]);
+        assert_eq!(
+            diff.body,
+            vec![DiffLine::Unchanged("Article body".to_string()), DiffLine::Added("A second line".to_string())]
+        );
+
+        let (article, restored) = Article::restore_revision(ArticleRevisionUuid(original.uuid), conn)
+            .expect("Should restore revision");
+        assert_ne!(restored.uuid, original.uuid);
+        assert_eq!(restored.body, original.body);
+        assert_eq!(article.body, original.body);
+        let revisions: Vec<ArticleRevision> = ArticleRevision::get_revisions_for_article(article_uuid, conn)
+            .expect("Should get revisions");
+        assert_eq!(revisions.len(), 3);
+    });
+}
+
+#[test]
+fn diff_lines_finds_changes() {
+    let diff = diff_lines("a\nb\nc\nd", "a\nc\nx\nd").expect("Should diff");
+    assert_eq!(
+        diff,
+        vec![
+            DiffLine::Unchanged("a".to_string()),
+            DiffLine::Removed("b".to_string()),
+            DiffLine::Unchanged("c".to_string()),
+            DiffLine::Added("x".to_string()),
+            DiffLine::Unchanged("d".to_string()),
+        ]
+    );
+    assert_eq!(diff_lines("", "a").expect("Should diff"), vec![DiffLine::Added("a".to_string())]);
+    assert_eq!(diff_lines("a", "a").expect("Should diff"), vec![DiffLine::Unchanged("a".to_string())]);
+}
+
+#[test]
+fn diff_lines_limits_changed_lines() {
+    let numbered = |count: usize, prefix: &str| -> String {
+        (0..count).map(|i| format!("{}{}", prefix, i)).collect::<Vec<String>>().join("\n")
+    };
+
+    // Long texts with a small change only diff the lines between the unchanged start and end.
+    let long: String = numbered(MAX_DIFF_LINES * 10, "line ");
+    let edited: String = long.replacen("line 5000\n", "edited\n", 1);
+    let diff = diff_lines(&long, &edited).expect("Should diff long texts with few changes");
+    assert_eq!(diff.len(), MAX_DIFF_LINES * 10 + 1);
+    assert!(diff.contains(&DiffLine::Removed("line 5000".to_string())));
+    assert!(diff.contains(&DiffLine::Added("edited".to_string())));
+
+    let diff = diff_lines(&numbered(MAX_DIFF_LINES, "old "), &numbered(MAX_DIFF_LINES, "new "))
+        .expect("Should diff up to the limit");
+    assert_eq!(diff.len(), MAX_DIFF_LINES * 2);
+
+    match diff_lines(&numbered(MAX_DIFF_LINES + 1, "old "), &numbered(MAX_DIFF_LINES + 1, "new ")) {
+        Err(Error::BadRequest) => {}
+        _ => panic!("Diffs with too many changed lines should be rejected")
+    }
+}
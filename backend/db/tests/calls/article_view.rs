@@ -0,0 +1,69 @@
+use db::article::Article;
+use db::article_view::{ArticleDailyViews, ArticleStats, MostReadArticle};
+use common::setup::*;
+use diesel::PgConnection;
+use identifiers::article::ArticleUuid;
+use chrono::{Duration, NaiveDate, Utc};
+use testing_fixtures::fixtures::article::ArticleFixture;
+
+
+#[test]
+fn viewers_are_counted_once_a_day() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let article_uuid = ArticleUuid(fixture.published_article.uuid);
+        let today: NaiveDate = Utc::now().naive_utc().date();
+        let yesterday: NaiveDate = today.pred();
+
+        assert!(ArticleDailyViews::record_view(article_uuid, "a".to_string(), yesterday, conn).expect("Should record view"));
+        assert!(ArticleDailyViews::record_view(article_uuid, "a".to_string(), today, conn).expect("Should record view"));
+        assert!(!ArticleDailyViews::record_view(article_uuid, "a".to_string(), today, conn).expect("Should record view"));
+        assert!(ArticleDailyViews::record_view(article_uuid, "b".to_string(), today, conn).expect("Should record view"));
+
+        let first: NaiveDate = today - Duration::days(2);
+        let stats: ArticleStats = ArticleDailyViews::get_stats(article_uuid, first, today, conn).expect("Should get stats");
+        assert_eq!(stats.total_views, 3);
+        let views: Vec<(NaiveDate, i32)> = stats.daily_views
+            .into_iter()
+            .map(|day| (day.view_date, day.views))
+            .collect();
+        assert_eq!(views, vec![(first, 0), (yesterday, 1), (today, 2)]);
+
+        // An article that was never viewed still gets every day.
+        let stats: ArticleStats = ArticleDailyViews::get_stats(ArticleUuid(fixture.unpublished_article.uuid), first, today, conn)
+            .expect("Should get stats");
+        assert_eq!(stats.total_views, 0);
+        assert_eq!(stats.daily_views.len(), 3);
+
+        // Only yesterday's hash is purged, and the counts are kept.
+        assert_eq!(ArticleDailyViews::purge_old_viewers(today, conn).expect("Should purge viewers"), 1);
+        assert_eq!(ArticleDailyViews::purge_old_viewers(today, conn).expect("Should purge viewers"), 0);
+        assert!(!ArticleDailyViews::record_view(article_uuid, "b".to_string(), today, conn).expect("Should record view"));
+        let stats: ArticleStats = ArticleDailyViews::get_stats(article_uuid, first, today, conn).expect("Should get stats");
+        assert_eq!(stats.total_views, 3);
+    });
+}
+
+#[test]
+fn most_read_articles_are_ranked_by_recent_views() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let published = ArticleUuid(fixture.published_article.uuid);
+        let unpublished = ArticleUuid(fixture.unpublished_article.uuid);
+        let now = Utc::now().naive_utc();
+        let today: NaiveDate = now.date();
+        let week_ago: NaiveDate = today - Duration::days(6);
+
+        ArticleDailyViews::record_view(published, "a".to_string(), today, conn).expect("Should record view");
+        ArticleDailyViews::record_view(published, "b".to_string(), today - Duration::days(1), conn).expect("Should record view");
+        // Views from before the week aren't counted.
+        ArticleDailyViews::record_view(published, "c".to_string(), today - Duration::days(30), conn).expect("Should record view");
+        // Unpublished articles aren't listed, however many views they had.
+        for viewer in &["a", "b", "c"] {
+            ArticleDailyViews::record_view(unpublished, viewer.to_string(), today, conn).expect("Should record view");
+        }
+
+        let most_read: Vec<MostReadArticle> = Article::get_most_read(week_ago, now, 10, conn).expect("Should get most read");
+        assert_eq!(most_read.len(), 1);
+        assert_eq!(most_read[0].data.article.uuid, published.0);
+        assert_eq!(most_read[0].views, 2);
+    });
+}
@@ -1,8 +1,25 @@
-use db::thread::{Thread, MinimalThreadData};
-use db::post::{Post, NewPost, EditPostChangeset, PostData, PostVote, Vote, VoteCounts};
+use db::thread::{Thread, MinimalThreadData, ThreadOrder};
+use db::forum::{Forum, NewForum, ForumChangeset};
+use db::post::{Post, NewPost, EditPostChangeset, PostData, ChildlessPostData, PostVote, Vote, VoteDirection, PostCensorAction};
+use db::reaction::{ReactionCounts, ReactionSet};
+use db::user::User;
+use db::ModeratorView;
+use db::search::{ForumSearch, SearchHitData};
+use db::notification::{Notification, NotificationData, Digest, parse_mentions};
+use db::subscription::Subscription;
+use db::poll::{Poll, NewPollData, PollData};
+use db::thread::ThreadData;
+use db::NewThread;
+use identifiers::poll_option::PollOptionUuid;
+use identifiers::poll::PollUuid;
+use wire::notification::NotificationKind;
+use identifiers::notification::NotificationUuid;
+use error::Error;
+use db::thread_read::{ThreadRead, UnreadPosts};
+use wire::post::PostResponse;
 use common::setup::*;
 use diesel::PgConnection;
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use identifiers::forum::ForumUuid;
 use identifiers::thread::ThreadUuid;
 use identifiers::post::PostUuid;
@@ -77,6 +94,39 @@ fn archive() {
     })
 }
 
+#[test]
+fn unarchive() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let forum_uuid = ForumUuid(fixture.forum.uuid);
+        let thread_uuid = ThreadUuid(fixture.populated_thread.uuid);
+
+        Thread::archive_thread(thread_uuid, conn).expect("Archive thread");
+
+        let archived: Vec<MinimalThreadData> = Thread::get_archived_paginated(forum_uuid, 1, 10, conn)
+            .expect("get archived threads");
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].thread.uuid, thread_uuid.0);
+
+        // Only moderators can see archived threads.
+        match Thread::get_full_thread(thread_uuid, None, false, conn) {
+            Err(Error::NotAuthorized { .. }) => {}
+            _ => panic!("Should not be able to view an archived thread")
+        }
+        Thread::get_full_thread(thread_uuid, None, true, conn).expect("moderators should see archived threads");
+
+        let thread: MinimalThreadData = Thread::unarchive_thread(thread_uuid, conn).expect("Unarchive thread");
+        assert!(!thread.thread.archived);
+        Thread::get_full_thread(thread_uuid, None, false, conn).expect("should see unarchived thread");
+
+        let archived: Vec<MinimalThreadData> = Thread::get_archived_paginated(forum_uuid, 1, 10, conn)
+            .expect("get archived threads");
+        assert!(archived.is_empty());
+        let threads: Vec<MinimalThreadData> = Thread::get_paginated(forum_uuid, 1, 10, conn)
+            .expect("get threads in forum");
+        assert_eq!(threads.len(), 2);
+    });
+}
+
 #[test]
 fn lock() {
     setup(|fixture: &ForumFixture, conn: &PgConnection| {
@@ -130,6 +180,720 @@ fn get_post_and_children() {
     });
 }
 
+#[test]
+fn censor_and_uncensor() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let post_uuid = PostUuid(fixture.post_2.uuid);
+        let moderator_uuid = UserUuid(fixture.user_fixture.admin_user.uuid);
+
+        let censored: ChildlessPostData = Post::censor_post(post_uuid, "Spam".to_string(), moderator_uuid, conn)
+            .expect("should censor post");
+        assert!(censored.post.censored);
+        assert_eq!(censored.post.censor_reason, Some("Spam".to_string()));
+        assert_eq!(censored.post.censor_changed_by, Some(moderator_uuid.0));
+        assert!(censored.post.censor_changed_date.is_some());
+
+        // Normal users can't see the content, but moderators can.
+        let response: PostResponse = censored.clone().into();
+        assert_eq!(response.content, "");
+        let response: PostResponse = ModeratorView(censored).into();
+        assert_eq!(response.content, fixture.post_2.content);
+
+        let uncensored: ChildlessPostData = Post::uncensor_post(post_uuid, "Not spam".to_string(), moderator_uuid, conn)
+            .expect("should uncensor post");
+        assert!(!uncensored.post.censored);
+        assert_eq!(uncensored.post.censor_reason, Some("Not spam".to_string()));
+
+        let response: PostResponse = uncensored.into();
+        assert_eq!(response.content, fixture.post_2.content);
+
+        // Both changes are kept, even though the post only records the latest one.
+        let history: Vec<PostCensorAction> = Post::get_censor_history(post_uuid, conn).expect("should get censor history");
+        let changes: Vec<(bool, &str)> = history.iter().map(|action| (action.censored, action.reason.as_str())).collect();
+        assert_eq!(changes, vec![(true, "Spam"), (false, "Not spam")]);
+        assert!(history.iter().all(|action| action.moderator_uuid == Some(moderator_uuid.0)));
+        assert!(history.iter().all(|action| action.content == fixture.post_2.content));
+    });
+}
+
+#[test]
+fn pinned_threads_are_listed_first() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let forum_uuid = ForumUuid(fixture.forum.uuid);
+        let threads: Vec<MinimalThreadData> = Thread::get_paginated(forum_uuid, 1, 10, conn)
+            .expect("get threads in forum");
+        assert_eq!(threads[0].thread.uuid, fixture.populated_thread.uuid);
+
+        let pinned: MinimalThreadData = Thread::set_pinned_position(ThreadUuid(fixture.empty_thread.uuid), Some(0), conn)
+            .expect("should pin thread");
+        assert_eq!(pinned.thread.pinned_position, Some(0));
+
+        let threads: Vec<MinimalThreadData> = Thread::get_paginated(forum_uuid, 1, 10, conn)
+            .expect("get threads in forum");
+        assert_eq!(threads[0].thread.uuid, fixture.empty_thread.uuid);
+        assert_eq!(threads[1].thread.uuid, fixture.populated_thread.uuid);
+
+        // The lower position comes first.
+        Thread::set_pinned_position(ThreadUuid(fixture.populated_thread.uuid), Some(-1), conn)
+            .expect("should pin thread");
+        let threads: Vec<MinimalThreadData> = Thread::get_paginated(forum_uuid, 1, 10, conn)
+            .expect("get threads in forum");
+        assert_eq!(threads[0].thread.uuid, fixture.populated_thread.uuid);
+
+        let unpinned: MinimalThreadData = Thread::set_pinned_position(ThreadUuid(fixture.populated_thread.uuid), None, conn)
+            .expect("should unpin thread");
+        assert_eq!(unpinned.thread.pinned_position, None);
+        let threads: Vec<MinimalThreadData> = Thread::get_paginated(forum_uuid, 1, 10, conn)
+            .expect("get threads in forum");
+        assert_eq!(threads[0].thread.uuid, fixture.empty_thread.uuid);
+    });
+}
+
+#[test]
+fn thread_activity() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let forum_uuid = ForumUuid(fixture.forum.uuid);
+
+        // The empty thread gets an original post and then a reply from the admin.
+        let new_post: NewPost = NewPost {
+            thread_uuid: fixture.empty_thread.uuid,
+            author_uuid: fixture.user_fixture.normal_user.uuid,
+            parent_uuid: None,
+            created_date: Utc::now().naive_utc(),
+            content: "Original post".to_string(),
+            censored: false,
+        };
+        let original_post: ChildlessPostData = Post::create_and_get_user(new_post.clone(), conn)
+            .expect("should create post");
+        let thread: Thread = Thread::get_thread(ThreadUuid(fixture.empty_thread.uuid), conn).expect("should get thread");
+        assert_eq!(thread.reply_count, 0);
+        assert_eq!(thread.last_post_date, original_post.post.created_date);
+
+        let reply: NewPost = NewPost {
+            author_uuid: fixture.user_fixture.admin_user.uuid,
+            parent_uuid: Some(original_post.post.uuid),
+            created_date: Utc::now().naive_utc(),
+            content: "Reply".to_string(),
+            ..new_post
+        };
+        let reply: ChildlessPostData = Post::create_and_get_user(reply, conn)
+            .expect("should create post");
+        let thread: Thread = Thread::get_thread(ThreadUuid(fixture.empty_thread.uuid), conn).expect("should get thread");
+        assert_eq!(thread.reply_count, 1);
+        assert_eq!(thread.last_post_date, reply.post.created_date);
+        assert_eq!(thread.last_post_author_uuid, Some(fixture.user_fixture.admin_user.uuid));
+
+        // A reply written before the latest one, but saved after it, doesn't replace it.
+        let late_reply: NewPost = NewPost {
+            thread_uuid: fixture.empty_thread.uuid,
+            author_uuid: fixture.user_fixture.normal_user.uuid,
+            parent_uuid: Some(original_post.post.uuid),
+            created_date: reply.post.created_date - Duration::minutes(1),
+            content: "Late reply".to_string(),
+            censored: false,
+        };
+        Post::create_and_get_user(late_reply, conn).expect("should create post");
+        let thread: Thread = Thread::get_thread(ThreadUuid(fixture.empty_thread.uuid), conn).expect("should get thread");
+        assert_eq!(thread.reply_count, 2);
+        assert_eq!(thread.last_post_date, reply.post.created_date);
+        assert_eq!(thread.last_post_author_uuid, Some(fixture.user_fixture.admin_user.uuid));
+
+        // The most recently active thread comes first.
+        let threads: Vec<MinimalThreadData> = Thread::get_paginated_ordered(forum_uuid, ThreadOrder::LastActivity, None, 1, 10, conn)
+            .expect("get threads in forum");
+        assert_eq!(threads[0].thread.uuid, fixture.empty_thread.uuid);
+        assert_eq!(threads[0].last_post_author.as_ref().map(|x| x.uuid), Some(fixture.user_fixture.admin_user.uuid));
+        assert_eq!(threads[1].thread.uuid, fixture.populated_thread.uuid);
+    });
+}
+
+#[test]
+fn thread_reads_only_move_forward() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let user_uuid = UserUuid(fixture.user_fixture.admin_user.uuid);
+        let thread_uuid = ThreadUuid(fixture.populated_thread.uuid);
+        let now = Utc::now().naive_utc();
+
+        let read: ThreadRead = ThreadRead::mark_read(user_uuid, thread_uuid, now, conn).expect("should mark read");
+        // Reading an older copy of the thread doesn't make newer posts unread again.
+        let reread: ThreadRead = ThreadRead::mark_read(user_uuid, thread_uuid, now - Duration::minutes(5), conn).expect("should mark read");
+        assert_eq!(reread.last_read_date, read.last_read_date);
+    });
+}
+
+#[test]
+fn unread_posts() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let forum_uuid = ForumUuid(fixture.forum.uuid);
+        let thread_uuid = ThreadUuid(fixture.populated_thread.uuid);
+        let admin_uuid = UserUuid(fixture.user_fixture.admin_user.uuid);
+
+        let find_thread = |threads: Vec<MinimalThreadData>| -> MinimalThreadData {
+            threads
+                .into_iter()
+                .find(|x| x.thread.uuid == fixture.populated_thread.uuid)
+                .expect("thread should be listed")
+        };
+
+        // Anonymous listings don't include unread information.
+        let thread = find_thread(Thread::get_paginated_ordered(forum_uuid, ThreadOrder::Created, None, 1, 10, conn).expect("get threads"));
+        assert_eq!(thread.unread_count, None);
+
+        // Nothing has been read yet.
+        let thread = find_thread(Thread::get_paginated_ordered(forum_uuid, ThreadOrder::Created, Some(admin_uuid), 1, 10, conn).expect("get threads"));
+        assert_eq!(thread.unread_count, Some(3));
+        assert_eq!(thread.first_unread_post_uuid, Some(fixture.post_1.uuid));
+
+        let new_post: NewPost = NewPost {
+            thread_uuid: fixture.populated_thread.uuid,
+            author_uuid: fixture.user_fixture.normal_user.uuid,
+            parent_uuid: Some(fixture.post_1.uuid),
+            created_date: Utc::now().naive_utc(),
+            content: "New content".to_string(),
+            censored: false,
+        };
+        Post::create_and_get_user(new_post.clone(), conn).expect("should create post");
+
+        Thread::get_full_thread(thread_uuid, Some(admin_uuid), false, conn).expect("should get thread");
+        let thread = find_thread(Thread::get_paginated_ordered(forum_uuid, ThreadOrder::Created, Some(admin_uuid), 1, 10, conn).expect("get threads"));
+        assert_eq!(thread.unread_count, Some(0));
+        assert_eq!(thread.first_unread_post_uuid, None);
+
+        // Only posts made after the thread was read are unread.
+        let reply: ChildlessPostData = Post::create_and_get_user(
+            NewPost {
+                created_date: Utc::now().naive_utc(),
+                ..new_post
+            },
+            conn
+        ).expect("should create post");
+        let thread = find_thread(Thread::get_paginated_ordered(forum_uuid, ThreadOrder::Created, Some(admin_uuid), 1, 10, conn).expect("get threads"));
+        assert_eq!(thread.unread_count, Some(1));
+        assert_eq!(thread.first_unread_post_uuid, Some(reply.post.uuid));
+
+        // Censored posts aren't counted.
+        Post::censor_post(PostUuid(reply.post.uuid), "Spam".to_string(), admin_uuid, conn).expect("should censor post");
+        let thread = find_thread(Thread::get_paginated_ordered(forum_uuid, ThreadOrder::Created, Some(admin_uuid), 1, 10, conn).expect("get threads"));
+        assert_eq!(thread.unread_count, Some(0));
+        assert_eq!(thread.first_unread_post_uuid, None);
+
+        // The author has always read their own posts.
+        let thread = find_thread(Thread::get_paginated_ordered(forum_uuid, ThreadOrder::Created, Some(UserUuid(fixture.user_fixture.normal_user.uuid)), 1, 10, conn).expect("get threads"));
+        assert_eq!(thread.unread_count, Some(0));
+    });
+}
+
+#[test]
+fn mentions_are_parsed() {
+    assert_eq!(parse_mentions("Hello @Admin and @some_user-2!"), vec!["Admin".to_string(), "some_user-2".to_string()]);
+    assert_eq!(parse_mentions("@Admin @Admin"), vec!["Admin".to_string()]);
+    assert!(parse_mentions("Email me at person@example.com").is_empty());
+    assert!(parse_mentions("Just an @ sign").is_empty());
+}
+
+#[test]
+fn reply_and_mention_notifications() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let admin_uuid = UserUuid(fixture.user_fixture.admin_user.uuid);
+        let normal_uuid = UserUuid(fixture.user_fixture.normal_user.uuid);
+
+        // The admin replies to the normal user's post.
+        let reply: NewPost = NewPost {
+            thread_uuid: fixture.populated_thread.uuid,
+            author_uuid: admin_uuid.0,
+            parent_uuid: Some(fixture.post_1.uuid),
+            created_date: Utc::now().naive_utc(),
+            content: "A reply".to_string(),
+            censored: false,
+        };
+        let reply: ChildlessPostData = Post::create_and_get_user(reply, conn).expect("should create post");
+
+        let notifications: Vec<NotificationData> = Notification::get_notifications_for_user(normal_uuid, 50, conn)
+            .expect("should get notifications");
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].notification.kind, i32::from(NotificationKind::Reply));
+        assert_eq!(notifications[0].notification.post_uuid, reply.post.uuid);
+        assert_eq!(notifications[0].actor.uuid, admin_uuid.0);
+        assert!(!notifications[0].notification.read);
+
+        // Users aren't notified about mentioning themselves.
+        let mention: NewPost = NewPost {
+            thread_uuid: fixture.populated_thread.uuid,
+            author_uuid: normal_uuid.0,
+            parent_uuid: Some(reply.post.uuid),
+            created_date: Utc::now().naive_utc(),
+            content: "Thanks @Admin, signed @Normal".to_string(),
+            censored: false,
+        };
+        Post::create_and_get_user(mention, conn).expect("should create post");
+        let notifications: Vec<NotificationData> = Notification::get_notifications_for_user(admin_uuid, 50, conn)
+            .expect("should get notifications");
+        assert_eq!(notifications.len(), 1, "Replying and mentioning should only notify once");
+        assert_eq!(notifications[0].notification.kind, i32::from(NotificationKind::Reply));
+
+        // Editing in a new mention notifies the mentioned user, but not twice.
+        let post: Post = Post::create_and_get_user(
+            NewPost {
+                thread_uuid: fixture.populated_thread.uuid,
+                author_uuid: normal_uuid.0,
+                parent_uuid: Some(fixture.post_1.uuid),
+                created_date: Utc::now().naive_utc(),
+                content: "No mentions yet".to_string(),
+                censored: false,
+            },
+            conn
+        ).expect("should create post").post;
+        for _ in 0..2 {
+            let edit = EditPostChangeset {
+                uuid: post.uuid,
+                modified_date: Utc::now().naive_utc(),
+                content: "Now mentioning @Admin".to_string(),
+            };
+            Post::modify_post(edit, ThreadUuid(post.thread_uuid), normal_uuid, conn).expect("should modify post");
+        }
+        let notifications: Vec<NotificationData> = Notification::get_notifications_for_user(admin_uuid, 50, conn)
+            .expect("should get notifications");
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0].notification.kind, i32::from(NotificationKind::Mention));
+        assert_eq!(notifications[0].notification.post_uuid, post.uuid);
+
+        // Only the recipient can mark a notification as read.
+        let notification_uuid = NotificationUuid(notifications[0].notification.uuid);
+        match Notification::mark_read(notification_uuid, normal_uuid, conn) {
+            Err(Error::NotAuthorized { .. }) => {}
+            _ => panic!("Should not be able to mark another user's notification as read")
+        }
+        let marked: NotificationData = Notification::mark_read(notification_uuid, admin_uuid, conn).expect("should mark read");
+        assert!(marked.notification.read);
+
+        Notification::mark_all_read(admin_uuid, conn).expect("should mark all read");
+        let notifications: Vec<NotificationData> = Notification::get_notifications_for_user(admin_uuid, 50, conn)
+            .expect("should get notifications");
+        assert!(notifications.iter().all(|x| x.notification.read));
+    });
+}
+
+#[test]
+fn subscription_notifications() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let admin_uuid = UserUuid(fixture.user_fixture.admin_user.uuid);
+        let thread_uuid = ThreadUuid(fixture.populated_thread.uuid);
+
+        // Following both the thread and its forum still only notifies once per post.
+        Subscription::subscribe_to_thread(admin_uuid, thread_uuid, conn).expect("should subscribe");
+        Subscription::subscribe_to_thread(admin_uuid, thread_uuid, conn).expect("subscribing again should be harmless");
+        Subscription::subscribe_to_forum(admin_uuid, ForumUuid(fixture.forum.uuid), conn).expect("should subscribe");
+        assert_eq!(Subscription::get_subscriptions_for_user(admin_uuid, conn).expect("should get subscriptions").len(), 2);
+
+        let new_post = NewPost {
+            thread_uuid: thread_uuid.0,
+            author_uuid: fixture.user_fixture.normal_user.uuid,
+            parent_uuid: Some(fixture.post_1.uuid),
+            created_date: Utc::now().naive_utc(),
+            content: "New post".to_string(),
+            censored: false,
+        };
+        Post::create_and_get_user(new_post, conn).expect("should create post");
+
+        let notifications: Vec<NotificationData> = Notification::get_notifications_for_user(admin_uuid, 50, conn)
+            .expect("should get notifications");
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].notification.kind, i32::from(NotificationKind::Subscription));
+
+        // Unseen notifications are gathered into a digest until they are marked as digested.
+        let since = Utc::now().naive_utc() - Duration::hours(1);
+        let digests: Vec<Digest> = Notification::get_digests(since, conn).expect("should get digests");
+        assert_eq!(digests.len(), 1);
+        assert_eq!(digests[0].user.uuid, admin_uuid.0);
+        let notification_uuids: Vec<Uuid> = digests[0].notifications.iter().map(|x| x.notification.uuid).collect();
+        Notification::mark_digested(notification_uuids, Utc::now().naive_utc(), conn).expect("should mark digested");
+        assert!(Notification::get_digests(since, conn).expect("should get digests").is_empty());
+
+        Subscription::unsubscribe_from_thread(admin_uuid, thread_uuid, conn).expect("should unsubscribe");
+        assert_eq!(Subscription::get_subscriptions_for_user(admin_uuid, conn).expect("should get subscriptions").len(), 1);
+    });
+}
+
+#[test]
+fn move_thread() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let new_forum = NewForum {
+            title: "Another Forum".to_string(),
+            description: "Another Description".to_string(),
+        };
+        let other_forum: Forum = Forum::create_forum(new_forum, conn).expect("should create forum");
+
+        let moved: MinimalThreadData = Thread::move_thread(ThreadUuid(fixture.populated_thread.uuid), ForumUuid(other_forum.uuid), conn)
+            .expect("should move thread");
+        assert_eq!(moved.thread.forum_uuid, other_forum.uuid);
+
+        let threads: Vec<MinimalThreadData> = Thread::get_paginated(ForumUuid(other_forum.uuid), 1, 10, conn).expect("get threads");
+        assert_eq!(threads.len(), 1);
+        let threads: Vec<MinimalThreadData> = Thread::get_paginated(ForumUuid(fixture.forum.uuid), 1, 10, conn).expect("get threads");
+        assert_eq!(threads.len(), 1);
+    });
+}
+
+#[test]
+fn merge_threads() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let new_post: NewPost = NewPost {
+            thread_uuid: fixture.empty_thread.uuid,
+            author_uuid: fixture.user_fixture.admin_user.uuid,
+            parent_uuid: None,
+            created_date: Utc::now().naive_utc(),
+            content: "Original post of the merged thread".to_string(),
+            censored: false,
+        };
+        let source_root: Post = Post::create_and_get_user(new_post, conn).expect("should create post").post;
+
+        let source_uuid = ThreadUuid(fixture.empty_thread.uuid);
+        let target_uuid = ThreadUuid(fixture.populated_thread.uuid);
+        let reader_uuid = UserUuid(fixture.user_fixture.normal_user.uuid);
+        Subscription::subscribe_to_thread(reader_uuid, source_uuid, conn).expect("should subscribe");
+        ThreadRead::mark_read(reader_uuid, target_uuid, Utc::now().naive_utc(), conn).expect("should mark read");
+        ThreadRead::mark_read(reader_uuid, source_uuid, source_root.created_date - Duration::minutes(1), conn).expect("should mark read");
+
+        assert!(Thread::merge_threads(target_uuid, target_uuid, conn).is_err(), "Can't merge a thread into itself");
+
+        let merged: MinimalThreadData = Thread::merge_threads(ThreadUuid(fixture.empty_thread.uuid), target_uuid, conn)
+            .expect("should merge threads");
+        assert_eq!(merged.thread.reply_count, 3);
+        assert_eq!(merged.thread.last_post_date, source_root.created_date);
+
+        assert!(Thread::get_thread(ThreadUuid(fixture.empty_thread.uuid), conn).is_err(), "The merged thread should be deleted");
+        let moved_root: Post = Post::get_post(PostUuid(source_root.uuid), conn).expect("should get post");
+        assert_eq!(moved_root.thread_uuid, target_uuid.0);
+        assert_eq!(moved_root.parent_uuid, Some(fixture.post_1.uuid));
+
+        let subscriptions: Vec<Subscription> = Subscription::get_subscriptions_for_user(reader_uuid, conn).expect("should get subscriptions");
+        assert!(
+            subscriptions.iter().any(|s| s.thread_uuid == Some(target_uuid.0)),
+            "The source thread's subscribers should follow the target thread"
+        );
+        let unread: Vec<UnreadPosts> = ThreadRead::get_unread_posts(reader_uuid, vec![target_uuid.0], conn).expect("should get unread posts");
+        assert_eq!(unread.len(), 1);
+        assert_eq!(unread[0].unread_count, 1, "The unread post from the source thread should stay unread");
+    });
+}
+
+#[test]
+fn split_thread() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let reply: NewPost = NewPost {
+            thread_uuid: fixture.populated_thread.uuid,
+            author_uuid: fixture.user_fixture.admin_user.uuid,
+            parent_uuid: Some(fixture.post_2.uuid),
+            created_date: Utc::now().naive_utc(),
+            content: "Reply to post 2".to_string(),
+            censored: false,
+        };
+        let reply: Post = Post::create_and_get_user(reply, conn).expect("should create post").post;
+
+        assert!(
+            Thread::split_thread(PostUuid(fixture.post_1.uuid), "Split".to_string(), conn).is_err(),
+            "The original post can't be split off"
+        );
+
+        let split: MinimalThreadData = Thread::split_thread(PostUuid(fixture.post_2.uuid), "Split".to_string(), conn)
+            .expect("should split thread");
+        assert_eq!(split.thread.title, "Split");
+        assert_eq!(split.thread.forum_uuid, fixture.forum.uuid);
+        assert_eq!(split.thread.reply_count, 1);
+
+        let new_root: Post = Post::get_post(PostUuid(fixture.post_2.uuid), conn).expect("should get post");
+        assert_eq!(new_root.parent_uuid, None);
+        assert_eq!(new_root.thread_uuid, split.thread.uuid);
+        let moved_reply: Post = Post::get_post(PostUuid(reply.uuid), conn).expect("should get post");
+        assert_eq!(moved_reply.thread_uuid, split.thread.uuid);
+
+        let original: Thread = Thread::get_thread(ThreadUuid(fixture.populated_thread.uuid), conn).expect("should get thread");
+        assert_eq!(original.reply_count, 1, "Only post 3 should remain as a reply");
+    });
+}
+
+#[test]
+fn edit_thread_title() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let thread_uuid = ThreadUuid(fixture.populated_thread.uuid);
+        let author_uuid = UserUuid(fixture.user_fixture.normal_user.uuid);
+        let other_uuid = UserUuid(fixture.user_fixture.admin_user.uuid);
+
+        let thread: MinimalThreadData = Thread::edit_title(thread_uuid, "New Title".to_string(), author_uuid, false, conn)
+            .expect("the author should be able to edit the title");
+        assert_eq!(thread.thread.title, "New Title");
+
+        match Thread::edit_title(thread_uuid, "Other Title".to_string(), other_uuid, false, conn) {
+            Err(Error::NotAuthorized { .. }) => {}
+            _ => panic!("Only the author or a moderator should be able to edit the title")
+        }
+        Thread::edit_title(thread_uuid, "Moderated Title".to_string(), other_uuid, true, conn)
+            .expect("moderators should be able to edit the title");
+
+        Thread::set_lock_status(thread_uuid, true, conn).expect("should lock thread");
+        match Thread::edit_title(thread_uuid, "Locked Title".to_string(), author_uuid, false, conn) {
+            Err(Error::ThreadImmutable) => {}
+            _ => panic!("Locked threads should not be editable")
+        }
+    });
+}
+
+#[test]
+fn edit_and_delete_forum() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let forum_uuid = ForumUuid(fixture.forum.uuid);
+        let changeset = ForumChangeset {
+            title: "Edited Title".to_string(),
+            description: "Edited Description".to_string(),
+        };
+        let forum: Forum = Forum::update_forum(forum_uuid, changeset, conn).expect("should edit forum");
+        assert_eq!(forum.title, "Edited Title");
+        assert_eq!(forum.description, "Edited Description");
+
+        // The forum still has threads, so it isn't deleted unless forced.
+        assert!(Forum::delete_forum_checked(forum_uuid, false, conn).is_err());
+        Forum::get_forum(forum_uuid, conn).expect("forum should still exist");
+
+        Forum::delete_forum_checked(forum_uuid, true, conn).expect("should delete forum");
+        assert!(Forum::get_forum(forum_uuid, conn).is_err());
+        assert!(Thread::get_thread(ThreadUuid(fixture.populated_thread.uuid), conn).is_err());
+    });
+}
+
+fn create_thread_with_poll(fixture: &ForumFixture, multiple_choice: bool, hide_results_until_closed: bool, conn: &PgConnection) -> PollData {
+    let now = Utc::now().naive_utc();
+    let new_thread = NewThread {
+        forum_uuid: fixture.forum.uuid,
+        author_uuid: fixture.user_fixture.normal_user.uuid,
+        created_date: now,
+        locked: false,
+        archived: false,
+        title: "Poll".to_string(),
+        last_post_date: now,
+    };
+    let new_poll = NewPollData {
+        question: "Which?".to_string(),
+        options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+        multiple_choice,
+        close_date: None,
+        hide_results_until_closed,
+    };
+    let thread: ThreadData = Thread::create_thread_with_initial_post(new_thread, "Vote".to_string(), Some(new_poll), conn)
+        .expect("should create thread with poll");
+    thread.poll.expect("thread should have a poll")
+}
+
+fn option_uuid(poll: &PollData, index: usize) -> PollOptionUuid {
+    PollOptionUuid(poll.options[index].option.uuid)
+}
+
+#[test]
+fn poll_votes() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let poll: PollData = create_thread_with_poll(fixture, false, false, conn);
+        let poll_uuid = PollUuid(poll.poll.uuid);
+        let user_uuid = UserUuid(fixture.user_fixture.normal_user.uuid);
+        assert_eq!(poll.options.len(), 3);
+        assert_eq!(poll.options[0].option.content, "A");
+        assert!(!poll.results_visible);
+        assert!(poll.options.iter().all(|option| option.votes.is_none()));
+
+        // Single choice polls only take one option.
+        assert!(Poll::vote(poll_uuid, user_uuid, vec![option_uuid(&poll, 0), option_uuid(&poll, 1)], conn).is_err());
+        assert!(Poll::vote(poll_uuid, user_uuid, vec![], conn).is_err());
+        // Options from other polls are rejected.
+        assert!(Poll::vote(poll_uuid, user_uuid, vec![PollOptionUuid(Uuid::new_v4())], conn).is_err());
+
+        let voted: PollData = Poll::vote(poll_uuid, user_uuid, vec![option_uuid(&poll, 0)], conn).expect("should vote");
+        assert!(voted.results_visible);
+        assert_eq!(voted.options[0].votes, Some(1));
+        assert!(voted.options[0].voted);
+
+        // Voting again replaces the previous vote.
+        let voted: PollData = Poll::vote(poll_uuid, user_uuid, vec![option_uuid(&poll, 1)], conn).expect("should change vote");
+        assert_eq!(voted.options[0].votes, Some(0));
+        assert_eq!(voted.options[1].votes, Some(1));
+
+        // Users who haven't voted can't see the results.
+        let other_view: PollData = Poll::get_poll_for_thread(ThreadUuid(poll.poll.thread_uuid), Some(UserUuid(fixture.user_fixture.admin_user.uuid)), conn)
+            .expect("should get poll")
+            .expect("poll should exist");
+        assert!(!other_view.results_visible);
+
+        let retracted: PollData = Poll::retract_vote(poll_uuid, user_uuid, conn).expect("should retract vote");
+        assert!(!retracted.results_visible);
+        assert!(retracted.options.iter().all(|option| !option.voted));
+
+        Thread::set_lock_status(ThreadUuid(poll.poll.thread_uuid), true, conn).expect("should lock thread");
+        match Poll::vote(poll_uuid, user_uuid, vec![option_uuid(&poll, 0)], conn) {
+            Err(Error::ThreadImmutable) => {}
+            _ => panic!("Votes should not be accepted in locked threads")
+        }
+
+        match Thread::merge_threads(ThreadUuid(poll.poll.thread_uuid), ThreadUuid(fixture.populated_thread.uuid), conn) {
+            Err(Error::BadRequest) => {}
+            _ => panic!("Threads with a poll should not be merged away")
+        }
+    });
+}
+
+#[test]
+fn poll_results_hidden_until_closed() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let poll: PollData = create_thread_with_poll(fixture, true, true, conn);
+        let poll_uuid = PollUuid(poll.poll.uuid);
+        let user_uuid = UserUuid(fixture.user_fixture.normal_user.uuid);
+
+        let voted: PollData = Poll::vote(poll_uuid, user_uuid, vec![option_uuid(&poll, 0), option_uuid(&poll, 2)], conn)
+            .expect("multiple choice polls should take several options");
+        assert!(voted.options[0].voted && voted.options[2].voted);
+        assert!(!voted.results_visible);
+        assert!(voted.options.iter().all(|option| option.votes.is_none()));
+
+        // Close the poll.
+        {
+            use db::schema::polls::dsl;
+            use diesel::{RunQueryDsl, QueryDsl, ExpressionMethods};
+            diesel::update(dsl::polls.find(poll.poll.uuid))
+                .set(dsl::close_date.eq(Some(Utc::now().naive_utc() - Duration::minutes(1))))
+                .execute(conn)
+                .expect("should close poll");
+        }
+
+        let closed: PollData = Poll::get_poll_for_thread(ThreadUuid(poll.poll.thread_uuid), None, conn)
+            .expect("should get poll")
+            .expect("poll should exist");
+        assert!(closed.closed);
+        assert!(closed.results_visible);
+        assert_eq!(closed.options[0].votes, Some(1));
+        assert_eq!(closed.options[1].votes, Some(0));
+        assert!(Poll::vote(poll_uuid, user_uuid, vec![option_uuid(&poll, 1)], conn).is_err());
+    });
+}
+
+#[test]
+fn invalid_poll_does_not_create_thread() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let now = Utc::now().naive_utc();
+        let new_thread = NewThread {
+            forum_uuid: fixture.forum.uuid,
+            author_uuid: fixture.user_fixture.normal_user.uuid,
+            created_date: now,
+            locked: false,
+            archived: false,
+            title: "Bad Poll".to_string(),
+            last_post_date: now,
+        };
+        let new_poll = NewPollData {
+            question: "Only one option?".to_string(),
+            options: vec!["A".to_string()],
+            multiple_choice: false,
+            close_date: None,
+            hide_results_until_closed: false,
+        };
+        assert!(Thread::create_thread_with_initial_post(new_thread, "Vote".to_string(), Some(new_poll), conn).is_err());
+        let threads = Thread::get_threads_in_forum(ForumUuid(fixture.forum.uuid), 100, conn).expect("should get threads");
+        assert!(threads.iter().all(|x| x.thread.title != "Bad Poll"));
+    });
+}
+
+#[test]
+fn reorder_forums() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let new_forum = NewForum {
+            title: "Another Forum".to_string(),
+            description: "Another Description".to_string(),
+        };
+        let other_forum: Forum = Forum::create_forum(new_forum, conn).expect("create forum");
+
+        let forums: Vec<Forum> = Forum::reorder_forums(vec![ForumUuid(other_forum.uuid), ForumUuid(fixture.forum.uuid)], conn)
+            .expect("should reorder forums");
+        let forum_uuids: Vec<Uuid> = forums.into_iter().map(|x| x.uuid).collect();
+        assert_eq!(forum_uuids, vec![other_forum.uuid, fixture.forum.uuid]);
+
+        let forums: Vec<Forum> = Forum::reorder_forums(vec![ForumUuid(fixture.forum.uuid), ForumUuid(other_forum.uuid)], conn)
+            .expect("should reorder forums");
+        let forum_uuids: Vec<Uuid> = forums.into_iter().map(|x| x.uuid).collect();
+        assert_eq!(forum_uuids, vec![fixture.forum.uuid, other_forum.uuid]);
+
+        // Nothing changes if any of the forums don't exist.
+        let result = Forum::reorder_forums(vec![ForumUuid(other_forum.uuid), ForumUuid(Uuid::new_v4())], conn);
+        assert!(result.is_err());
+        let forums: Vec<Forum> = Forum::get_forums(conn).expect("should get forums");
+        assert_eq!(forums[0].uuid, fixture.forum.uuid);
+    });
+}
+
+fn search_for(query: &str) -> ForumSearch {
+    ForumSearch {
+        query: query.to_string(),
+        forum_uuid: None,
+        author_uuid: None,
+        before: None,
+        after: None,
+    }
+}
+
+#[test]
+fn search_posts() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let hits: Vec<SearchHitData> = search_for("content").search(1, 10, conn)
+            .expect("should search");
+        assert_eq!(hits.len(), 3);
+
+        let hits: Vec<SearchHitData> = search_for("\"post 2\"").search(1, 10, conn)
+            .expect("should search");
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0].hit;
+        assert_eq!(hit.post_uuid, fixture.post_2.uuid);
+        assert_eq!(hit.thread_uuid, fixture.populated_thread.uuid);
+        assert_eq!(hit.forum_uuid, fixture.forum.uuid);
+        assert_eq!(hit.snippet, "<mark>Post</mark> <mark>2</mark> content");
+        assert_eq!(hits[0].user.uuid, fixture.user_fixture.normal_user.uuid);
+
+        // The thread's title matches against its first post.
+        let hits: Vec<SearchHitData> = search_for("title").search(1, 10, conn)
+            .expect("should search");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].hit.post_uuid, fixture.post_1.uuid);
+        assert_eq!(hits[0].hit.title, "Thread 1 <mark>Title</mark>");
+
+        let hits: Vec<SearchHitData> = search_for("content").search(2, 2, conn)
+            .expect("should search");
+        assert_eq!(hits.len(), 1);
+
+        assert!(search_for("  ").search(1, 10, conn).is_err());
+    });
+}
+
+#[test]
+fn search_filters() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let mut search = search_for("content");
+        search.author_uuid = Some(UserUuid(fixture.user_fixture.admin_user.uuid));
+        assert_eq!(search.search(1, 10, conn).expect("should search").len(), 0);
+
+        let mut search = search_for("content");
+        search.forum_uuid = Some(ForumUuid(fixture.forum.uuid));
+        search.after = Some(fixture.post_1.created_date - Duration::seconds(1));
+        assert_eq!(search.search(1, 10, conn).expect("should search").len(), 3);
+
+        let mut search = search_for("content");
+        search.before = Some(fixture.post_1.created_date);
+        assert_eq!(search.search(1, 10, conn).expect("should search").len(), 0);
+
+        // Censored posts and posts in archived threads are hidden.
+        let moderator_uuid = UserUuid(fixture.user_fixture.admin_user.uuid);
+        Post::censor_post(PostUuid(fixture.post_2.uuid), "Spam".to_string(), moderator_uuid, conn)
+            .expect("should censor post");
+        assert_eq!(search_for("content").search(1, 10, conn).expect("should search").len(), 2);
+
+        Thread::archive_thread(ThreadUuid(fixture.populated_thread.uuid), conn)
+            .expect("should archive thread");
+        assert_eq!(search_for("content").search(1, 10, conn).expect("should search").len(), 0);
+    });
+}
+
 #[test]
 fn voting_simple_vote() {
     setup(|fixture: &ForumFixture, conn: &PgConnection| {
@@ -144,10 +908,10 @@ fn voting_simple_vote() {
         Post::vote(upvote, conn).expect("Vote should be cast");
 
         let post = Post::get_individual_post(post_uuid, user_uuid, conn).expect("Should get post");
-        let vote_counts: VoteCounts = post.votes;
-        assert_eq!(vote_counts.up, 1);
-        assert_eq!(vote_counts.down, 0);
-        assert!(vote_counts.user_voted_up);
+        let vote_counts: ReactionCounts = post.reactions;
+        assert_eq!(vote_counts.up(), 1);
+        assert_eq!(vote_counts.down(), 0);
+        assert!(vote_counts.user_voted_up());
     });
 }
 
@@ -165,19 +929,19 @@ fn voting_up_then_down() {
         Post::vote(upvote, conn).expect("Vote should be cast");
 
         let post = Post::get_individual_post(post_uuid, user_uuid, conn).expect("Should get post");
-        let vote_counts: VoteCounts = post.votes;
-        assert_eq!(vote_counts.up, 1);
-        assert_eq!(vote_counts.down, 0);
-        assert!(vote_counts.user_voted_up);
+        let vote_counts: ReactionCounts = post.reactions;
+        assert_eq!(vote_counts.up(), 1);
+        assert_eq!(vote_counts.down(), 0);
+        assert!(vote_counts.user_voted_up());
 
         let downvote = PostVote::Down(vote);
         Post::vote(downvote, conn).expect("Vote should be cast");
         let post = Post::get_individual_post(post_uuid, user_uuid, conn).expect("Should get post");
-        let vote_counts: VoteCounts = post.votes;
-        assert_eq!(vote_counts.up, 0);
-        assert_eq!(vote_counts.down, 1);
-        assert!(vote_counts.user_voted_down);
-        assert!(!vote_counts.user_voted_up);
+        let vote_counts: ReactionCounts = post.reactions;
+        assert_eq!(vote_counts.up(), 0);
+        assert_eq!(vote_counts.down(), 1);
+        assert!(vote_counts.user_voted_down());
+        assert!(!vote_counts.user_voted_up());
     });
 }
 
@@ -196,16 +960,16 @@ fn voting_twice() {
         Post::vote(upvote, conn).expect("Vote should be cast");
 
         let post = Post::get_individual_post(post_uuid, user_uuid, conn).expect("Should get post");
-        let vote_counts: VoteCounts = post.votes;
-        assert_eq!(vote_counts.up, 1);
-        assert_eq!(vote_counts.down, 0);
-        assert!(vote_counts.user_voted_up);
+        let vote_counts: ReactionCounts = post.reactions;
+        assert_eq!(vote_counts.up(), 1);
+        assert_eq!(vote_counts.down(), 0);
+        assert!(vote_counts.user_voted_up());
 
         let upvote = PostVote::Up(vote);
         Post::vote(upvote, conn).expect_err("Should not be able to vote twice");
         let post = Post::get_individual_post(post_uuid, user_uuid, conn).expect("Should get post");
-        let vote_counts: VoteCounts = post.votes;
-        assert_eq!(vote_counts.up, 1);
+        let vote_counts: ReactionCounts = post.reactions;
+        assert_eq!(vote_counts.up(), 1);
     });
 }
 #[test]
@@ -259,14 +1023,224 @@ fn voting_many() {
         Post::vote(upvote, conn).expect("Vote should be cast");
 
         let posts: Vec<Post> = vec![fixture.post_1.clone(), fixture.post_2.clone(), fixture.post_3.clone()];
-        let posts_and_votes = Post::get_votes_for_posts(&posts, Some(admin_user_uuid), conn).expect("should get vote counts");
+        let posts_and_votes = Post::get_reactions_for_posts(&posts, Some(admin_user_uuid), conn).expect("should get vote counts");
 
         assert_eq!(posts_and_votes.len(), 3);
-        assert_eq!(posts_and_votes[0].up, 2);
-        assert!(posts_and_votes[0].user_voted_up);
-        assert_eq!(posts_and_votes[1].up, 1);
-        assert!(!posts_and_votes[1].user_voted_up);
+        assert_eq!(posts_and_votes[0].up(), 2);
+        assert!(posts_and_votes[0].user_voted_up());
+        assert_eq!(posts_and_votes[1].up(), 1);
+        assert!(!posts_and_votes[1].user_voted_up());
+
+    });
+}
+
+
+#[test]
+fn reactions() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let post_uuid = PostUuid(fixture.post_1.uuid);
+        let normal_user_uuid = UserUuid(fixture.user_fixture.normal_user.uuid);
+        let admin_user_uuid = UserUuid(fixture.user_fixture.admin_user.uuid);
+        let set = ReactionSet::default();
+
+        Post::react(post_uuid, normal_user_uuid, "heart".to_string(), &set, conn).expect("should react");
+        Post::react(post_uuid, admin_user_uuid, "heart".to_string(), &set, conn).expect("should react");
+        let counts: ReactionCounts = Post::react(post_uuid, normal_user_uuid, "laugh".to_string(), &set, conn)
+            .expect("users should be able to leave several reactions");
+        assert_eq!(counts.count("heart"), 2);
+        assert_eq!(counts.count("laugh"), 1);
+        assert!(counts.user_reacted("heart"));
+        // Reactions are ordered by popularity.
+        assert_eq!(counts.reactions[0].reaction, "heart");
+
+        Post::react(post_uuid, normal_user_uuid, "heart".to_string(), &set, conn)
+            .expect_err("Should not be able to leave the same reaction twice");
+        Post::react(post_uuid, normal_user_uuid, "not a reaction".to_string(), &set, conn)
+            .expect_err("Reactions outside of the set should be rejected");
+
+        // Votes are reactions too.
+        Post::vote(PostVote::Up(Vote { post_uuid, user_uuid: normal_user_uuid }), conn).expect("Vote should be cast");
+        let counts: ReactionCounts = Post::unreact(post_uuid, normal_user_uuid, "heart".to_string(), conn).expect("should remove reaction");
+        assert_eq!(counts.count("heart"), 1);
+        assert!(!counts.user_reacted("heart"));
+        assert_eq!(counts.up(), 1);
+        assert_eq!(counts.reactions[0].reaction, "up");
+        assert_eq!(counts.score(), 1);
+    });
+}
+
+#[test]
+fn replies_are_ordered_by_score() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let post_2_uuid = PostUuid(fixture.post_2.uuid);
+        let post_3_uuid = PostUuid(fixture.post_3.uuid);
+        let user_uuid = UserUuid(fixture.user_fixture.normal_user.uuid);
+
+        Post::vote(PostVote::Down(Vote { post_uuid: post_2_uuid, user_uuid }), conn).expect("Vote should be cast");
+        Post::vote(PostVote::Up(Vote { post_uuid: post_3_uuid, user_uuid }), conn).expect("Vote should be cast");
+
+        let root: PostData = Post::get_posts_in_thread(ThreadUuid(fixture.populated_thread.uuid), None, conn).expect("should get posts");
+        assert_eq!(root.children[0].post.uuid, fixture.post_3.uuid);
+        assert_eq!(root.children[0].reactions.score(), 1);
+        assert_eq!(root.children[1].post.uuid, fixture.post_2.uuid);
+        assert_eq!(root.children[1].reactions.score(), -1);
+    });
+}
+
+
+/// Runs the closure on several threads at once, each with its own connection,
+/// returning the results in the order the threads were started.
+fn run_concurrently<T, F>(count: usize, f: F) -> Vec<T>
+    where
+        F: Fn(usize, &PgConnection) -> T + Send + Sync + 'static,
+        T: Send + 'static
+{
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+    use diesel::Connection;
+
+    let barrier = Arc::new(Barrier::new(count));
+    let f = Arc::new(f);
+    let handles: Vec<thread::JoinHandle<T>> = (0..count)
+        .map(|index| {
+            let barrier = barrier.clone();
+            let f = f.clone();
+            thread::spawn(move || {
+                let conn = PgConnection::establish(DATABASE_URL).expect("Database not available.");
+                barrier.wait();
+                f(index, &conn)
+            })
+        })
+        .collect();
+    handles
+        .into_iter()
+        .map(|handle| handle.join().expect("thread should not panic"))
+        .collect()
+}
+
+#[test]
+fn concurrent_votes_are_only_counted_once() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let vote = Vote {
+            post_uuid: PostUuid(fixture.post_1.uuid),
+            user_uuid: UserUuid(fixture.user_fixture.normal_user.uuid)
+        };
+
+        let results = run_concurrently(8, move |_, conn| Post::vote(PostVote::Up(vote), conn).is_ok());
+        assert_eq!(results.iter().filter(|cast| **cast).count(), 1);
+
+        let post = Post::get_individual_post(vote.post_uuid, vote.user_uuid, conn).expect("Should get post");
+        assert_eq!(post.reactions.up(), 1);
+    });
+}
+
+#[test]
+fn concurrent_vote_switching_leaves_one_vote() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let vote = Vote {
+            post_uuid: PostUuid(fixture.post_1.uuid),
+            user_uuid: UserUuid(fixture.user_fixture.normal_user.uuid)
+        };
+
+        let results = run_concurrently(8, move |index, conn| {
+            let direction = if index % 2 == 0 { VoteDirection::Up } else { VoteDirection::Down };
+            Post::change_vote(vote, Some(direction), conn).is_ok()
+        });
+        assert!(results.iter().all(|changed| *changed));
+
+        let post = Post::get_individual_post(vote.post_uuid, vote.user_uuid, conn).expect("Should get post");
+        assert_eq!(post.reactions.up() + post.reactions.down(), 1);
+        assert!(post.reactions.user_voted_up() != post.reactions.user_voted_down());
+
+        let author = User::get_user(UserUuid(fixture.post_1.author_uuid), conn).expect("Should get author");
+        assert_eq!(author.reputation as i64, post.reactions.score());
+    });
+}
+
+#[test]
+fn change_vote() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let vote = Vote {
+            post_uuid: PostUuid(fixture.post_1.uuid),
+            user_uuid: UserUuid(fixture.user_fixture.normal_user.uuid)
+        };
+
+        let counts: ReactionCounts = Post::change_vote(vote, Some(VoteDirection::Up), conn).expect("should vote");
+        assert_eq!(counts.up(), 1);
+        let counts: ReactionCounts = Post::change_vote(vote, Some(VoteDirection::Up), conn).expect("voting the same way again is fine");
+        assert_eq!(counts.up(), 1);
+        let counts: ReactionCounts = Post::change_vote(vote, Some(VoteDirection::Down), conn).expect("should switch vote");
+        assert_eq!(counts.up(), 0);
+        assert_eq!(counts.down(), 1);
+        assert!(counts.user_voted_down());
+        let counts: ReactionCounts = Post::change_vote(vote, None, conn).expect("should remove vote");
+        assert_eq!(counts.score(), 0);
+        assert!(counts.reactions.is_empty());
+    });
+}
+
+fn reputation_of(user_uuid: Uuid, conn: &PgConnection) -> i32 {
+    User::get_user(UserUuid(user_uuid), conn)
+        .expect("Should get user")
+        .reputation
+}
+
+#[test]
+fn reputation_follows_votes() {
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let author_uuid = fixture.post_1.author_uuid;
+        let vote = Vote {
+            post_uuid: PostUuid(fixture.post_1.uuid),
+            user_uuid: UserUuid(fixture.user_fixture.admin_user.uuid)
+        };
+
+        Post::vote(PostVote::Up(vote), conn).expect("Should vote up");
+        assert_eq!(reputation_of(author_uuid, conn), 1);
+        Post::vote(PostVote::Up(vote), conn).expect_err("Voting twice is rejected");
+        assert_eq!(reputation_of(author_uuid, conn), 1);
+        Post::vote(PostVote::Down(vote), conn).expect("Should switch vote");
+        assert_eq!(reputation_of(author_uuid, conn), -1);
+        Post::revoke_vote(vote.user_uuid, vote.post_uuid, conn).expect("Should revoke vote");
+        assert_eq!(reputation_of(author_uuid, conn), 0);
+
+        let set = ReactionSet::default();
+        Post::react(vote.post_uuid, vote.user_uuid, "up".to_string(), &set, conn).expect("Should react");
+        Post::react(vote.post_uuid, vote.user_uuid, "heart".to_string(), &set, conn).expect("Should react");
+        assert_eq!(reputation_of(author_uuid, conn), 1);
+        Post::unreact(vote.post_uuid, vote.user_uuid, "down".to_string(), conn).expect("Removing a missing vote is fine");
+        assert_eq!(reputation_of(author_uuid, conn), 1);
+        Post::unreact(vote.post_uuid, vote.user_uuid, "up".to_string(), conn).expect("Should remove vote");
+        assert_eq!(reputation_of(author_uuid, conn), 0);
+        assert_eq!(reputation_of(fixture.user_fixture.admin_user.uuid, conn), 0);
+    });
+}
+
+#[test]
+fn recompute_reputation_and_leaderboard() {
+    use db::schema::users;
+    use diesel::{ExpressionMethods, RunQueryDsl};
+
+    setup(|fixture: &ForumFixture, conn: &PgConnection| {
+        let author_uuid = fixture.post_1.author_uuid;
+        let vote = Vote {
+            post_uuid: PostUuid(fixture.post_1.uuid),
+            user_uuid: UserUuid(fixture.user_fixture.admin_user.uuid)
+        };
+        Post::vote(PostVote::Up(vote), conn).expect("Should vote up");
+
+        // Knock the stored reputation out of line with the votes.
+        diesel::update(users::table)
+            .set(users::reputation.eq(100))
+            .execute(conn)
+            .expect("Should set reputation");
+
+        User::recompute_reputation(conn).expect("Should recompute reputation");
+        assert_eq!(reputation_of(author_uuid, conn), 1);
+        assert_eq!(reputation_of(fixture.user_fixture.admin_user.uuid, conn), 0);
 
+        let leaderboard: Vec<User> = User::get_leaderboard(10, conn).expect("Should get leaderboard");
+        assert_eq!(leaderboard[0].uuid, author_uuid);
+        assert!(leaderboard.windows(2).all(|pair| pair[0].reputation >= pair[1].reputation));
     });
 }
 
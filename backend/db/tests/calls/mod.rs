@@ -2,3 +2,7 @@ mod user;
 mod auth;
 mod forum;
 mod bucket;
+mod article;
+mod tag;
+mod upload;
+mod article_view;
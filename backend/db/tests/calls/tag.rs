@@ -0,0 +1,123 @@
+use db::article::{Article, ArticleData, ArticleFilter, NewArticle};
+use db::tag::{Tag, TagCount};
+use common::setup::*;
+use diesel::PgConnection;
+use error::Error;
+use identifiers::article::ArticleUuid;
+use identifiers::user::UserUuid;
+use testing_fixtures::fixtures::article::{ArticleFixture, approve_article};
+
+
+fn names(tags: Vec<Tag>) -> Vec<String> {
+    tags.into_iter().map(|tag| tag.name).collect()
+}
+
+#[test]
+fn tags_are_normalized_and_replaced() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let article_uuid = ArticleUuid(fixture.published_article.uuid);
+        let tags = Tag::set_tags_for_article(article_uuid, vec!["Rust  Lang".to_string(), "rust-lang".to_string(), "Web".to_string()], conn)
+            .expect("Should set tags");
+        assert_eq!(names(tags), vec!["rust-lang", "web"]);
+
+        let data: ArticleData = Article::get_article_data(article_uuid, conn).expect("Should get article");
+        assert_eq!(data.tags, vec!["rust-lang", "web"]);
+
+        let tags = Tag::set_tags_for_article(article_uuid, vec!["web".to_string()], conn).expect("Should replace tags");
+        assert_eq!(names(tags), vec!["web"]);
+        Tag::set_tags_for_article(article_uuid, vec![], conn).expect("Should clear tags");
+        let data: ArticleData = Article::get_article_data(article_uuid, conn).expect("Should get article");
+        assert!(data.tags.is_empty());
+
+        let too_long: String = "a".repeat(100);
+        match Tag::set_tags_for_article(article_uuid, vec![too_long], conn) {
+            Err(Error::BadRequest) => {}
+            other => panic!("Expected the tag to be rejected, got: {:?}", other)
+        }
+        match Tag::set_tags_for_article(article_uuid, vec!["  ".to_string()], conn) {
+            Err(Error::BadRequest) => {}
+            other => panic!("Expected the tag to be rejected, got: {:?}", other)
+        }
+    });
+}
+
+#[test]
+fn published_articles_are_filtered_by_tag_and_author() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        Tag::set_tags_for_article(ArticleUuid(fixture.published_article.uuid), vec!["news".to_string()], conn)
+            .expect("Should set tags");
+        Tag::set_tags_for_article(ArticleUuid(fixture.unpublished_article.uuid), vec!["news".to_string()], conn)
+            .expect("Should set tags");
+
+        let filter = ArticleFilter { tag: Some("News".to_string()), author_uuid: None };
+        let tagged: Vec<ArticleData> = Article::get_paginated(&filter, 1, 10, conn).expect("Should get articles");
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].article.uuid, fixture.published_article.uuid);
+        assert_eq!(tagged[0].tags, vec!["news"]);
+
+        let filter = ArticleFilter { tag: Some("sports".to_string()), author_uuid: None };
+        assert!(Article::get_paginated(&filter, 1, 10, conn).expect("Should get articles").is_empty());
+
+        let filter = ArticleFilter { tag: None, author_uuid: Some(UserUuid(fixture.user_fixture.normal_user.uuid)) };
+        assert_eq!(Article::get_paginated(&filter, 1, 10, conn).expect("Should get articles").len(), 1);
+        let filter = ArticleFilter { tag: Some("news".to_string()), author_uuid: Some(UserUuid(fixture.user_fixture.admin_user.uuid)) };
+        assert!(Article::get_paginated(&filter, 1, 10, conn).expect("Should get articles").is_empty());
+
+        // Only published articles are counted.
+        let counts: Vec<TagCount> = Tag::get_tag_counts(conn).expect("Should count tags");
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].name, "news");
+        assert_eq!(counts[0].article_count, 1);
+    });
+}
+
+#[test]
+fn related_articles_share_the_most_tags() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let publish = |title: &str, tags: Vec<&str>| -> Article {
+            let new_article = NewArticle {
+                title: title.to_string(),
+                slug: title.to_lowercase(),
+                body: "Body".to_string(),
+                author_uuid: fixture.user_fixture.admin_user.uuid,
+            };
+            let article = Article::create_article(new_article, conn).expect("Should create article");
+            let tags: Vec<String> = tags.into_iter().map(String::from).collect();
+            Tag::set_tags_for_article(ArticleUuid(article.uuid), tags, conn).expect("Should set tags");
+            approve_article(ArticleUuid(article.uuid), UserUuid(fixture.user_fixture.normal_user.uuid), conn);
+            Article::set_publish_status(ArticleUuid(article.uuid), true, conn).expect("Should publish article")
+        };
+        Tag::set_tags_for_article(ArticleUuid(fixture.published_article.uuid), vec!["a".to_string(), "b".to_string()], conn)
+            .expect("Should set tags");
+        let one_shared = publish("One", vec!["a", "c"]);
+        let two_shared = publish("Two", vec!["a", "b"]);
+        publish("None", vec!["c"]);
+        // Unpublished articles aren't suggested.
+        Tag::set_tags_for_article(ArticleUuid(fixture.unpublished_article.uuid), vec!["a".to_string(), "b".to_string()], conn)
+            .expect("Should set tags");
+
+        let related: Vec<ArticleData> = Article::get_related(ArticleUuid(fixture.published_article.uuid), 5, conn)
+            .expect("Should get related articles");
+        let related_uuids: Vec<_> = related.iter().map(|data| data.article.uuid).collect();
+        assert_eq!(related_uuids, vec![two_shared.uuid, one_shared.uuid]);
+    });
+}
+
+#[test]
+fn bad_tags_dont_leave_an_article_behind() {
+    setup(|fixture: &ArticleFixture, conn: &PgConnection| {
+        let author_uuid = UserUuid(fixture.user_fixture.normal_user.uuid);
+        let new_article = NewArticle {
+            title: "Tagged".to_string(),
+            slug: "tagged".to_string(),
+            body: "Body".to_string(),
+            author_uuid: author_uuid.0,
+        };
+        match Article::create_article_with_tags(new_article, vec!["  ".to_string()], conn) {
+            Err(Error::BadRequest) => {}
+            other => panic!("Expected the tag to be rejected, got: {:?}", other)
+        }
+        let unpublished: Vec<Article> = Article::get_unpublished_articles_for_user(author_uuid, conn).expect("Should get articles");
+        assert!(unpublished.iter().all(|article| article.slug != "tagged"));
+    });
+}
@@ -0,0 +1,67 @@
+use db::upload::{Upload, NewUpload, MAX_UPLOAD_SIZE};
+use common::setup::*;
+use diesel::PgConnection;
+use error::Error;
+use identifiers::upload::UploadUuid;
+use identifiers::user::UserUuid;
+use testing_fixtures::fixtures::user::UserFixture;
+
+
+#[test]
+fn content_has_to_match_its_type() {
+    assert_eq!(Upload::check_content("image/png", b"\x89PNG\r\n\x1a\n..."), Ok("image/png"));
+    assert_eq!(Upload::check_content("IMAGE/GIF", b"GIF89a..."), Ok("image/gif"));
+    assert_eq!(Upload::check_content("text/plain; charset=utf-8", b"Notes"), Ok("text/plain"));
+    assert_eq!(Upload::check_content("image/png", b"<html>"), Err(Error::UnsupportedMediaType));
+    assert_eq!(Upload::check_content("text/plain", b"\xff\xfe"), Err(Error::UnsupportedMediaType));
+    assert_eq!(Upload::check_content("image/svg+xml", b"<svg></svg>"), Err(Error::UnsupportedMediaType));
+
+    let too_large: Vec<u8> = vec![b'a'; MAX_UPLOAD_SIZE as usize + 1];
+    assert_eq!(Upload::check_content("text/plain", &too_large), Err(Error::PayloadTooLarge { limit: MAX_UPLOAD_SIZE }));
+}
+
+#[test]
+fn file_names_are_cleaned() {
+    assert_eq!(Upload::clean_file_name("C:\\Users\\joe\\cat.png"), "cat.png");
+    assert_eq!(Upload::clean_file_name("../../etc/passwd"), "passwd");
+    assert_eq!(Upload::clean_file_name(" \u{0}\n "), "upload");
+}
+
+#[test]
+fn uploads_are_found_by_owner_and_content() {
+    setup(|fixture: &UserFixture, conn: &PgConnection| {
+        let hash: String = "a".repeat(40);
+        let new_upload = NewUpload {
+            owner_uuid: fixture.normal_user.uuid,
+            content_hash: hash.clone(),
+            file_name: "cat.png".to_string(),
+            mime_type: "image/png".to_string(),
+            size: 10,
+            has_thumbnail: false,
+        };
+        let upload: Upload = Upload::create_upload(new_upload, conn).expect("Should create upload");
+        assert_eq!(upload.file_path(), format!("aa/{}.png", hash));
+        assert_eq!(Upload::thumbnail_path(&hash), format!("aa/{}.thumb.png", hash));
+
+        let uploads: Vec<Upload> = Upload::get_uploads_for_user(UserUuid(fixture.normal_user.uuid), conn)
+            .expect("Should get uploads");
+        assert_eq!(uploads, vec![upload.clone()]);
+        let uploads: Vec<Upload> = Upload::get_uploads_for_user(UserUuid(fixture.admin_user.uuid), conn)
+            .expect("Should get uploads");
+        assert!(uploads.is_empty());
+
+        let same_content: Option<Upload> = Upload::get_by_content_hash(&hash, conn).expect("Should look up content");
+        assert_eq!(same_content, Some(upload.clone()));
+        let same_file: Option<Upload> = Upload::get_by_content_hash_and_type(&hash, "image/png", conn).expect("Should look up content");
+        assert_eq!(same_file, Some(upload.clone()));
+        let same_file: Option<Upload> = Upload::get_by_content_hash_and_type(&hash, "text/plain", conn).expect("Should look up content");
+        assert_eq!(same_file, None, "Content stored as another type is in another file");
+
+        let locked: Option<Upload> = Upload::with_content_lock(&hash, conn, || Upload::get_by_content_hash(&hash, conn))
+            .expect("Should lock content");
+        assert_eq!(locked, Some(upload.clone()));
+        Upload::delete_upload(UploadUuid(upload.uuid), conn).expect("Should delete upload");
+        let same_content: Option<Upload> = Upload::get_by_content_hash(&hash, conn).expect("Should look up content");
+        assert_eq!(same_content, None);
+    });
+}
@@ -51,7 +51,13 @@ pub enum Error {
     /// The JWT 'bearer schema' was not followed.
     MalformedToken,
     /// The user has been banned and therefore can't perform their desired action.
-    UserBanned
+    UserBanned,
+    /// The user has performed the action too often, and must wait the given number of seconds before trying again.
+    RateLimited { retry_after: u64 },
+    /// The uploaded file was larger than the given number of bytes.
+    PayloadTooLarge { limit: u64 },
+    /// The uploaded file wasn't of a type that can be uploaded.
+    UnsupportedMediaType,
 }
 
 pub type LoginResult = Result<String, LoginError>;
@@ -86,6 +92,14 @@ pub fn handle_diesel_error(diesel_error: DieselError, type_name: &'static str) -
     }
 }
 
+/// Allows calls that return a `BackendResult` to be made within a diesel transaction.
+/// Errors that come from the transaction itself, rather than a call, aren't about any one type.
+impl From<DieselError> for Error {
+    fn from(diesel_error: DieselError) -> Self {
+        handle_diesel_error(diesel_error, "Transaction")
+    }
+}
+
 pub trait ErrorFormatter {
     fn handle_error(diesel_error: DieselError) -> Error;
 }
@@ -194,6 +208,28 @@ mod rocket_support {
                         .status(Status::Forbidden)
                         .ok()
                 }
+                RateLimited { retry_after } => {
+                    build
+                        .merge("Too many requests, try again later."
+                            .respond_to(req)?)
+                        .raw_header("Retry-After", retry_after.to_string())
+                        .status(Status::TooManyRequests)
+                        .ok()
+                }
+                PayloadTooLarge { .. } => {
+                    build
+                        .merge("The file is too large."
+                            .respond_to(req)?)
+                        .status(Status::PayloadTooLarge)
+                        .ok()
+                }
+                UnsupportedMediaType => {
+                    build
+                        .merge("Files of this type can't be uploaded."
+                            .respond_to(req)?)
+                        .status(Status::UnsupportedMediaType)
+                        .ok()
+                }
             }
         }
     }
@@ -225,6 +261,8 @@ pub mod warp_support {
     use warp::reject::Rejection;
     use warp::reply::Reply;
     use warp::http::StatusCode;
+    use warp::http::header::RETRY_AFTER;
+    use warp::http::header::HeaderValue;
 
     impl Display for Error {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -244,6 +282,9 @@ pub mod warp_support {
                 Error::MissingToken => "The Api route was expecting a JWT token and none was provided. Try logging in.".to_string(),
                 Error::NotAuthorized {reason} => format!("You are forbidden from accessing this resource. ({})", reason),
                 Error::UserBanned => "Your account has been banned".to_string(),
+                Error::RateLimited {retry_after} => format!("You are doing that too often, try again in {} seconds", retry_after),
+                Error::PayloadTooLarge {limit} => format!("The file is too large, it can be at most {} bytes", limit),
+                Error::UnsupportedMediaType => "Files of this type can't be uploaded".to_string(),
                 Error::BadRequest => "Your request is malformed".to_string(),
                 Error::InternalServerError => "Internal server error encountered".to_string(),
                 Error::NotFound {type_name}=> format!("The resource ({})you requested could not be found", type_name),
@@ -288,6 +329,12 @@ pub mod warp_support {
             Error::InternalServerError => *resp.status_mut() = StatusCode::INTERNAL_SERVER_ERROR,
             Error::ThreadImmutable => *resp.status_mut() = StatusCode::BAD_REQUEST,
             Error::MissingToken => *resp.status_mut() = StatusCode::UNAUTHORIZED,
+            Error::RateLimited {retry_after} => {
+                *resp.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+                resp.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after));
+            }
+            Error::PayloadTooLarge {..} => *resp.status_mut() = StatusCode::PAYLOAD_TOO_LARGE,
+            Error::UnsupportedMediaType => *resp.status_mut() = StatusCode::UNSUPPORTED_MEDIA_TYPE,
         }
 
 //        warn!("rewrote error response: {:?}", resp);
@@ -23,7 +23,7 @@ fn get_article(article_uuid: ArticleUuid, conn: Conn) -> Result<Json<FullArticle
 /// Gets the published articles.
 #[get("/articles/<index>/<page_size>", rank = 0)]
 fn get_published_articles(index: i32, page_size: i32, conn: Conn) -> Result<Json<Vec<ArticlePreviewResponse>>, Error> {
-    Article::get_paginated(index, page_size, &conn)
+    Article::get_paginated(&ArticleFilter::default(), index, page_size, &conn)
         .map(convert_vector)
         .map(Json)
 }
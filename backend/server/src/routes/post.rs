@@ -4,7 +4,8 @@ use rocket::Route;
 use db::post::*;
 use error::Error;
 use pool::Conn;
-use wire::post::{PostResponse, NewPostRequest, EditPostRequest};
+use wire::post::{PostResponse, NewPostRequest, EditPostRequest, CensorPostRequest};
+use db::ModeratorView;
 use auth_lib::user_authorization::NormalUser;
 use auth_lib::user_authorization::ModeratorUser;
 use error::VectorMappable;
@@ -50,9 +51,10 @@ fn edit_post(edit_post_request: Json<EditPostRequest>, login_user: NormalUser, c
 
 /// Censors a post, preventing it from being seen immediately.
 /// This operation is available to moderators.
-#[put("/censor/<post_uuid>")]
-fn censor_post(post_uuid: PostUuid, _moderator: ModeratorUser, conn: Conn) -> Result<Json<PostResponse>, Error> {
-    Post::censor_post(post_uuid, &conn)
+#[put("/censor/<post_uuid>", data = "<censor_request>")]
+fn censor_post(post_uuid: PostUuid, censor_request: Json<CensorPostRequest>, moderator: ModeratorUser, conn: Conn) -> Result<Json<PostResponse>, Error> {
+    Post::censor_post(post_uuid, censor_request.into_inner().reason, moderator.user_uuid, &conn)
+        .map(ModeratorView)
         .map(PostResponse::from)
         .map(Json)
 }
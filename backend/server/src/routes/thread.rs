@@ -25,7 +25,7 @@ fn create_thread(new_thread_request: Json<NewThreadRequest>, user: NormalUser, c
     let post_content: String = new_thread_request.post_content;
 
 
-    Thread::create_thread_with_initial_post(new_thread, post_content, &conn)
+    Thread::create_thread_with_initial_post(new_thread, post_content, None, &conn)
         .map(ThreadResponse::from)
         .map(Json)
 }
@@ -76,7 +76,7 @@ fn get_threads_by_forum_id(forum_uuid: ForumUuid, index: i32, conn: Conn) -> Bac
 #[get("/<thread_uuid>")]
 fn get_thread_contents(thread_uuid: ThreadUuid, user: Option<NormalUser>, conn: Conn) -> BackendResult<Json<ThreadResponse>> {
     let user_uuid: Option<UserUuid> = user.map(|x| x.user_uuid);
-    Thread::get_full_thread(thread_uuid, user_uuid, &conn)
+    Thread::get_full_thread(thread_uuid, user_uuid, false, &conn)
         .map(ThreadResponse::from)
         .map(Json)
 }
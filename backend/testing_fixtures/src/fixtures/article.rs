@@ -0,0 +1,65 @@
+use diesel::PgConnection;
+use Fixture;
+
+use db::article::{Article, NewArticle};
+use db::article_review::ArticleReview;
+use identifiers::article::ArticleUuid;
+use identifiers::user::UserUuid;
+use wire::article::ReviewDecision;
+
+use fixtures::user::UserFixture;
+
+
+const PUBLISHED_TITLE: &'static str = "Published Article";
+const PUBLISHED_SLUG: &'static str = "published-article-aaaaaa";
+const UNPUBLISHED_TITLE: &'static str = "Unpublished Article";
+const UNPUBLISHED_SLUG: &'static str = "unpublished-article-bbbbbb";
+const ARTICLE_BODY: &'static str = "Article body";
+
+
+pub struct ArticleFixture {
+    pub user_fixture: UserFixture,
+    /// Written by the normal user, and approved by the admin user.
+    pub published_article: Article,
+    /// Written by the normal user.
+    pub unpublished_article: Article,
+}
+
+impl Fixture for ArticleFixture {
+    fn generate(conn: &PgConnection) -> Self {
+        let user_fixture: UserFixture = UserFixture::generate(conn);
+
+        let new_article = NewArticle {
+            title: PUBLISHED_TITLE.to_string(),
+            slug: PUBLISHED_SLUG.to_string(),
+            body: ARTICLE_BODY.to_string(),
+            author_uuid: user_fixture.normal_user.uuid,
+        };
+        let published_article = Article::create_article(new_article, conn).expect("Should create article");
+        approve_article(ArticleUuid(published_article.uuid), UserUuid(user_fixture.admin_user.uuid), conn);
+        let published_article = Article::set_publish_status(ArticleUuid(published_article.uuid), true, conn)
+            .expect("Should publish article");
+
+        let new_article = NewArticle {
+            title: UNPUBLISHED_TITLE.to_string(),
+            slug: UNPUBLISHED_SLUG.to_string(),
+            body: ARTICLE_BODY.to_string(),
+            author_uuid: user_fixture.normal_user.uuid,
+        };
+        let unpublished_article = Article::create_article(new_article, conn).expect("Should create article");
+
+        ArticleFixture {
+            user_fixture,
+            published_article,
+            unpublished_article,
+        }
+    }
+}
+
+/// Submits the article for review, and has the reviewer approve it, so that it can be published.
+pub fn approve_article(article_uuid: ArticleUuid, reviewer_uuid: UserUuid, conn: &PgConnection) -> Article {
+    Article::submit_for_review(article_uuid, conn).expect("Should submit article for review");
+    ArticleReview::review(article_uuid, reviewer_uuid, ReviewDecision::Approve, String::new(), conn)
+        .expect("Should approve article");
+    Article::get_article(article_uuid, conn).expect("Should get article")
+}
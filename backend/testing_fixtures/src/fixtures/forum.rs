@@ -1,5 +1,5 @@
 use diesel::PgConnection;
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use Fixture;
 
 use db::forum::{Forum, NewForum};
@@ -45,16 +45,22 @@ impl Fixture for ForumFixture {
         let forum = Forum::create_forum(new_forum, conn).expect("create forum");
 
 
+        // The populated thread is created first, so the threads are always listed in the same order.
+        let now = Utc::now().naive_utc();
+        let earlier = now - Duration::minutes(1);
         let new_thread_1: NewThread = NewThread {
             forum_uuid: forum.uuid,
             author_uuid: user_fixture.normal_user.uuid,
-            created_date: Utc::now().naive_utc(),
+            created_date: earlier,
             locked: false,
             archived: false,
             title: THREAD_1_TITLE.to_string(),
+            last_post_date: earlier,
         };
         let mut new_thread_2: NewThread = new_thread_1.clone();
         new_thread_2.title = THREAD_2_TITLE.to_string();
+        new_thread_2.created_date = now;
+        new_thread_2.last_post_date = now;
 
         let thread_1 = Thread::create_thread(new_thread_1, conn).expect("create thread");
         let thread_2 = Thread::create_thread(new_thread_2, conn).expect("create thread");
@@ -1,3 +1,4 @@
 pub mod user;
 pub mod bucket;
-pub mod forum;
\ No newline at end of file
+pub mod forum;
+pub mod article;
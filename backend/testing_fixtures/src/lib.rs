@@ -3,6 +3,8 @@
 extern crate db;
 extern crate diesel;
 extern crate auth;
+extern crate identifiers;
+extern crate wire;
 #[macro_use]
 extern crate lazy_static;
 
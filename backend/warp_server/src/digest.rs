@@ -0,0 +1,154 @@
+//! Periodically gathers up the notifications users haven't seen and hands them off for delivery.
+//!
+//! Delivery itself is left to a `DigestSink`, so the job doesn't need to know whether digests
+//! end up in an email, a log, or a file.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration as StdDuration;
+use chrono::Duration;
+use chrono::NaiveDateTime;
+use chrono::Utc;
+use uuid::Uuid;
+use db::notification::Notification;
+use db::notification::Digest;
+use wire::subscription::DigestResponse;
+use error::Error;
+use pool::Pool;
+use pool::PooledConn;
+
+/// Somewhere digests can be delivered to.
+pub trait DigestSink: Send {
+    /// Delivers a single user's digest.
+    /// If delivery fails, the digest's notifications will be included in the next run instead.
+    fn deliver(&mut self, digest: &DigestResponse) -> Result<(), String>;
+}
+
+/// Writes digests to the log.
+pub struct LogSink;
+
+impl DigestSink for LogSink {
+    fn deliver(&mut self, digest: &DigestResponse) -> Result<(), String> {
+        info!(
+            "Digest for {}: {} new notification(s)",
+            digest.user.user_name,
+            digest.notifications.len()
+        );
+        Ok(())
+    }
+}
+
+/// Appends each digest to a file as a line of JSON.
+pub struct FileSink {
+    pub path: PathBuf
+}
+
+impl DigestSink for FileSink {
+    fn deliver(&mut self, digest: &DigestResponse) -> Result<(), String> {
+        let line: String = serde_json::to_string(digest).map_err(|e| e.to_string())?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line).map_err(|e| e.to_string())
+    }
+}
+
+/// Configuration for the digest job.
+#[derive(Debug, Clone, Copy)]
+pub struct DigestConfig {
+    /// How long to wait between runs.
+    pub interval: StdDuration,
+    /// How far back to look for unseen notifications.
+    pub window: Duration,
+}
+
+/// Gathers the unseen notifications created within the window and delivers them to the sink.
+/// Returns the number of digests delivered.
+pub fn run_digest(sink: &mut dyn DigestSink, window: Duration, conn: &PooledConn) -> Result<usize, Error> {
+    let now: NaiveDateTime = Utc::now().naive_utc();
+    let digests: Vec<Digest> = Notification::get_digests(now - window, conn)?;
+
+    let mut delivered: usize = 0;
+    for digest in digests {
+        let notification_uuids: Vec<Uuid> = digest.notifications
+            .iter()
+            .map(|x| x.notification.uuid)
+            .collect();
+        let response: DigestResponse = digest.into();
+        match sink.deliver(&response) {
+            Ok(()) => {
+                Notification::mark_digested(notification_uuids, now, conn)?;
+                delivered += 1;
+            }
+            Err(e) => warn!("Couldn't deliver digest to {}: {}", response.user.user_name, e)
+        }
+    }
+    Ok(delivered)
+}
+
+/// Starts a thread that runs the digest job every interval for the life of the process.
+pub fn spawn_digest_job(config: DigestConfig, pool: Pool, mut sink: Box<dyn DigestSink>) -> JoinHandle<()> {
+    info!("Starting digest job, running every {:?}", config.interval);
+    thread::spawn(move || loop {
+        thread::sleep(config.interval);
+        let result = pool
+            .get()
+            .map_err(|_| Error::DatabaseUnavailable)
+            .and_then(|conn| run_digest(sink.as_mut(), config.window, &conn));
+        match result {
+            Ok(delivered) => info!("Delivered {} digest(s)", delivered),
+            Err(e) => error!("Digest job failed: {:?}", e)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing_fixtures::fixtures::forum::ForumFixture;
+    use testing_common::setup::setup_warp;
+    use db::post::{Post, NewPost};
+
+    /// Keeps the digests it is given, so they can be inspected.
+    #[derive(Default)]
+    struct VecSink {
+        digests: Vec<DigestResponse>
+    }
+
+    impl DigestSink for VecSink {
+        fn deliver(&mut self, digest: &DigestResponse) -> Result<(), String> {
+            self.digests.push(digest.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn notifications_are_only_digested_once() {
+        setup_warp(|fixture: &ForumFixture, pool: Pool| {
+            let conn = pool.get().expect("should get connection");
+            let reply = NewPost {
+                thread_uuid: fixture.populated_thread.uuid,
+                author_uuid: fixture.user_fixture.admin_user.uuid,
+                parent_uuid: Some(fixture.post_1.uuid),
+                created_date: Utc::now().naive_utc(),
+                content: "Reply".to_string(),
+                censored: false,
+            };
+            Post::create_and_get_user(reply, &conn).expect("should create post");
+
+            let mut sink = VecSink::default();
+            let delivered = run_digest(&mut sink, Duration::hours(1), &conn).expect("should run digest");
+            assert_eq!(delivered, 1);
+            assert_eq!(sink.digests[0].user.user_name, fixture.user_fixture.normal_user.user_name);
+            assert_eq!(sink.digests[0].notifications.len(), 1);
+
+            let delivered = run_digest(&mut sink, Duration::hours(1), &conn).expect("should run digest");
+            assert_eq!(delivered, 0);
+        })
+    }
+}
@@ -8,6 +8,7 @@ extern crate db;
 extern crate wire;
 extern crate auth;
 extern crate uuid;
+extern crate chrono;
 extern crate identifiers;
 extern crate error;
 
@@ -15,6 +16,9 @@ extern crate serde;
 extern crate serde_json;
 extern crate simplelog;
 extern crate clap;
+extern crate markdown;
+extern crate sha1;
+extern crate futures;
 
 //extern crate diesel;
 extern crate pool;
@@ -33,6 +37,10 @@ mod uuid_integration;
 mod logging;
 mod util;
 mod state;
+mod digest;
+mod publication;
+mod viewer_purge;
+mod multipart;
 
 use self::logging::setup_logging;
 use crate::state::StateConfig;
@@ -50,6 +58,18 @@ fn main() {
             .expect("Could not create admin user");
         println!("Created Admin user with UserName: Admin, Password: Admin. Please change the password immediately");
     }
+    if config.recompute_reputation {
+        let updated: usize = configuration::recompute_reputation(&state_config.database_url)
+            .expect("Could not recompute reputation");
+        println!("Recomputed the reputation of {} users", updated);
+    }
+    let digest_pool = pool::init_pool(&state_config.database_url);
+    digest::spawn_digest_job(config.digest, digest_pool, config.digest_sink);
+    let publication_pool = pool::init_pool(&state_config.database_url);
+    publication::spawn_publication_job(config.publication_interval, publication_pool, config.announcement_sink);
+    let viewer_purge_pool = pool::init_pool(&state_config.database_url);
+    viewer_purge::spawn_viewer_purge_job(viewer_purge::VIEWER_PURGE_INTERVAL, viewer_purge_pool);
+
     let state = State::init(state_config);
 
     warp::serve(self::routes::routes(&state))
@@ -66,16 +86,44 @@ mod configuration {
     use crate::state::StateConfig;
     use clap::App;
     use clap::Arg;
+    use crate::digest::DigestConfig;
+    use crate::digest::DigestSink;
+    use crate::digest::LogSink;
+    use crate::digest::FileSink;
+    use crate::publication::AnnouncementSink;
+    use crate::publication::LogAnnouncer;
+    use crate::publication::FileAnnouncer;
+    use std::time::Duration as StdDuration;
+    use std::path::PathBuf;
+    use chrono::Duration;
+    use db::reaction::ReactionSet;
+    use crate::state::rate_limit::{Action, RateLimit, RateLimitConfig};
 
     pub struct Config {
-        pub create_admin: bool
+        pub create_admin: bool,
+        pub recompute_reputation: bool,
+        pub digest: DigestConfig,
+        pub digest_sink: Box<dyn DigestSink>,
+        pub publication_interval: StdDuration,
+        pub announcement_sink: Box<dyn AnnouncementSink>
     }
 
 
 
     pub fn parse_arguments() -> (Config, StateConfig) {
         const CREATE_ADMIN: &'static str = "create_admin";
+        const RECOMPUTE_REPUTATION: &'static str = "recompute_reputation";
         const SECRET_KEY: &'static str = "secret_key";
+        const DIGEST_INTERVAL: &'static str = "digest_interval";
+        const DIGEST_WINDOW: &'static str = "digest_window";
+        const DIGEST_FILE: &'static str = "digest_file";
+        const PUBLICATION_INTERVAL: &'static str = "publication_interval";
+        const ANNOUNCEMENT_FILE: &'static str = "announcement_file";
+        const REACTIONS: &'static str = "reactions";
+        const RATE_LIMIT: &'static str = "rate_limit";
+        const UPLOAD_DIRECTORY: &'static str = "upload_directory";
+        const TRUSTED_PROXY: &'static str = "trusted_proxy";
+        const SITE_URL: &'static str = "site_url";
 
         let matches = App::new("Weekend At Joes Backend")
             .version("0.1.0")
@@ -87,6 +135,12 @@ mod configuration {
                     .help("Creates an administrator user if one doesn't already exist.")
                     .takes_value(false),
             )
+            .arg(
+                Arg::with_name(RECOMPUTE_REPUTATION)
+                    .long("recompute_reputation")
+                    .help("Recomputes every user's reputation from the votes on their posts before starting.")
+                    .takes_value(false),
+            )
             .arg(
                 Arg::with_name(SECRET_KEY)
                     .long("secret")
@@ -97,26 +151,174 @@ mod configuration {
                     )
                     .takes_value(true),
             )
+            .arg(
+                Arg::with_name(DIGEST_INTERVAL)
+                    .long("digest_interval")
+                    .value_name("MINUTES")
+                    .help("How often notification digests are sent out. Defaults to 60 minutes.")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name(DIGEST_WINDOW)
+                    .long("digest_window")
+                    .value_name("MINUTES")
+                    .help("How far back to look for unseen notifications when assembling digests. Defaults to 1440 minutes (one day).")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name(DIGEST_FILE)
+                    .long("digest_file")
+                    .value_name("PATH")
+                    .help("Appends digests to the given file instead of writing them to the log.")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name(PUBLICATION_INTERVAL)
+                    .long("publication_interval")
+                    .value_name("SECONDS")
+                    .help("How often to check for scheduled articles whose publish date has passed, so they can be announced. Defaults to 60 seconds.")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name(ANNOUNCEMENT_FILE)
+                    .long("announcement_file")
+                    .value_name("PATH")
+                    .help("Appends newly published articles to the given file instead of writing them to the log.")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name(REACTIONS)
+                    .long("reactions")
+                    .value_name("NAMES")
+                    .help("A comma separated list of the reactions that can be left on posts, in addition to up and down votes. Defaults to heart,laugh,hooray,confused.")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name(RATE_LIMIT)
+                    .long("rate_limit")
+                    .value_name("ACTION=COUNT/SECONDS")
+                    .help("Lets users perform the action (post, thread, question or message) COUNT times in a row, regaining one use every SECONDS. Can be given once per action. Moderators and admins are exempt.")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1),
+            )
+            .arg(
+                Arg::with_name(UPLOAD_DIRECTORY)
+                    .long("upload_directory")
+                    .value_name("PATH")
+                    .help("The directory that uploaded files are kept in. Thumbnails of uploaded images are made with ImageMagick, if it is installed. Defaults to ./uploads.")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name(TRUSTED_PROXY)
+                    .long("trusted_proxy")
+                    .help("Trusts the X-Forwarded-For and X-Real-IP headers to hold the client's address, so readers of articles that aren't signed in can be counted. Only use this if the server can only be reached through a proxy that sets these headers.")
+                    .takes_value(false),
+            )
+            .arg(
+                Arg::with_name(SITE_URL)
+                    .long("site_url")
+                    .value_name("URL")
+                    .help("The address the site is reached at, like https://example.com, which the links in the article and forum feeds start with. Defaults to http://localhost:8001.")
+                    .takes_value(true),
+            )
             .get_matches();
 
         let create_admin: bool = matches.is_present(CREATE_ADMIN);
+        let recompute_reputation: bool = matches.is_present(RECOMPUTE_REPUTATION);
         let secret_key: Option<String> = matches.value_of(SECRET_KEY).map(
             String::from,
         );
 
         let database_url: String = pool::DATABASE_URL.to_string();
 
+        let minutes = |name: &str, default: u64| -> u64 {
+            matches
+                .value_of(name)
+                .map(|x| x.parse().expect("Digest timings must be a whole number of minutes"))
+                .unwrap_or(default)
+        };
+        let digest = DigestConfig {
+            interval: StdDuration::from_secs(minutes(DIGEST_INTERVAL, 60) * 60),
+            window: Duration::minutes(minutes(DIGEST_WINDOW, 1440) as i64),
+        };
+        let digest_sink: Box<dyn DigestSink> = match matches.value_of(DIGEST_FILE) {
+            Some(path) => Box::new(FileSink { path: path.into() }),
+            None => Box::new(LogSink)
+        };
+
+        let publication_interval: u64 = matches
+            .value_of(PUBLICATION_INTERVAL)
+            .map(|x| x.parse().expect("The publication interval must be a whole number of seconds"))
+            .unwrap_or(60);
+        let publication_interval = StdDuration::from_secs(publication_interval);
+        let announcement_sink: Box<dyn AnnouncementSink> = match matches.value_of(ANNOUNCEMENT_FILE) {
+            Some(path) => Box::new(FileAnnouncer { path: path.into() }),
+            None => Box::new(LogAnnouncer)
+        };
+
+        let reactions: ReactionSet = match matches.value_of(REACTIONS) {
+            Some(names) => ReactionSet::new(names.split(',').map(String::from).collect()),
+            None => ReactionSet::default()
+        };
+
+        let mut rate_limits = RateLimitConfig::default();
+        for spec in matches.values_of(RATE_LIMIT).into_iter().flatten() {
+            let (action, limit) = parse_rate_limit(spec)
+                .expect("Rate limits must be of the form ACTION=COUNT/SECONDS");
+            *rate_limits.limit_mut(action) = limit;
+        }
+
+        let upload_directory: PathBuf = matches
+            .value_of(UPLOAD_DIRECTORY)
+            .unwrap_or("uploads")
+            .into();
+        let trusted_proxy: bool = matches.is_present(TRUSTED_PROXY);
+        let site_url: String = matches
+            .value_of(SITE_URL)
+            .unwrap_or(crate::state::DEFAULT_SITE_URL)
+            .to_string();
+
         let config = Config {
             create_admin,
+            recompute_reputation,
+            digest,
+            digest_sink,
+            publication_interval,
+            announcement_sink
         };
 
         let state_config = StateConfig {
             specified_secret: secret_key,
-            database_url
+            database_url,
+            reactions,
+            rate_limits,
+            upload_directory,
+            trusted_proxy,
+            site_url
         };
         (config, state_config)
     }
 
+    /// Parses a rate limit of the form `ACTION=COUNT/SECONDS`, eg. `post=10/6`.
+    fn parse_rate_limit(spec: &str) -> Option<(Action, RateLimit)> {
+        let mut parts = spec.splitn(2, '=');
+        let action = match parts.next()?.trim() {
+            "post" => Action::CreatePost,
+            "thread" => Action::CreateThread,
+            "question" => Action::CreateQuestion,
+            "message" => Action::SendMessage,
+            _ => return None
+        };
+        let mut limit = parts.next()?.splitn(2, '/');
+        let capacity: u32 = limit.next()?.trim().parse().ok()?;
+        let seconds: u64 = limit.next()?.trim().parse().ok()?;
+        if capacity == 0 || seconds == 0 {
+            return None
+        }
+        Some((action, RateLimit::new(capacity, StdDuration::from_secs(seconds))))
+    }
+
     pub fn create_admin(db_url: &str) -> Result<User, Error> {
         let conn = pool::create_single_connection(db_url);
         let mut user: NewUser = NewUserRequest {
@@ -134,6 +336,11 @@ mod configuration {
             .map_err(|_| Error::DatabaseError(Some(String::from("Admin User already exists"))))
     }
 
+    pub fn recompute_reputation(db_url: &str) -> Result<usize, Error> {
+        let conn = pool::create_single_connection(db_url);
+        User::recompute_reputation(&conn)
+    }
+
 }
 
 
@@ -0,0 +1,166 @@
+//! Reads `multipart/form-data` request bodies, which is how browsers send files.
+//!
+//! Warp doesn't parse these itself, so the parts are split out of the whole body here.
+//! Only what is needed to receive uploads is supported; nested multipart bodies aren't.
+
+/// One of the fields of the form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    /// The name of the form field.
+    pub name: String,
+    /// The name of the file, if the field held a file.
+    pub file_name: Option<String>,
+    /// The type of the content, as declared by the browser.
+    pub content_type: Option<String>,
+    pub data: Vec<u8>,
+}
+
+/// Gets the boundary that separates the parts of the body from the request's `Content-Type`,
+/// or None if the body isn't `multipart/form-data`.
+pub fn boundary(content_type: &str) -> Option<String> {
+    let mut params = content_type.split(';');
+    if params.next()?.trim().to_lowercase() != "multipart/form-data" {
+        return None
+    }
+    params
+        .filter_map(|param| {
+            let mut pair = param.splitn(2, '=');
+            let name: &str = pair.next()?.trim();
+            let value: &str = pair.next()?.trim();
+            if name.eq_ignore_ascii_case("boundary") {
+                Some(value.trim_matches('"').to_string())
+            } else {
+                None
+            }
+        })
+        .find(|boundary| !boundary.is_empty())
+}
+
+/// Splits the body into its parts.
+///
+/// Returns None if the body isn't well formed.
+pub fn parse(body: &[u8], boundary: &str) -> Option<Vec<Part>> {
+    let delimiter: Vec<u8> = format!("--{}", boundary).into_bytes();
+    // Every delimiter after the first one starts on a new line.
+    let separator: Vec<u8> = format!("\r\n--{}", boundary).into_bytes();
+
+    let mut position: usize = find(body, &delimiter, 0)? + delimiter.len();
+    let mut parts: Vec<Part> = vec![];
+    loop {
+        let rest: &[u8] = &body[position..];
+        if rest.starts_with(b"--") {
+            return Some(parts)
+        }
+        if !rest.starts_with(b"\r\n") {
+            return None
+        }
+        position += 2;
+
+        let headers_end: usize = find(body, b"\r\n\r\n", position)?;
+        let headers: &str = ::std::str::from_utf8(&body[position..headers_end]).ok()?;
+        let content_start: usize = headers_end + 4;
+        let content_end: usize = find(body, &separator, content_start)?;
+
+        let mut part = Part {
+            name: String::new(),
+            file_name: None,
+            content_type: None,
+            data: body[content_start..content_end].to_vec(),
+        };
+        for header in headers.split("\r\n") {
+            let mut header = header.splitn(2, ':');
+            let name: String = header.next()?.trim().to_lowercase();
+            let value: &str = header.next()?.trim();
+            match name.as_str() {
+                "content-disposition" => {
+                    part.name = disposition_param(value, "name")?;
+                    part.file_name = disposition_param(value, "filename");
+                }
+                "content-type" => part.content_type = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        if part.name.is_empty() {
+            return None
+        }
+        parts.push(part);
+        position = content_end + separator.len();
+    }
+}
+
+/// Gets a parameter of a `Content-Disposition` header, eg. the `filename` of `form-data; name="file"; filename="a.png"`.
+fn disposition_param(disposition: &str, param: &str) -> Option<String> {
+    disposition
+        .split(';')
+        .skip(1)
+        .filter_map(|pair| {
+            let mut pair = pair.splitn(2, '=');
+            let name: &str = pair.next()?.trim();
+            let value: &str = pair.next()?.trim();
+            if name.eq_ignore_ascii_case(param) {
+                Some(value.trim_matches('"').to_string())
+            } else {
+                None
+            }
+        })
+        .next()
+}
+
+/// Finds where the needle first appears in the haystack, at or after the start.
+fn find(haystack: &[u8], needle: &[u8], start: usize) -> Option<usize> {
+    if start > haystack.len() {
+        return None
+    }
+    haystack[start..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| index + start)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Builds a body with one file part, the way a browser would.
+    pub fn file_body(boundary: &str, file_name: &str, content_type: &str, data: &[u8]) -> Vec<u8> {
+        let mut body: Vec<u8> = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            boundary, file_name, content_type
+        ).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+        body
+    }
+
+    #[test]
+    fn boundary_is_read_from_content_type() {
+        assert_eq!(boundary("multipart/form-data; boundary=abc"), Some("abc".to_string()));
+        assert_eq!(boundary("Multipart/Form-Data; charset=utf-8; boundary=\"a b\""), Some("a b".to_string()));
+        assert_eq!(boundary("application/json"), None);
+        assert_eq!(boundary("multipart/form-data"), None);
+    }
+
+    #[test]
+    fn parts_are_split() {
+        let mut body: Vec<u8> = b"preamble\r\n--xyz\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHello\r\n".to_vec();
+        // The content of the file looks a bit like a delimiter, but isn't one.
+        body.extend(file_body("xyz", "a.png", "image/png", b"\x89PNG\r\n--xy\r\n"));
+
+        let parts: Vec<Part> = parse(&body, "xyz").expect("Should parse body");
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].name, "title");
+        assert_eq!(parts[0].file_name, None);
+        assert_eq!(parts[0].data, b"Hello".to_vec());
+        assert_eq!(parts[1].name, "file");
+        assert_eq!(parts[1].file_name, Some("a.png".to_string()));
+        assert_eq!(parts[1].content_type, Some("image/png".to_string()));
+        assert_eq!(parts[1].data, b"\x89PNG\r\n--xy\r\n".to_vec());
+    }
+
+    #[test]
+    fn malformed_bodies_are_rejected() {
+        assert_eq!(parse(b"no delimiters", "xyz"), None);
+        assert_eq!(parse(b"--xyz\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nunterminated", "xyz"), None);
+        assert_eq!(parse(b"--xyz\r\nContent-Type: text/plain\r\n\r\nno name\r\n--xyz--", "xyz"), None);
+    }
+}
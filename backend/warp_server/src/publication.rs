@@ -0,0 +1,179 @@
+//! Periodically announces articles whose publish date has passed.
+//!
+//! Articles can be scheduled to be published in the future,
+//! so rather than announcing them when they are published, this job picks them up once their embargo lapses.
+//! What an announcement amounts to is left to an `AnnouncementSink`.
+//! The server can write announcements to its log, or append them to a file for other services,
+//! like a mailer or a social media poster, to pick up.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration as StdDuration;
+use chrono::NaiveDateTime;
+use chrono::Utc;
+use uuid::Uuid;
+use db::Article;
+use identifiers::article::ArticleUuid;
+use db::article::ArticleData;
+use wire::article::ArticlePreviewResponse;
+use error::Error;
+use pool::Pool;
+use pool::PooledConn;
+
+/// Somewhere newly published articles can be announced.
+pub trait AnnouncementSink: Send {
+    /// Announces a single article.
+    /// If the announcement fails, it will be attempted again on the next run.
+    fn announce(&mut self, article: &ArticlePreviewResponse) -> Result<(), String>;
+}
+
+/// Writes announcements to the log.
+pub struct LogAnnouncer;
+
+impl AnnouncementSink for LogAnnouncer {
+    fn announce(&mut self, article: &ArticlePreviewResponse) -> Result<(), String> {
+        info!(
+            "Published article \"{}\" by {}",
+            article.title,
+            article.author.user_name
+        );
+        Ok(())
+    }
+}
+
+/// Appends each announced article to a file as a line of JSON.
+pub struct FileAnnouncer {
+    pub path: PathBuf
+}
+
+impl AnnouncementSink for FileAnnouncer {
+    fn announce(&mut self, article: &ArticlePreviewResponse) -> Result<(), String> {
+        let line: String = serde_json::to_string(article).map_err(|e| e.to_string())?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line).map_err(|e| e.to_string())
+    }
+}
+
+/// Announces the articles whose publish date has passed since the last run.
+///
+/// Returns the number of articles announced.
+pub fn run_publication(sink: &mut dyn AnnouncementSink, conn: &PooledConn) -> Result<usize, Error> {
+    let now: NaiveDateTime = Utc::now().naive_utc();
+    let articles: Vec<ArticleData> = Article::get_unannounced_articles(now, conn)?;
+
+    let mut announced: Vec<Uuid> = vec![];
+    for data in articles {
+        let article_uuid: Uuid = data.article.uuid;
+        // Embargoed articles only get their comment thread once the embargo lapses.
+        // An article whose comments can't be opened is left to be announced on the next run,
+        // rather than holding up the others.
+        if let Err(e) = Article::open_comments(ArticleUuid(article_uuid), conn) {
+            warn!("Couldn't open comments for article {}: {:?}", data.article.title, e);
+            continue
+        }
+        let response: ArticlePreviewResponse = data.into();
+        match sink.announce(&response) {
+            Ok(()) => announced.push(article_uuid),
+            Err(e) => warn!("Couldn't announce article {}: {}", response.title, e)
+        }
+    }
+    let count: usize = announced.len();
+    if count > 0 {
+        Article::mark_announced(announced, now, conn)?;
+    }
+    Ok(count)
+}
+
+/// Starts a thread that runs the publication job every interval for the life of the process.
+pub fn spawn_publication_job(interval: StdDuration, pool: Pool, mut sink: Box<dyn AnnouncementSink>) -> JoinHandle<()> {
+    info!("Starting publication job, running every {:?}", interval);
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        let result = pool
+            .get()
+            .map_err(|_| Error::DatabaseUnavailable)
+            .and_then(|conn| run_publication(sink.as_mut(), &conn));
+        match result {
+            Ok(announced) => info!("Announced {} article(s)", announced),
+            Err(e) => error!("Publication job failed: {:?}", e)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use testing_fixtures::fixtures::article::{ArticleFixture, approve_article};
+    use identifiers::user::UserUuid;
+    use testing_common::setup::setup_warp;
+
+    /// Keeps the articles it is given, so they can be inspected.
+    #[derive(Default)]
+    struct VecSink {
+        articles: Vec<ArticlePreviewResponse>
+    }
+
+    impl AnnouncementSink for VecSink {
+        fn announce(&mut self, article: &ArticlePreviewResponse) -> Result<(), String> {
+            self.articles.push(article.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn embargoed_articles_are_announced_once_their_date_passes() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let conn = pool.get().expect("should get connection");
+            let mut sink = VecSink::default();
+
+            // The fixture's published article hasn't been announced yet.
+            let announced = run_publication(&mut sink, &conn).expect("should run publication");
+            assert_eq!(announced, 1);
+            assert_eq!(sink.articles[0].uuid, ArticleUuid(fixture.published_article.uuid));
+
+            let article_uuid = ArticleUuid(fixture.unpublished_article.uuid);
+            approve_article(article_uuid, UserUuid(fixture.user_fixture.admin_user.uuid), &conn);
+            let tomorrow = Utc::now().naive_utc() + Duration::days(1);
+            let scheduled = Article::set_publish_date(article_uuid, Some(tomorrow), &conn).expect("should schedule article");
+            let announced = run_publication(&mut sink, &conn).expect("should run publication");
+            assert_eq!(announced, 0);
+            // The comment thread would give the embargoed article away.
+            assert_eq!(scheduled.comment_thread_uuid, None);
+
+            let yesterday = Utc::now().naive_utc() - Duration::days(1);
+            Article::set_publish_date(article_uuid, Some(yesterday), &conn).expect("should reschedule article");
+            let announced = run_publication(&mut sink, &conn).expect("should run publication");
+            assert_eq!(announced, 1);
+            assert_eq!(sink.articles[1].uuid, article_uuid);
+
+            let announced = run_publication(&mut sink, &conn).expect("should run publication");
+            assert_eq!(announced, 0);
+        })
+    }
+
+    #[test]
+    fn file_announcer_appends_articles_as_json() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let conn = pool.get().expect("should get connection");
+            let path: PathBuf = ::std::env::temp_dir().join(format!("announcements-{}", Uuid::new_v4()));
+            let mut sink = FileAnnouncer { path: path.clone() };
+
+            let announced = run_publication(&mut sink, &conn).expect("should run publication");
+            assert_eq!(announced, 1);
+            let contents: String = ::std::fs::read_to_string(&path).expect("should read announcements");
+            let _ = ::std::fs::remove_file(&path);
+            let lines: Vec<&str> = contents.lines().collect();
+            assert_eq!(lines.len(), 1);
+            let article: ArticlePreviewResponse = serde_json::from_str(lines[0]).expect("should deserialize announcement");
+            assert_eq!(article.uuid, ArticleUuid(fixture.published_article.uuid));
+        })
+    }
+}
@@ -10,16 +10,48 @@ use wire::article::FullArticleResponse;
 //use crate::error::Error;
 use db::Article;
 use db::article::ArticleData;
+use db::article::ArticleFilter;
+use db::tag::{Tag, TagCount};
 use wire::article::ArticlePreviewResponse;
 use crate::state::jwt::normal_user_filter;
+use crate::state::jwt::publisher_user_filter;
+use crate::state::jwt::optional_viewer_filter;
+use crate::state::jwt::Viewer;
 use identifiers::user::UserUuid;
 use wire::article::MinimalArticleResponse;
 use crate::util::json_body_filter;
+use crate::util::optional_query_filter;
 use wire::article::NewArticleRequest;
 
 use db::article::NewArticle;
 //use db::article::ArticleChangeset;
 use wire::article::UpdateArticleRequest;
+use wire::article::SchedulePublishRequest;
+use wire::article::SetCommentsRequest;
+use wire::article::SetCoAuthorsRequest;
+use wire::article::ReviewRequest;
+use wire::article::ReviewDecision;
+use wire::article::ArticleReviewResponse;
+use wire::user::UserResponse;
+use db::article_author::ArticleAuthor;
+use db::article_review::{ArticleReview, ReviewData};
+use db::User;
+use db::article_view::{ArticleDailyViews, ArticleStats, MostReadArticle};
+use wire::article::ArticleStatsQuery;
+use wire::article::ArticleStatsResponse;
+use wire::article::MostReadArticleResponse;
+use crate::state::view_counter::ViewCounter;
+use chrono::{Duration, NaiveDate};
+use warp::http::HeaderMap;
+use wire::article::ArticleListQuery;
+use wire::article::TagCountResponse;
+use wire::article::ArticleRevisionResponse;
+use wire::article::RevisionDiffResponse;
+use db::article_revision::ArticleRevision;
+use db::article_revision::RevisionDiff;
+use identifiers::article_revision::ArticleRevisionUuid;
+use chrono::NaiveDateTime;
+use chrono::Utc;
 //use crate::log_attach;
 //use crate::HttpMethod;
 use crate::logging::log_attach;
@@ -30,18 +62,53 @@ use crate::uuid_integration::uuid_wrap_filter;
 use crate::state::State;
 use pool::PooledConn;
 use error::Error;
+use warp::http::Uri;
+use crate::routes::API_STRING;
+
+/// The number of articles on each page when listing articles.
+const ARTICLES_PER_PAGE: i32 = 10;
+/// The most articles suggested as related to another.
+const RELATED_ARTICLE_LIMIT: i64 = 5;
+/// The most articles listed as the most read.
+const MOST_READ_LIMIT: i64 = 10;
+/// The number of days, up to and including today, that the most read articles are counted over.
+const MOST_READ_DAYS: i64 = 7;
+/// The number of days of view counts given when the number isn't specified.
+const DEFAULT_STATS_DAYS: i64 = 30;
+/// The most days of view counts that can be requested at once.
+const MAX_STATS_DAYS: i64 = 366;
 
 pub fn article_api(s: &State) -> BoxedFilter<(impl warp::Reply,)> {
     info!("Attaching Article API");
     warp::path("article")
         .and(
-            get_article(s)
+            get_article_by_slug(s)
+                .or(redirect_old_slug(s))
+                .or(list_articles(s))
+                .or(get_tag_counts(s))
+                .or(get_related_articles(s))
+                .or(get_most_read(s))
+                .or(get_stats(s))
+                .or(get_review_queue(s))
+                .or(get_reviews(s))
+                .or(get_article(s))
+                .or(schedule_publish(s)) // Must come before update_article, which would take the body
+                .or(autosave(s)) // Must come before update_article, which would take the body
+                .or(set_comments(s)) // Must come before update_article, which would take the body
+                .or(set_co_authors(s)) // Must come before update_article, which would take the body
+                .or(review_article(s)) // Must come before create_article, which would take the body
+                .or(submit_for_review(s))
+                .or(get_revisions(s))
+                .or(diff_revisions(s))
+                .or(get_revision(s))
+                .or(restore_revision(s))
                 .or(create_article(s))
                 .or(update_article(s))
                 .or(get_published_articles(s))
                 .or(get_owned_unpublished_articles(s))
                 .or(publish(s))
                 .or(unpublish(s))
+                .or(republish(s))
         )
         .with(warp::log("article"))
         .boxed()
@@ -55,15 +122,95 @@ fn get_article(s: &State) -> BoxedFilter<(impl Reply,)> {
 
     warp::get2()
         .and(uuid_wrap_filter())
+        .and(optional_viewer_filter(s))
+        .and(warp::header::headers_cloned())
+        .and(s.view_counter.clone())
         .and(s.db.clone())
-        .and_then(|article_uuid: ArticleUuid, conn: PooledConn| {
+        .and_then(|article_uuid: ArticleUuid, viewer: Viewer, headers: HeaderMap, view_counter: ViewCounter, conn: PooledConn| {
             Article::get_article_data(article_uuid, &conn)
+                .and_then(|data| visible_to(data, viewer))
+                .map(|data| count_view(data, viewer, &headers, &view_counter, &conn))
+                .map(convert_and_json::<ArticleData,FullArticleResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Unpublished and embargoed articles are hidden from everyone but their authors and publishers.
+fn visible_to(data: ArticleData, viewer: Viewer) -> Result<ArticleData, Error> {
+    if data.is_visible_to(viewer.user_uuid, viewer.is_publisher) {
+        Ok(data)
+    } else {
+        Err(Error::NotFound { type_name: "Article".to_string() })
+    }
+}
+
+/// Counts the viewer as a reader of the published article.
+///
+/// Authors reading their own article aren't counted, and neither are readers that can't be told apart,
+/// and the article is still returned if the view couldn't be counted.
+fn count_view(data: ArticleData, viewer: Viewer, headers: &HeaderMap, view_counter: &ViewCounter, conn: &PooledConn) -> ArticleData {
+    let is_author: bool = viewer.user_uuid.map(|user_uuid| data.is_author(user_uuid)).unwrap_or(false);
+    if data.article.is_published() && !is_author {
+        let today: NaiveDate = Utc::now().naive_utc().date();
+        if let Some(viewer_hash) = view_counter.viewer_hash(viewer.user_uuid, headers, today) {
+            if let Err(e) = ArticleDailyViews::record_view(ArticleUuid(data.article.uuid), viewer_hash, today, conn) {
+                warn!("Couldn't count a view of article {}: {:?}", data.article.uuid, e);
+            }
+        }
+    }
+    data
+}
+
+fn get_article_by_slug(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "article/slug/<slug>");
+
+    warp::get2()
+        .and(warp::path("slug"))
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(optional_viewer_filter(s))
+        .and(warp::header::headers_cloned())
+        .and(s.view_counter.clone())
+        .and(s.db.clone())
+        .and_then(|slug: String, viewer: Viewer, headers: HeaderMap, view_counter: ViewCounter, conn: PooledConn| {
+            Article::get_article_data_by_slug(&slug, &conn)
+                .and_then(|data| visible_to(data, viewer))
+                .map(|data| count_view(data, viewer, &headers, &view_counter, &conn))
                 .map(convert_and_json::<ArticleData,FullArticleResponse>)
                 .map_err(Error::simple_reject)
         })
         .boxed()
 }
 
+/// Permanently redirects slugs that articles used before their titles changed to their current slug.
+///
+/// Articles the viewer can't see aren't found, so their current slugs aren't given away.
+fn redirect_old_slug(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "article/slug/<old slug>");
+
+    warp::get2()
+        .and(warp::path("slug"))
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(optional_viewer_filter(s))
+        .and(s.db.clone())
+        .and_then(|old_slug: String, viewer: Viewer, conn: PooledConn| {
+            let current_slug: String = Article::get_current_slug(&old_slug, &conn)
+                .and_then(|current_slug| Article::get_article_data_by_slug(&current_slug, &conn))
+                .and_then(|data| visible_to(data, viewer))
+                .map(|data| data.article.slug)
+                .map_err(Error::simple_reject)?;
+            format!("/{}/article/slug/{}", API_STRING, current_slug)
+                .parse::<Uri>()
+                .map(warp::redirect)
+                .map_err(|_| Error::InternalServerError.simple_reject())
+        })
+        .boxed()
+}
+
 fn get_published_articles(s: &State) -> BoxedFilter<(impl Reply,)> {
     log_attach(HttpMethod::Get, "article/<index=i32>/<page_size=i32>");
     warp::get2()
@@ -71,13 +218,62 @@ fn get_published_articles(s: &State) -> BoxedFilter<(impl Reply,)> {
         .and(warp::path::param::<i32>())
         .and(s.db.clone())
         .and_then(|index: i32, page_size: i32, conn: PooledConn| {
-            Article::get_paginated(index, page_size, &conn)
+            Article::get_paginated(&ArticleFilter::default(), index, page_size, &conn)
+                .map(convert_vector_and_json::<ArticleData,ArticlePreviewResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+
+/// Lists the published articles, optionally only those with a tag or by an author.
+fn list_articles(s: &State) -> BoxedFilter<(impl Reply,)> {
+    log_attach(HttpMethod::Get, "article?tag=<tag>&author=<uuid>&page=<i32>");
+    warp::get2()
+        .and(warp::path::end())
+        .and(optional_query_filter::<ArticleListQuery>())
+        .and(s.db.clone())
+        .and_then(|query: ArticleListQuery, conn: PooledConn| {
+            let filter = ArticleFilter {
+                tag: query.tag,
+                author_uuid: query.author.map(UserUuid),
+            };
+            Article::get_paginated(&filter, query.page.unwrap_or(1), ARTICLES_PER_PAGE, &conn)
                 .map(convert_vector_and_json::<ArticleData,ArticlePreviewResponse>)
                 .map_err(Error::simple_reject)
         })
         .boxed()
 }
 
+/// Gets the tags of published articles, and how many articles have each one.
+fn get_tag_counts(s: &State) -> BoxedFilter<(impl Reply,)> {
+    log_attach(HttpMethod::Get, "article/tags");
+    warp::get2()
+        .and(warp::path("tags"))
+        .and(warp::path::end())
+        .and(s.db.clone())
+        .and_then(|conn: PooledConn| {
+            Tag::get_tag_counts(&conn)
+                .map(convert_vector_and_json::<TagCount,TagCountResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Suggests published articles that share tags with the article.
+fn get_related_articles(s: &State) -> BoxedFilter<(impl Reply,)> {
+    log_attach(HttpMethod::Get, "article/related/<uuid>");
+    warp::get2()
+        .and(warp::path("related"))
+        .and(uuid_wrap_filter())
+        .and(s.db.clone())
+        .and_then(|article_uuid: ArticleUuid, conn: PooledConn| {
+            Article::get_related(article_uuid, RELATED_ARTICLE_LIMIT, &conn)
+                .map(convert_vector_and_json::<ArticleData,ArticlePreviewResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
 
 fn get_owned_unpublished_articles(s: &State) -> BoxedFilter<(impl Reply,)> {
 
@@ -104,10 +300,11 @@ fn create_article(s: &State) -> BoxedFilter<(impl Reply,)> {
         .and(normal_user_filter(s))
         .and(s.db.clone())
         .and_then(|request: NewArticleRequest, user_uuid: UserUuid, conn: PooledConn| {
+            let tags: Vec<String> = request.tags.clone();
             let mut request: NewArticle = request.into();
             request.author_uuid = user_uuid.0; // This api isn't perfect - so the uuid must be gotten from the jwt
 
-            Article::create_article(request.into(), &conn)
+            Article::create_article_with_tags(request, tags, &conn)
                 .map(convert_and_json::<Article,MinimalArticleResponse>)
                 .map_err(Error::simple_reject)
         })
@@ -124,13 +321,11 @@ fn update_article(s: &State) -> BoxedFilter<(impl Reply,)> {
         .and(normal_user_filter(s))
         .and(s.db.clone())
         .and_then(|request: UpdateArticleRequest, user_uuid: UserUuid, conn: PooledConn|{
-            let article_to_update: Article = Article::get_article(request.uuid, &conn)
-                .map_err(Error::simple_reject)?;
-            if article_to_update.author_uuid != user_uuid.0 {
-                return Error::NotAuthorized {reason: "User not author"}.reject()
+            if let Err(e) = get_authored_article(request.uuid, user_uuid, &conn) {
+                return e.reject()
             }
-
-            Article::update_article(request.into(), &conn)
+            let tags: Option<Vec<String>> = request.tags.clone();
+            Article::update_article_with_tags(request.into(), tags, &conn)
                 .map(convert_and_json::<Article,MinimalArticleResponse>)
                 .map_err(Error::simple_reject)
         })
@@ -148,13 +343,8 @@ fn publish(s: &State) -> BoxedFilter<(impl Reply,)> {
         .and(normal_user_filter(s))
         .and(s.db.clone())
         .and_then(|article_uuid: ArticleUuid, user_uuid: UserUuid, conn: PooledConn|{
-            let article_to_update: Article = Article::get_article(article_uuid, &conn)
-                .map_err(Error::simple_reject)?;
-            if article_to_update.author_uuid != user_uuid.0 {
-                return Error::NotAuthorized {reason: "User not author"}.reject()
-            }
-
-            Article::set_publish_status(article_uuid, true, &conn)
+            get_authored_article(article_uuid, user_uuid, &conn)
+                .and_then(|_| Article::set_publish_status(article_uuid, true, &conn))
                 .map(|_| warp::http::StatusCode::NO_CONTENT)
                 .map_err(Error::simple_reject)
         })
@@ -171,17 +361,823 @@ fn unpublish(s: &State) -> BoxedFilter<(impl Reply,)> {
         .and(normal_user_filter(s))
         .and(s.db.clone())
         .and_then(|uuid: Uuid, user_uuid: UserUuid, conn: PooledConn| {
-            let article_uuid = ArticleUuid(uuid);
-            let article_to_update: Article = Article::get_article(article_uuid, &conn)
+            get_authored_article(ArticleUuid(uuid), user_uuid, &conn)
+                .and_then(|_| Article::set_publish_status(ArticleUuid(uuid), false, &conn))
+                .map(|_| warp::http::StatusCode::NO_CONTENT)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Gets the article, as long as the user wrote it, or is one of its co-authors.
+fn get_authored_article(article_uuid: ArticleUuid, user_uuid: UserUuid, conn: &PooledConn) -> Result<Article, Error> {
+    let article: Article = Article::get_article(article_uuid, conn)?;
+    if !article.is_author(user_uuid, conn)? {
+        return Err(Error::NotAuthorized {reason: "User not author"})
+    }
+    Ok(article)
+}
+
+/// Saves a revision from the editor, without changing what readers of a published article see.
+fn autosave(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "article/autosave");
+
+    warp::put2()
+        .and(warp::path("autosave"))
+        .and(json_body_filter(128))
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|request: UpdateArticleRequest, user_uuid: UserUuid, conn: PooledConn| {
+            get_authored_article(request.uuid, user_uuid, &conn)
+                .and_then(|_| Article::autosave(request.into(), &conn))
+                .map(convert_and_json::<ArticleRevision,ArticleRevisionResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+fn get_revisions(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "article/revisions/<uuid>");
+
+    warp::get2()
+        .and(warp::path("revisions"))
+        .and(uuid_wrap_filter())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|article_uuid: ArticleUuid, user_uuid: UserUuid, conn: PooledConn| {
+            get_authored_article(article_uuid, user_uuid, &conn)
+                .and_then(|_| ArticleRevision::get_revisions_for_article(article_uuid, &conn))
+                .map(convert_vector_and_json::<ArticleRevision,ArticleRevisionResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+fn get_revision(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "article/revision/<uuid>");
+
+    warp::get2()
+        .and(warp::path("revision"))
+        .and(uuid_wrap_filter())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|revision_uuid: ArticleRevisionUuid, user_uuid: UserUuid, conn: PooledConn| {
+            ArticleRevision::get_revision(revision_uuid, &conn)
+                .and_then(|revision| {
+                    get_authored_article(ArticleUuid(revision.article_uuid), user_uuid, &conn)
+                        .map(|_| revision)
+                })
+                .map(convert_and_json::<ArticleRevision,ArticleRevisionResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+fn diff_revisions(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "article/revision/diff/<from uuid>/<to uuid>");
+
+    warp::get2()
+        .and(warp::path("revision"))
+        .and(warp::path("diff"))
+        .and(uuid_wrap_filter())
+        .and(uuid_wrap_filter())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|from: ArticleRevisionUuid, to: ArticleRevisionUuid, user_uuid: UserUuid, conn: PooledConn| {
+            ArticleRevision::diff(from, to, &conn)
+                .and_then(|diff| {
+                    get_authored_article(ArticleUuid(diff.from.article_uuid), user_uuid, &conn)
+                        .map(|_| diff)
+                })
+                .map(convert_and_json::<RevisionDiff,RevisionDiffResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Saves a copy of the revision as the article's latest one.
+fn restore_revision(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "article/revision/restore/<uuid>");
+
+    warp::put2()
+        .and(warp::path("revision"))
+        .and(warp::path("restore"))
+        .and(uuid_wrap_filter())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|revision_uuid: ArticleRevisionUuid, user_uuid: UserUuid, conn: PooledConn| {
+            ArticleRevision::get_revision(revision_uuid, &conn)
+                .and_then(|revision| get_authored_article(ArticleUuid(revision.article_uuid), user_uuid, &conn))
+                .and_then(|_| Article::restore_revision(revision_uuid, &conn))
+                .map(|(_, revision)| convert_and_json::<ArticleRevision,ArticleRevisionResponse>(revision))
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Shows the article's latest draft to readers.
+fn republish(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "article/republish/<uuid>");
+
+    warp::put2()
+        .and(warp::path("republish"))
+        .and(uuid_wrap_filter())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|article_uuid: ArticleUuid, user_uuid: UserUuid, conn: PooledConn| {
+            get_authored_article(article_uuid, user_uuid, &conn)
+                .and_then(|_| Article::publish_draft(article_uuid, &conn))
+                .map(convert_and_json::<Article,MinimalArticleResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Sets a future publish date for the article, keeping it hidden until then.
+fn schedule_publish(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "article/schedule/<uuid>");
+
+    warp::put2()
+        .and(warp::path("schedule"))
+        .and(uuid_wrap_filter())
+        .and(json_body_filter(1))
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|article_uuid: ArticleUuid, request: SchedulePublishRequest, user_uuid: UserUuid, conn: PooledConn| {
+            get_authored_article(article_uuid, user_uuid, &conn)
                 .map_err(Error::simple_reject)?;
-            if article_to_update.author_uuid != user_uuid.0 {
+            let now: NaiveDateTime = Utc::now().naive_utc();
+            if request.publish_date <= now {
+                return Error::BadRequest.reject()
+            }
+
+            Article::set_publish_date(article_uuid, Some(request.publish_date), &conn)
+                .map(convert_and_json::<Article,MinimalArticleResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Allows or disallows comments on the article.
+/// Existing comments stay readable when comments are disallowed.
+fn set_comments(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "article/comments/<uuid>");
+
+    warp::put2()
+        .and(warp::path("comments"))
+        .and(uuid_wrap_filter())
+        .and(json_body_filter(1))
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|article_uuid: ArticleUuid, request: SetCommentsRequest, user_uuid: UserUuid, conn: PooledConn| {
+            get_authored_article(article_uuid, user_uuid, &conn)
+                .and_then(|_| Article::set_comments_enabled(article_uuid, request.enabled, &conn))
+                .map(convert_and_json::<Article,MinimalArticleResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Lists the published articles that the most people read over the last week.
+fn get_most_read(s: &State) -> BoxedFilter<(impl Reply,)> {
+    log_attach(HttpMethod::Get, "article/most_read");
+    warp::get2()
+        .and(warp::path("most_read"))
+        .and(warp::path::end())
+        .and(s.db.clone())
+        .and_then(|conn: PooledConn| {
+            let now: NaiveDateTime = Utc::now().naive_utc();
+            let since: NaiveDate = now.date() - Duration::days(MOST_READ_DAYS - 1);
+            Article::get_most_read(since, now, MOST_READ_LIMIT, &conn)
+                .map(convert_vector_and_json::<MostReadArticle,MostReadArticleResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Gets how many people viewed the article on each of the last few days, for publishers.
+fn get_stats(s: &State) -> BoxedFilter<(impl Reply,)> {
+    log_attach(HttpMethod::Get, "article/stats/<uuid>?days=<i64>");
+    warp::get2()
+        .and(warp::path("stats"))
+        .and(uuid_wrap_filter())
+        .and(optional_query_filter::<ArticleStatsQuery>())
+        .and(publisher_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|article_uuid: ArticleUuid, query: ArticleStatsQuery, _user_uuid: UserUuid, conn: PooledConn| {
+            let days: i64 = query.days.unwrap_or(DEFAULT_STATS_DAYS);
+            if days < 1 || days > MAX_STATS_DAYS {
+                return Error::BadRequest.reject()
+            }
+            let today: NaiveDate = Utc::now().naive_utc().date();
+            let first: NaiveDate = today - Duration::days(days - 1);
+            Article::get_article(article_uuid, &conn)
+                .and_then(|_| ArticleDailyViews::get_stats(article_uuid, first, today, &conn))
+                .map(convert_and_json::<ArticleStats,ArticleStatsResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Replaces the article's co-authors.
+/// Only the article's original author can choose who they write it with.
+fn set_co_authors(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "article/co_authors/<uuid>");
+
+    warp::put2()
+        .and(warp::path("co_authors"))
+        .and(uuid_wrap_filter())
+        .and(json_body_filter(4))
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|article_uuid: ArticleUuid, request: SetCoAuthorsRequest, user_uuid: UserUuid, conn: PooledConn| {
+            let article: Article = Article::get_article(article_uuid, &conn)
+                .map_err(Error::simple_reject)?;
+            if article.author_uuid != user_uuid.0 {
                 return Error::NotAuthorized {reason: "User not author"}.reject()
+            }
+
+            ArticleAuthor::set_co_authors(&article, request.co_author_uuids, &conn)
+                .map(convert_vector_and_json::<User,UserResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Asks the publishers to review the article.
+fn submit_for_review(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "article/submit/<uuid>");
 
+    warp::put2()
+        .and(warp::path("submit"))
+        .and(uuid_wrap_filter())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|article_uuid: ArticleUuid, user_uuid: UserUuid, conn: PooledConn| {
+            get_authored_article(article_uuid, user_uuid, &conn)
+                .and_then(|_| Article::submit_for_review(article_uuid, &conn))
+                .map(convert_and_json::<Article,MinimalArticleResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Gets the user, as long as they can take part in the article's review,
+/// which is open to its authors and publishers.
+fn get_reviewer(article_uuid: ArticleUuid, viewer: Viewer, conn: &PooledConn) -> Result<UserUuid, Error> {
+    let user_uuid: UserUuid = viewer.user_uuid.ok_or(Error::MissingToken)?;
+    if !viewer.is_publisher {
+        get_authored_article(article_uuid, user_uuid, conn)?;
+    }
+    Ok(user_uuid)
+}
+
+/// Comments on the article, or approves it or requests changes to it.
+/// Only publishers that didn't write the article can approve it or request changes.
+fn review_article(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Post, "article/review/<uuid>");
+
+    warp::post2()
+        .and(warp::path("review"))
+        .and(uuid_wrap_filter())
+        .and(json_body_filter(16))
+        .and(optional_viewer_filter(s))
+        .and(s.db.clone())
+        .and_then(|article_uuid: ArticleUuid, request: ReviewRequest, viewer: Viewer, conn: PooledConn| {
+            let reviewer_uuid: UserUuid = get_reviewer(article_uuid, viewer, &conn)
+                .map_err(Error::simple_reject)?;
+            if request.decision != ReviewDecision::Comment && !viewer.is_publisher {
+                return Error::NotAuthorized {reason: "Only publishers can approve articles"}.reject()
             }
 
-            Article::set_publish_status(ArticleUuid(uuid), false, &conn)
-                .map(|_| warp::http::StatusCode::NO_CONTENT)
+            ArticleReview::review(article_uuid, reviewer_uuid, request.decision, request.comment, &conn)
+                .map(convert_and_json::<ReviewData,ArticleReviewResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+fn get_reviews(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "article/review/<uuid>");
+
+    warp::get2()
+        .and(warp::path("review"))
+        .and(uuid_wrap_filter())
+        .and(optional_viewer_filter(s))
+        .and(s.db.clone())
+        .and_then(|article_uuid: ArticleUuid, viewer: Viewer, conn: PooledConn| {
+            get_reviewer(article_uuid, viewer, &conn)
+                .and_then(|_| ArticleReview::get_reviews_for_article(article_uuid, &conn))
+                .map(convert_vector_and_json::<ReviewData,ArticleReviewResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Lists the articles waiting to be reviewed, for publishers to pick from.
+fn get_review_queue(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "article/review_queue");
+
+    warp::get2()
+        .and(warp::path("review_queue"))
+        .and(publisher_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|_user_uuid: UserUuid, conn: PooledConn| {
+            Article::get_review_queue(&conn)
+                .map(convert_vector_and_json::<ArticleData,ArticlePreviewResponse>)
                 .map_err(Error::simple_reject)
         })
         .boxed()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing_fixtures::fixtures::article::{ArticleFixture, approve_article};
+    use testing_common::setup::setup_warp;
+    use pool::Pool;
+    use crate::util::test::deserialize;
+    use db::article::ArticleChangeset;
+    use crate::state::jwt::AUTHORIZATION_HEADER_KEY;
+    use wire::user::BEARER;
+    use wire::article::ReviewState;
+    use crate::state::view_counter::view_counter_filter;
+
+    #[test]
+    fn get_by_slug_and_redirect_old_slug() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let s = State::testing_init(pool.clone(), fixture.user_fixture.secret.clone());
+            let old_slug = fixture.published_article.slug.clone();
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/slug/{}", old_slug))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let article: FullArticleResponse = deserialize(response);
+            assert_eq!(article.id, ArticleUuid(fixture.published_article.uuid));
+
+            let changeset = ArticleChangeset {
+                uuid: fixture.published_article.uuid,
+                title: Some("Renamed".to_string()),
+                body: None,
+            };
+            let conn = pool.get().expect("should get connection");
+            Article::update_article(changeset, &conn).expect("should rename article");
+            approve_article(ArticleUuid(fixture.published_article.uuid), UserUuid(fixture.user_fixture.admin_user.uuid), &conn);
+            let renamed: Article = Article::publish_draft(ArticleUuid(fixture.published_article.uuid), &conn)
+                .expect("should publish renamed article");
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/slug/{}", old_slug))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 301);
+            assert_eq!(response.headers()["Location"], format!("/api/article/slug/{}", renamed.slug).as_str());
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/slug/{}", renamed.slug))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let article: FullArticleResponse = deserialize(response);
+            assert_eq!(article.slug, renamed.slug);
+
+            // Old slugs of articles that can't be seen aren't redirected.
+            Article::set_publish_status(ArticleUuid(renamed.uuid), false, &conn).expect("should unpublish article");
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/slug/{}", old_slug))
+                .reply(&article_api(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 404);
+        })
+    }
+
+    #[test]
+    fn scheduled_articles_are_hidden_until_published() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let conn = pool.get().expect("should get connection");
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let article_uuid = fixture.unpublished_article.uuid;
+            approve_article(ArticleUuid(article_uuid), UserUuid(fixture.user_fixture.admin_user.uuid), &conn);
+
+            let past = SchedulePublishRequest { publish_date: Utc::now().naive_utc() - Duration::days(1) };
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&format!("/schedule/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .header("Content-Length", "1000") // Requires sized length
+                .json(&past)
+                .reply(&schedule_publish(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 400);
+
+            let future = SchedulePublishRequest { publish_date: Utc::now().naive_utc() + Duration::days(1) };
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&format!("/article/schedule/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .header("Content-Length", "1000") // Requires sized length
+                .json(&future)
+                .reply(&article_api(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 200);
+            let article: MinimalArticleResponse = deserialize(response);
+            // The database only stores microseconds.
+            assert_eq!(article.publish_date.map(|x| x.timestamp()), Some(future.publish_date.timestamp()));
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/{}", article_uuid))
+                .reply(&article_api(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 404);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&article_api(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 200);
+        })
+    }
+
+    #[test]
+    fn autosave_and_republish() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let s = State::testing_init(pool.clone(), fixture.user_fixture.secret.clone());
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let article_uuid = ArticleUuid(fixture.published_article.uuid);
+
+            let request = UpdateArticleRequest {
+                uuid: article_uuid,
+                title: None,
+                body: Some("Autosaved body".to_string()),
+                tags: None,
+            };
+            let response = warp::test::request()
+                .method("PUT")
+                .path("/article/autosave")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .header("Content-Length", "1000") // Requires sized length
+                .json(&request)
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let revision: ArticleRevisionResponse = deserialize(response);
+            assert!(revision.autosave);
+
+            // Readers still see the published revision.
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/{}", article_uuid))
+                .reply(&article_api(&s));
+            let article: FullArticleResponse = deserialize(response);
+            assert_eq!(article.body, fixture.published_article.body);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/revisions/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&article_api(&s));
+            let revisions: Vec<ArticleRevisionResponse> = deserialize(response);
+            assert_eq!(revisions.len(), 2);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/revision/diff/{}/{}", revisions[1].uuid, revisions[0].uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&article_api(&s));
+            let diff: RevisionDiffResponse = deserialize(response);
+            assert!(diff.body.contains(&wire::article::DiffLineResponse::Added("Autosaved body".to_string())));
+
+            let republish = || {
+                warp::test::request()
+                    .method("PUT")
+                    .path(&format!("/article/republish/{}", article_uuid))
+                    .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                    .reply(&article_api(&s).recover(error::warp_support::customize_error))
+            };
+            // The changes have to be approved before they are published.
+            assert_eq!(republish().status(), 403);
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&format!("/article/publish/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&article_api(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 403);
+
+            let conn = pool.get().expect("should get connection");
+            approve_article(article_uuid, UserUuid(fixture.user_fixture.admin_user.uuid), &conn);
+            let response = republish();
+            assert_eq!(response.status(), 200);
+            let article: MinimalArticleResponse = deserialize(response);
+            assert_eq!(article.body, "Autosaved body");
+            assert_eq!(article.published_revision_uuid, Some(revision.uuid));
+        })
+    }
+
+    #[test]
+    fn tagged_articles_are_listed_and_counted() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let article_uuid = ArticleUuid(fixture.published_article.uuid);
+
+            let request = UpdateArticleRequest {
+                uuid: article_uuid,
+                title: None,
+                body: None,
+                tags: Some(vec!["Weekend News".to_string()]),
+            };
+            let response = warp::test::request()
+                .method("PUT")
+                .path("/article/")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .header("Content-Length", "1000") // Requires sized length
+                .json(&request)
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/article?tag=weekend-news&page=1")
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let articles: Vec<ArticlePreviewResponse> = deserialize(response);
+            assert_eq!(articles.len(), 1);
+            assert_eq!(articles[0].tags, vec!["weekend-news"]);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article?author={}", fixture.user_fixture.admin_user.uuid))
+                .reply(&article_api(&s));
+            let articles: Vec<ArticlePreviewResponse> = deserialize(response);
+            assert!(articles.is_empty());
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/article/tags")
+                .reply(&article_api(&s));
+            let counts: Vec<TagCountResponse> = deserialize(response);
+            assert_eq!(counts, vec![TagCountResponse { name: "weekend-news".to_string(), article_count: 1 }]);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/related/{}", article_uuid))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let related: Vec<ArticlePreviewResponse> = deserialize(response);
+            assert!(related.is_empty());
+        })
+    }
+
+    #[test]
+    fn published_articles_have_comments() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/slug/{}", fixture.published_article.slug))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let article: FullArticleResponse = deserialize(response);
+            assert!(article.comment_thread_uuid.is_some());
+            assert_eq!(article.comment_count, 0);
+
+            let request = SetCommentsRequest { enabled: false };
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&format!("/article/comments/{}", fixture.published_article.uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .header("Content-Length", "1000") // Requires sized length
+                .json(&request)
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let article: MinimalArticleResponse = deserialize(response);
+            assert!(!article.comments_enabled);
+
+            // Only the author can change whether the article can be commented on.
+            let admin_jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.admin_user.user_name.clone());
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&format!("/article/comments/{}", fixture.published_article.uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, admin_jwt))
+                .header("Content-Length", "1000") // Requires sized length
+                .json(&request)
+                .reply(&article_api(&s));
+            assert_ne!(response.status(), 200);
+        })
+    }
+
+    #[test]
+    fn articles_are_reviewed_before_publishing() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let author_jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let publisher_jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.admin_user.user_name.clone());
+            let article_uuid = fixture.unpublished_article.uuid;
+            let publish = |jwt: &str| {
+                warp::test::request()
+                    .method("PUT")
+                    .path(&format!("/article/publish/{}", article_uuid))
+                    .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                    .reply(&article_api(&s).recover(error::warp_support::customize_error))
+            };
+            let review = |jwt: &str, decision: ReviewDecision| {
+                let request = ReviewRequest { decision, comment: "Looks good".to_string() };
+                warp::test::request()
+                    .method("POST")
+                    .path(&format!("/article/review/{}", article_uuid))
+                    .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                    .header("Content-Length", "1000") // Requires sized length
+                    .json(&request)
+                    .reply(&article_api(&s).recover(error::warp_support::customize_error))
+            };
+
+            assert_eq!(publish(&author_jwt).status(), 403);
+
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&format!("/article/submit/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, author_jwt))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let article: MinimalArticleResponse = deserialize(response);
+            assert_eq!(article.review_state, ReviewState::InReview);
+
+            // Only publishers see the queue.
+            let response = warp::test::request()
+                .method("GET")
+                .path("/article/review_queue")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, author_jwt))
+                .reply(&article_api(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 403);
+            let response = warp::test::request()
+                .method("GET")
+                .path("/article/review_queue")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, publisher_jwt))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let queue: Vec<ArticlePreviewResponse> = deserialize(response);
+            assert_eq!(queue.len(), 1);
+            assert_eq!(queue[0].uuid, ArticleUuid(article_uuid));
+
+            // The author can comment on the review, but not approve their own article.
+            assert_eq!(review(&author_jwt, ReviewDecision::Approve).status(), 403);
+            assert_eq!(review(&author_jwt, ReviewDecision::Comment).status(), 200);
+            assert_eq!(review(&publisher_jwt, ReviewDecision::Approve).status(), 200);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/review/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, author_jwt))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let reviews: Vec<ArticleReviewResponse> = deserialize(response);
+            assert_eq!(reviews.len(), 2);
+            assert_eq!(reviews[1].decision, ReviewDecision::Approve);
+            assert_eq!(reviews[1].reviewer.uuid, UserUuid(fixture.user_fixture.admin_user.uuid));
+
+            assert_eq!(publish(&author_jwt).status(), 204);
+        })
+    }
+
+    #[test]
+    fn co_authors_can_edit_but_not_approve() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let author_jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let co_author_jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.admin_user.user_name.clone());
+            let article_uuid = fixture.unpublished_article.uuid;
+
+            let request = SetCoAuthorsRequest { co_author_uuids: vec![UserUuid(fixture.user_fixture.admin_user.uuid)] };
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&format!("/article/co_authors/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, author_jwt))
+                .header("Content-Length", "1000") // Requires sized length
+                .json(&request)
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let co_authors: Vec<UserResponse> = deserialize(response);
+            assert_eq!(co_authors.len(), 1);
+
+            let update = UpdateArticleRequest {
+                uuid: ArticleUuid(article_uuid),
+                title: None,
+                body: Some("Written together".to_string()),
+                tags: None,
+            };
+            let response = warp::test::request()
+                .method("PUT")
+                .path("/article/")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, co_author_jwt))
+                .header("Content-Length", "1000") // Requires sized length
+                .json(&update)
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, co_author_jwt))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let article: FullArticleResponse = deserialize(response);
+            assert_eq!(article.body, "Written together");
+            assert_eq!(article.co_authors[0].uuid, UserUuid(fixture.user_fixture.admin_user.uuid));
+
+            // Being a publisher doesn't let a co-author approve the article.
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&format!("/article/submit/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, co_author_jwt))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let request = ReviewRequest { decision: ReviewDecision::Approve, comment: String::new() };
+            let response = warp::test::request()
+                .method("POST")
+                .path(&format!("/article/review/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, co_author_jwt))
+                .header("Content-Length", "1000") // Requires sized length
+                .json(&request)
+                .reply(&article_api(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 403);
+        })
+    }
+
+    #[test]
+    fn views_are_counted_once_a_day_per_reader() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let untrusted = State::testing_init(pool.clone(), fixture.user_fixture.secret.clone());
+            let mut s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            s.view_counter = view_counter_filter(ViewCounter::new(true));
+            let author_jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let publisher_jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.admin_user.user_name.clone());
+            let article_uuid = fixture.published_article.uuid;
+            let view = |s: &State, address: &str| {
+                let response = warp::test::request()
+                    .method("GET")
+                    .path(&format!("/article/slug/{}", fixture.published_article.slug))
+                    .header("X-Forwarded-For", address)
+                    .reply(&article_api(s));
+                assert_eq!(response.status(), 200);
+            };
+
+            // Without a trusted proxy, the address could be made up, so anonymous readers aren't counted.
+            view(&untrusted, "203.0.113.6");
+            view(&s, "203.0.113.7");
+            view(&s, "203.0.113.7");
+            view(&s, "203.0.113.8");
+            // The author reading their own article isn't counted.
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, author_jwt))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/stats/{}?days=3", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, publisher_jwt))
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let stats: ArticleStatsResponse = deserialize(response);
+            assert_eq!(stats.total_views, 2);
+            let views: Vec<i64> = stats.daily_views.iter().map(|day| day.views).collect();
+            assert_eq!(views, vec![0, 0, 2]);
+
+            // Only publishers can see the stats.
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/article/stats/{}", article_uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, author_jwt))
+                .reply(&article_api(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 403);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/article/most_read")
+                .reply(&article_api(&s));
+            assert_eq!(response.status(), 200);
+            let most_read: Vec<MostReadArticleResponse> = deserialize(response);
+            assert_eq!(most_read.len(), 1);
+            assert_eq!(most_read[0].article.uuid, ArticleUuid(article_uuid));
+            assert_eq!(most_read[0].views, 2);
+        })
+    }
+}
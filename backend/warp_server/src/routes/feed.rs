@@ -0,0 +1,399 @@
+//! Atom and RSS feeds for the published articles, and for the threads in each forum.
+//!
+//! These live outside of the api, at `/feed/articles.atom`, `/feed/articles.rss`,
+//! `/feed/forum/<forum uuid>.atom` and `/feed/forum/<forum uuid>.rss`.
+
+use std::collections::HashMap;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+use warp::Filter;
+use warp::filters::BoxedFilter;
+use warp::reply::Reply;
+use warp::Rejection;
+use warp::http::HeaderMap;
+use warp::http::Response;
+use warp::http::StatusCode;
+use warp::http::header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use sha1::{Digest, Sha1};
+use db::Article;
+use db::Forum;
+use db::Post;
+use db::Thread;
+use db::article::ArticleData;
+use db::article_revision::ArticleRevision;
+use db::thread::{MinimalThreadData, ThreadOrder};
+use identifiers::forum::ForumUuid;
+use error::Error;
+use pool::PooledConn;
+use crate::state::State;
+use markdown::render_html;
+
+/// The number of entries in each feed.
+const FEED_SIZE: i32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FeedFormat {
+    Atom,
+    Rss,
+}
+
+impl FeedFormat {
+    /// Splits a path segment like `articles.atom` into its name and format.
+    fn split(segment: &str) -> Option<(&str, FeedFormat)> {
+        if segment.ends_with(".atom") {
+            Some((&segment[..segment.len() - ".atom".len()], FeedFormat::Atom))
+        } else if segment.ends_with(".rss") {
+            Some((&segment[..segment.len() - ".rss".len()], FeedFormat::Rss))
+        } else {
+            None
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+        }
+    }
+}
+
+struct Feed {
+    title: String,
+    description: String,
+    /// The url of the feed itself.
+    url: String,
+    /// The url of the page the feed follows.
+    link: String,
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    uuid: Uuid,
+    title: String,
+    link: String,
+    author: String,
+    published: NaiveDateTime,
+    updated: NaiveDateTime,
+    /// Sanitized HTML.
+    content: String,
+}
+
+impl Feed {
+    /// The feed was last updated when its most recently updated entry was.
+    fn updated(&self) -> NaiveDateTime {
+        self.entries
+            .iter()
+            .map(|entry| entry.updated)
+            .max()
+            .unwrap_or_else(|| NaiveDateTime::from_timestamp(0, 0))
+    }
+
+    fn render(&self, format: FeedFormat) -> String {
+        match format {
+            FeedFormat::Atom => self.to_atom(),
+            FeedFormat::Rss => self.to_rss(),
+        }
+    }
+
+    fn to_atom(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        xml.push_str(&format!("<title>{}</title>\n", escape(&self.title)));
+        xml.push_str(&format!("<subtitle>{}</subtitle>\n", escape(&self.description)));
+        xml.push_str(&format!("<link href=\"{}\" rel=\"self\"/>\n", escape(&self.url)));
+        xml.push_str(&format!("<link href=\"{}\"/>\n", escape(&self.link)));
+        xml.push_str(&format!("<id>{}</id>\n", escape(&self.url)));
+        xml.push_str(&format!("<updated>{}</updated>\n", atom_date(self.updated())));
+        for entry in &self.entries {
+            xml.push_str("<entry>\n");
+            xml.push_str(&format!("<title>{}</title>\n", escape(&entry.title)));
+            xml.push_str(&format!("<link href=\"{}\"/>\n", escape(&entry.link)));
+            xml.push_str(&format!("<id>urn:uuid:{}</id>\n", entry.uuid));
+            xml.push_str(&format!("<published>{}</published>\n", atom_date(entry.published)));
+            xml.push_str(&format!("<updated>{}</updated>\n", atom_date(entry.updated)));
+            xml.push_str(&format!("<author><name>{}</name></author>\n", escape(&entry.author)));
+            xml.push_str(&format!("<content type=\"html\">{}</content>\n", escape(&entry.content)));
+            xml.push_str("</entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    fn to_rss(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<rss version=\"2.0\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n<channel>\n");
+        xml.push_str(&format!("<title>{}</title>\n", escape(&self.title)));
+        xml.push_str(&format!("<link>{}</link>\n", escape(&self.link)));
+        xml.push_str(&format!("<description>{}</description>\n", escape(&self.description)));
+        xml.push_str(&format!("<lastBuildDate>{}</lastBuildDate>\n", rss_date(self.updated())));
+        for entry in &self.entries {
+            xml.push_str("<item>\n");
+            xml.push_str(&format!("<title>{}</title>\n", escape(&entry.title)));
+            xml.push_str(&format!("<link>{}</link>\n", escape(&entry.link)));
+            xml.push_str(&format!("<guid isPermaLink=\"false\">urn:uuid:{}</guid>\n", entry.uuid));
+            xml.push_str(&format!("<pubDate>{}</pubDate>\n", rss_date(entry.published)));
+            xml.push_str(&format!("<dc:creator>{}</dc:creator>\n", escape(&entry.author)));
+            xml.push_str(&format!("<description>{}</description>\n", escape(&entry.content)));
+            xml.push_str("</item>\n");
+        }
+        xml.push_str("</channel>\n</rss>\n");
+        xml
+    }
+}
+
+/// Escapes text for use in XML content and attributes.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats the date as RFC 3339, which Atom requires.
+fn atom_date(date: NaiveDateTime) -> String {
+    date.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// Formats the date as RFC 822, which RSS requires.
+fn rss_date(date: NaiveDateTime) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Hashes the rendered feed, so clients can avoid downloading it again if it hasn't changed.
+/// The hash stays the same across restarts and builds of the server.
+fn etag(body: &str) -> String {
+    let hash: String = Sha1::digest(body.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("\"{}\"", hash)
+}
+
+/// Responds with the feed, or with Not Modified if the client already has this version of it.
+fn feed_response(feed: &Feed, format: FeedFormat, headers: &HeaderMap) -> Result<Response<String>, Rejection> {
+    let body: String = feed.render(format);
+    let etag: String = etag(&body);
+    let not_modified: bool = headers
+        .get(IF_NONE_MATCH)
+        .and_then(|x| x.to_str().ok())
+        .map(|tags| tags.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"))
+        .unwrap_or(false);
+
+    let response = if not_modified {
+        Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(ETAG, etag.as_str())
+            .body(String::new())
+    } else {
+        Response::builder()
+            .header(CONTENT_TYPE, format.content_type())
+            .header(ETAG, etag.as_str())
+            .body(body)
+    };
+    response.map_err(|_| Error::InternalServerError.simple_reject())
+}
+
+pub fn feed_api(s: &State) -> BoxedFilter<(impl Reply,)> {
+    info!("Attaching Feeds");
+    warp::path("feed")
+        .and(
+            article_feed(s)
+                .or(forum_feed(s))
+        )
+        .with(warp::log("feed"))
+        .boxed()
+}
+
+fn article_feed(s: &State) -> BoxedFilter<(impl Reply,)> {
+    warp::get2()
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(warp::header::headers_cloned())
+        .and(s.site_url.clone())
+        .and(s.db.clone())
+        .and_then(|segment: String, headers: HeaderMap, base_url: String, conn: PooledConn| {
+            let format: FeedFormat = match FeedFormat::split(&segment) {
+                Some(("articles", format)) => format,
+                _ => return Err(warp::reject::not_found())
+            };
+            let articles: Vec<ArticleData> = Article::get_recently_published(i64::from(FEED_SIZE), &conn)
+                .map_err(Error::simple_reject)?;
+            let feed = Feed {
+                title: "Weekend At Joes".to_string(),
+                description: "News from Weekend At Joes".to_string(),
+                url: format!("{}/feed/{}", base_url, segment),
+                link: base_url.clone(),
+                entries: article_entries(articles, &base_url, &conn).map_err(Error::simple_reject)?,
+            };
+            feed_response(&feed, format, &headers)
+        })
+        .boxed()
+}
+
+fn article_entries(articles: Vec<ArticleData>, base_url: &str, conn: &PooledConn) -> Result<Vec<Entry>, Error> {
+    // Articles are updated when a new revision is published.
+    let revision_uuids: Vec<Uuid> = articles
+        .iter()
+        .filter_map(|data| data.article.published_revision_uuid)
+        .collect();
+    let revision_dates: HashMap<Uuid, NaiveDateTime> = ArticleRevision::get_revisions(revision_uuids, conn)?
+        .into_iter()
+        .map(|revision| (revision.uuid, revision.created_date))
+        .collect();
+
+    let entries = articles
+        .into_iter()
+        .map(|data| {
+            let published: NaiveDateTime = data.article.publish_date.unwrap_or_else(|| NaiveDateTime::from_timestamp(0, 0));
+            let revised: Option<NaiveDateTime> = data.article.published_revision_uuid
+                .and_then(|uuid| revision_dates.get(&uuid).cloned());
+            Entry {
+                uuid: data.article.uuid,
+                link: format!("{}/article/{}", base_url, data.article.slug),
+                content: render_html(&data.article.body),
+                title: data.article.title,
+                author: data.user.display_name,
+                published,
+                updated: revised.map(|revised| revised.max(published)).unwrap_or(published),
+            }
+        })
+        .collect();
+    Ok(entries)
+}
+
+fn forum_feed(s: &State) -> BoxedFilter<(impl Reply,)> {
+    warp::get2()
+        .and(warp::path("forum"))
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(warp::header::headers_cloned())
+        .and(s.site_url.clone())
+        .and(s.db.clone())
+        .and_then(|segment: String, headers: HeaderMap, base_url: String, conn: PooledConn| {
+            let (forum_uuid, format): (ForumUuid, FeedFormat) = match FeedFormat::split(&segment) {
+                Some((forum_uuid, format)) => match ForumUuid::parse_str(forum_uuid) {
+                    Ok(forum_uuid) => (forum_uuid, format),
+                    Err(_) => return Err(warp::reject::not_found())
+                },
+                None => return Err(warp::reject::not_found())
+            };
+            let forum: Forum = Forum::get_forum(forum_uuid, &conn)
+                .map_err(Error::simple_reject)?;
+            let threads: Vec<MinimalThreadData> = Thread::get_paginated_ordered(forum_uuid, ThreadOrder::LastActivity, None, 1, FEED_SIZE, &conn)
+                .map_err(Error::simple_reject)?;
+            let feed = Feed {
+                title: forum.title,
+                description: forum.description,
+                url: format!("{}/feed/forum/{}", base_url, segment),
+                link: format!("{}/forum/{}", base_url, forum_uuid),
+                entries: thread_entries(threads, &base_url, &conn).map_err(Error::simple_reject)?,
+            };
+            feed_response(&feed, format, &headers)
+        })
+        .boxed()
+}
+
+fn thread_entries(threads: Vec<MinimalThreadData>, base_url: &str, conn: &PooledConn) -> Result<Vec<Entry>, Error> {
+    let thread_uuids: Vec<Uuid> = threads
+        .iter()
+        .map(|data| data.thread.uuid)
+        .collect();
+    // Censored posts aren't shown in the feed.
+    let mut contents: HashMap<Uuid, String> = Post::get_root_posts(thread_uuids, conn)?
+        .into_iter()
+        .filter(|post| !post.censored)
+        .map(|post| (post.thread_uuid, post.content))
+        .collect();
+
+    let entries = threads
+        .into_iter()
+        .map(|data| Entry {
+            uuid: data.thread.uuid,
+            link: format!("{}/forum/{}/{}", base_url, data.thread.forum_uuid, data.thread.uuid),
+            content: contents
+                .remove(&data.thread.uuid)
+                .map(|content| render_html(&content))
+                .unwrap_or_default(),
+            title: data.thread.title,
+            author: data.user.display_name,
+            published: data.thread.created_date,
+            updated: data.thread.last_post_date,
+        })
+        .collect();
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::DEFAULT_SITE_URL;
+    use testing_fixtures::fixtures::article::ArticleFixture;
+    use testing_fixtures::fixtures::forum::ForumFixture;
+    use testing_common::setup::setup_warp;
+    use pool::Pool;
+    use crate::util::test::deserialize_string;
+
+    #[test]
+    fn article_feeds() {
+        setup_warp(|fixture: &ArticleFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/feed/articles.atom")
+                .reply(&feed_api(&s));
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.headers()[CONTENT_TYPE], "application/atom+xml; charset=utf-8");
+            let etag = response.headers()[ETAG].clone();
+            let atom: String = deserialize_string(response);
+            assert!(atom.contains(&format!("<id>urn:uuid:{}</id>", fixture.published_article.uuid)));
+            assert!(atom.contains(&format!("<link href=\"{}/article/{}\"/>", DEFAULT_SITE_URL, fixture.published_article.slug)));
+            assert!(!atom.contains(&fixture.unpublished_article.uuid.to_string()));
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/feed/articles.atom")
+                .header("If-None-Match", etag.to_str().unwrap())
+                .reply(&feed_api(&s));
+            assert_eq!(response.status(), 304);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/feed/articles.rss")
+                .reply(&feed_api(&s));
+            assert_eq!(response.status(), 200);
+            let rss: String = deserialize_string(response);
+            assert!(rss.contains("<rss version=\"2.0\""));
+            assert!(rss.contains(&format!("<title>{}</title>", fixture.published_article.title)));
+        })
+    }
+
+    #[test]
+    fn forum_feed_renders_root_posts() {
+        setup_warp(|fixture: &ForumFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/feed/forum/{}.atom", fixture.forum.uuid))
+                .reply(&feed_api(&s));
+            assert_eq!(response.status(), 200);
+            let atom: String = deserialize_string(response);
+            assert!(atom.contains(&format!("<id>urn:uuid:{}</id>", fixture.populated_thread.uuid)));
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/feed/forum/not-a-uuid.atom")
+                .reply(&feed_api(&s));
+            assert_eq!(response.status(), 404);
+        })
+    }
+}
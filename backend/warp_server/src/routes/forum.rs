@@ -12,6 +12,14 @@ use crate::util::json_body_filter;
 use crate::state::jwt::admin_user_filter;
 use identifiers::user::UserUuid;
 use wire::forum::NewForumRequest;
+use wire::forum::ReorderForumsRequest;
+use wire::forum::EditForumRequest;
+use wire::forum::DeleteForumQuery;
+use crate::util::optional_query_filter;
+use wire::forum::ForumSearchQuery;
+use wire::forum::ForumSearchResultResponse;
+use db::search::ForumSearch;
+use db::search::SearchHitData;
 
 use crate::logging::log_attach;
 use crate::logging::HttpMethod;
@@ -22,9 +30,13 @@ use pool::PooledConn;
 
 pub fn forum_api(s: &State) -> BoxedFilter<(impl Reply,)> {
     info!("Attaching Forum API");
-    let api = get_forums(s)
+    let api = search_forums(s)
+        .or(get_forums(s))
         .or(get_forum(s))
         .or(create_forum(s))
+        .or(reorder_forums(s))
+        .or(edit_forum(s))
+        .or(delete_forum(s))
         ;
 
     warp::path("forum")
@@ -33,6 +45,25 @@ pub fn forum_api(s: &State) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+/// Searches the titles of threads and the content of posts across the forums.
+fn search_forums(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "forum/search?q=&forum=&author=&before=&after=&page=");
+
+    warp::get2()
+        .and(warp::path("search"))
+        .and(warp::query::query::<ForumSearchQuery>())
+        .and(s.db.clone())
+        .and_then(|query: ForumSearchQuery, conn: PooledConn| {
+            let results_per_page: i32 = 25;
+            let page_index: i32 = query.page.unwrap_or(1);
+            ForumSearch::from(query).search(page_index, results_per_page, &conn)
+                .map(convert_vector_and_json::<SearchHitData, ForumSearchResultResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
 /// Gets all the forums
 fn get_forums(s: &State) -> BoxedFilter<(impl Reply,)> {
 
@@ -77,4 +108,109 @@ fn create_forum(s: &State) -> BoxedFilter<(impl Reply,)> {
                 .map_err(Error::simple_reject)
         })
         .boxed()
-}
\ No newline at end of file
+}
+/// Sets the order in which forums are listed.
+fn reorder_forums(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "forum/reorder");
+
+    warp::put2()
+        .and(warp::path("reorder"))
+        .and(json_body_filter(4))
+        .and(admin_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|request: ReorderForumsRequest, _admin: UserUuid, conn: PooledConn|{
+            Forum::reorder_forums(request.forum_uuids, &conn)
+                .map(convert_vector_and_json::<Forum, ForumResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Changes the title and description of a forum.
+fn edit_forum(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "forum/<uuid>");
+
+    warp::put2()
+        .and(uuid_wrap_filter::<ForumUuid>())
+        .and(json_body_filter(4))
+        .and(admin_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|forum_uuid: ForumUuid, request: EditForumRequest, _admin: UserUuid, conn: PooledConn|{
+            Forum::update_forum(forum_uuid, request.into(), &conn)
+                .map(convert_and_json::<Forum, ForumResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Deletes a forum.
+/// Forums that still have threads are only deleted if `?force=true` is provided.
+fn delete_forum(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Delete, "forum/<uuid>?force=<bool>");
+
+    warp::delete2()
+        .and(uuid_wrap_filter::<ForumUuid>())
+        .and(optional_query_filter::<DeleteForumQuery>())
+        .and(admin_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|forum_uuid: ForumUuid, query: DeleteForumQuery, _admin: UserUuid, conn: PooledConn|{
+            Forum::delete_forum_checked(forum_uuid, query.force, &conn)
+                .map(convert_and_json::<Forum, ForumResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing_fixtures::fixtures::forum::ForumFixture;
+    use testing_common::setup::setup_warp;
+    use pool::Pool;
+    use crate::util::test::deserialize;
+    use crate::state::jwt::AUTHORIZATION_HEADER_KEY;
+    use wire::user::BEARER;
+
+    #[test]
+    fn search() {
+        setup_warp(|fixture: &ForumFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let response = warp::test::request()
+                .method("GET")
+                .path(&format!("/forum/search?q=post%20content&forum={}&after=2000-01-01T00:00:00", fixture.forum.uuid))
+                .reply(&forum_api(&s));
+
+            assert_eq!(response.status(), 200);
+            let results: Vec<ForumSearchResultResponse> = deserialize(response);
+            assert_eq!(results.len(), 3);
+            assert_eq!(results[0].forum_uuid, ForumUuid(fixture.forum.uuid));
+        })
+    }
+
+    #[test]
+    fn delete_non_empty_forum_requires_force() {
+        setup_warp(|fixture: &ForumFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let jwt: String = crate::routes::auth::tests::get_admin_jwt_string(&s, &fixture.user_fixture);
+
+            let response = warp::test::request()
+                .method("DELETE")
+                .path(&format!("/forum/{}", fixture.forum.uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&forum_api(&s));
+            assert_eq!(response.status(), 400);
+
+            let response = warp::test::request()
+                .method("DELETE")
+                .path(&format!("/forum/{}?force=true", fixture.forum.uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&forum_api(&s));
+            assert_eq!(response.status(), 200);
+            let forum: ForumResponse = deserialize(response);
+            assert_eq!(forum.uuid, ForumUuid(fixture.forum.uuid));
+        })
+    }
+}
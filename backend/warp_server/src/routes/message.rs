@@ -11,6 +11,7 @@ use crate::util::convert_vector_and_json;
 use crate::util::json_body_filter;
 use identifiers::user::UserUuid;
 use crate::state::jwt::normal_user_filter;
+use crate::state::rate_limit::{rate_limited_user_filter, Action};
 use db::Message;
 use db::Chat;
 use identifiers::chat::ChatUuid;
@@ -66,7 +67,7 @@ fn send_message(s: &State) -> BoxedFilter<(impl Reply,)> {
     warp::post2()
         .and(warp::path::path("send"))
         .and(json_body_filter(20))
-        .and(normal_user_filter(s))
+        .and(rate_limited_user_filter(s, Action::SendMessage))
         .and(s.db.clone())
         .and_then(|request: NewMessageRequest, user_uuid: UserUuid, conn: PooledConn|{
             if !Chat::is_user_in_chat(&request.chat_uuid, user_uuid, &conn).map_err(Error::simple_reject)? {
@@ -8,10 +8,15 @@ mod bucket;
 mod chat;
 mod forum;
 mod message;
+mod notification;
+mod poll;
 mod post;
 mod question;
+mod subscription;
 mod thread;
 mod static_file;
+mod feed;
+mod upload;
 
 use self::user::user_api;
 use self::auth::auth_api;
@@ -21,9 +26,14 @@ use self::bucket::bucket_api;
 use self::chat::chat_api;
 use self::forum::forum_api;
 use self::message::message_api;
+use self::notification::notification_api;
+use self::poll::poll_api;
 use self::post::post_api;
 use self::question::question_api;
+use self::subscription::subscription_api;
 use self::thread::thread_api;
+use self::feed::feed_api;
+use self::upload::{upload_api, upload_files};
 
 pub use self::static_file::static_files_handler;
 
@@ -37,11 +47,13 @@ use crate::state::State;
 pub const API_STRING: &str = "api";
 
 
-/// Combine the API with the static file handler.
+/// Combine the API, the feeds and the uploaded files with the static file handler.
 /// Any missed GETs that doesn't start with '/api' will redirect to the index.html.
 /// Also support CORS, as that should be applied to the whole server.
 pub fn routes(s: &State) -> BoxedFilter<(impl warp::Reply,)> {
     api(&s)
+        .or(feed_api(&s))
+        .or(upload_files(&s))
         .or(static_files_handler())
         .recover(customize_error) // Top level error correction
         .or(cors()) // For some reason, this needs to come after the recover() section.
@@ -58,9 +70,13 @@ fn api(s: &State) -> BoxedFilter<(impl warp::Reply,)> {
         .or(chat_api(s))
         .or(forum_api(s))
         .or(message_api(s))
+        .or(notification_api(s))
+        .or(poll_api(s))
         .or(post_api(s))
         .or(question_api(s))
+        .or(subscription_api(s))
         .or(thread_api(s))
+        .or(upload_api(s))
     ;
 
     warn!("Attaching Main API");
@@ -0,0 +1,135 @@
+use warp::Filter;
+use warp::filters::BoxedFilter;
+use warp::reply::Reply;
+use error::Error;
+use crate::util::convert_and_json;
+use crate::util::convert_vector_and_json;
+use identifiers::user::UserUuid;
+use identifiers::notification::NotificationUuid;
+use crate::state::jwt::normal_user_filter;
+use db::notification::Notification;
+use db::notification::NotificationData;
+use wire::notification::NotificationResponse;
+use crate::uuid_integration::uuid_wrap_filter;
+use crate::logging::log_attach;
+use crate::logging::HttpMethod;
+use crate::state::State;
+use pool::PooledConn;
+
+/// The number of recent notifications returned to a user.
+const NOTIFICATION_LIMIT: i64 = 50;
+
+pub fn notification_api(s: &State) -> BoxedFilter<(impl Reply,)> {
+    info!("Attaching Notification API");
+    let api = get_notifications(s)
+        .or(mark_notification_read(s))
+        .or(mark_all_notifications_read(s))
+        ;
+
+    warp::path("notification")
+        .and(api)
+        .with(warp::log("notification"))
+        .boxed()
+}
+
+fn get_notifications(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "notification");
+
+    warp::get2()
+        .and(warp::path::end())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|user_uuid: UserUuid, conn: PooledConn| {
+            Notification::get_notifications_for_user(user_uuid, NOTIFICATION_LIMIT, &conn)
+                .map(convert_vector_and_json::<NotificationData, NotificationResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+fn mark_notification_read(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "notification/read/<uuid>");
+
+    warp::put2()
+        .and(warp::path("read"))
+        .and(uuid_wrap_filter::<NotificationUuid>())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|notification_uuid: NotificationUuid, user_uuid: UserUuid, conn: PooledConn| {
+            Notification::mark_read(notification_uuid, user_uuid, &conn)
+                .map(convert_and_json::<NotificationData, NotificationResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Marks every notification as read, responding with the user's notifications.
+fn mark_all_notifications_read(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "notification/read");
+
+    warp::put2()
+        .and(warp::path("read"))
+        .and(warp::path::end())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|user_uuid: UserUuid, conn: PooledConn| {
+            Notification::mark_all_read(user_uuid, &conn)
+                .and_then(|_| Notification::get_notifications_for_user(user_uuid, NOTIFICATION_LIMIT, &conn))
+                .map(convert_vector_and_json::<NotificationData, NotificationResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing_fixtures::fixtures::forum::ForumFixture;
+    use testing_common::setup::setup_warp;
+    use pool::Pool;
+    use crate::util::test::deserialize;
+    use crate::state::jwt::AUTHORIZATION_HEADER_KEY;
+    use wire::user::BEARER;
+    use db::post::{Post, NewPost};
+
+    #[test]
+    fn reply_notification_is_listed_and_marked_read() {
+        setup_warp(|fixture: &ForumFixture, pool: Pool| {
+            let s = State::testing_init(pool.clone(), fixture.user_fixture.secret.clone());
+            let reply = NewPost {
+                thread_uuid: fixture.populated_thread.uuid,
+                author_uuid: fixture.user_fixture.admin_user.uuid,
+                parent_uuid: Some(fixture.post_1.uuid),
+                created_date: fixture.post_1.created_date,
+                content: "A **bold** reply".to_string(),
+                censored: false,
+            };
+            let conn = pool.get().expect("should get connection");
+            Post::create_and_get_user(reply, &conn).expect("should create post");
+
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let response = warp::test::request()
+                .method("GET")
+                .path("/notification")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&notification_api(&s));
+            assert_eq!(response.status(), 200);
+            let notifications: Vec<NotificationResponse> = deserialize(response);
+            assert_eq!(notifications.len(), 1);
+            assert!(!notifications[0].read);
+            assert_eq!(notifications[0].excerpt, "A bold reply");
+
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&format!("/notification/read/{}", notifications[0].uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&notification_api(&s));
+            assert_eq!(response.status(), 200);
+            let notification: NotificationResponse = deserialize(response);
+            assert!(notification.read);
+        })
+    }
+}
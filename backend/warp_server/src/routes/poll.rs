@@ -0,0 +1,125 @@
+use warp::Filter;
+use warp::filters::BoxedFilter;
+use warp::reply::Reply;
+use error::Error;
+use crate::util::convert_and_json;
+use crate::util::json_body_filter;
+use identifiers::user::UserUuid;
+use identifiers::poll::PollUuid;
+use crate::state::jwt::normal_user_filter;
+use db::poll::Poll;
+use db::poll::PollData;
+use wire::poll::PollResponse;
+use wire::poll::PollVoteRequest;
+use crate::uuid_integration::uuid_wrap_filter;
+use crate::logging::log_attach;
+use crate::logging::HttpMethod;
+use crate::state::State;
+use pool::PooledConn;
+
+pub fn poll_api(s: &State) -> BoxedFilter<(impl Reply,)> {
+    info!("Attaching Poll API");
+    let api = vote(s)
+        .or(retract_vote(s))
+        ;
+
+    warp::path("poll")
+        .and(api)
+        .with(warp::log("poll"))
+        .boxed()
+}
+
+/// Votes in the poll, replacing the user's previous votes.
+fn vote(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "poll/vote/<uuid>");
+
+    warp::put2()
+        .and(warp::path("vote"))
+        .and(uuid_wrap_filter::<PollUuid>())
+        .and(json_body_filter(4))
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|poll_uuid: PollUuid, request: PollVoteRequest, user_uuid: UserUuid, conn: PooledConn| {
+            Poll::vote(poll_uuid, user_uuid, request.option_uuids, &conn)
+                .map(convert_and_json::<PollData, PollResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+fn retract_vote(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Delete, "poll/vote/<uuid>");
+
+    warp::delete2()
+        .and(warp::path("vote"))
+        .and(uuid_wrap_filter::<PollUuid>())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|poll_uuid: PollUuid, user_uuid: UserUuid, conn: PooledConn| {
+            Poll::retract_vote(poll_uuid, user_uuid, &conn)
+                .map(convert_and_json::<PollData, PollResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing_fixtures::fixtures::forum::ForumFixture;
+    use testing_common::setup::setup_warp;
+    use pool::Pool;
+    use crate::util::test::deserialize;
+    use crate::state::jwt::AUTHORIZATION_HEADER_KEY;
+    use wire::user::BEARER;
+    use db::poll::NewPollData;
+    use identifiers::thread::ThreadUuid;
+
+    #[test]
+    fn vote_and_retract() {
+        setup_warp(|fixture: &ForumFixture, pool: Pool| {
+            let s = State::testing_init(pool.clone(), fixture.user_fixture.secret.clone());
+            let conn = pool.get().expect("should get connection");
+            let new_poll = NewPollData {
+                question: "Which?".to_string(),
+                options: vec!["A".to_string(), "B".to_string()],
+                multiple_choice: false,
+                close_date: None,
+                hide_results_until_closed: false,
+            };
+            let poll: PollData = Poll::create_poll(ThreadUuid(fixture.populated_thread.uuid), new_poll, &conn)
+                .expect("should create poll");
+            let poll: PollResponse = poll.into();
+
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let path = format!("/poll/vote/{}", poll.uuid);
+            let request = PollVoteRequest {
+                option_uuids: vec![poll.options[1].uuid]
+            };
+
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&path)
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .header("Content-Length", "1000") // Requires sized length
+                .json(&request)
+                .reply(&poll_api(&s));
+            assert_eq!(response.status(), 200);
+            let voted: PollResponse = deserialize(response);
+            assert!(voted.options[1].voted);
+            assert_eq!(voted.options[1].votes, Some(1));
+
+            let response = warp::test::request()
+                .method("DELETE")
+                .path(&path)
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&poll_api(&s));
+            assert_eq!(response.status(), 200);
+            let retracted: PollResponse = deserialize(response);
+            assert!(!retracted.results_visible);
+            assert!(retracted.options.iter().all(|option| !option.voted));
+        })
+    }
+}
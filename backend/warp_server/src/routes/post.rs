@@ -5,10 +5,12 @@ use error::Error;
 //use crate::db_integration::s.db.clone();
 //use db::Conn;
 use crate::util::convert_and_json;
+use crate::util::convert_vector_for_viewer_and_json;
 use crate::util::convert_vector_and_json;
 use crate::util::json_body_filter;
 use identifiers::user::UserUuid;
 use crate::state::jwt::normal_user_filter;
+use crate::state::rate_limit::{rate_limited_user_filter, Action};
 use wire::post::NewPostRequest;
 use db::Post;
 use wire::post::PostResponse;
@@ -18,20 +20,39 @@ use wire::post::EditPostRequest;
 use identifiers::thread::ThreadUuid;
 use db::post::EditPostChangeset;
 use crate::state::jwt::moderator_user_filter;
+use crate::state::jwt::optional_viewer_filter;
+use crate::state::jwt::Viewer;
+use wire::post::CensorPostRequest;
+use wire::post::PostCensorActionResponse;
+use db::post::PostCensorAction;
+use db::ModeratorView;
 use identifiers::post::PostUuid;
 use crate::logging::log_attach;
 use crate::logging::HttpMethod;
 use crate::uuid_integration::uuid_wrap_filter;
 use crate::state::State;
 use pool::PooledConn;
+use db::reaction::ReactionSet;
+use db::reaction::ReactionCounts;
+use wire::post::ReactionResponse;
+use wire::post::VoteRequest;
+use wire::post::VoteCountsResponse;
+use db::post::Vote;
+use db::post::VoteDirection;
 
 
 pub fn post_api(s: &State) -> BoxedFilter<(impl Reply,)> {
     info!("Attaching Post API");
+    // Voting comes before editing, as editing takes the body of any PUT to the base path.
     let api = create_post(s)
+        .or(vote(s))
         .or(edit_post(s))
         .or(censor_post(s))
+        .or(uncensor_post(s))
+        .or(get_censor_history(s))
         .or(get_posts_by_user(s))
+        .or(react_to_post(s))
+        .or(remove_reaction(s))
         ;
 
     warp::path("post")
@@ -47,7 +68,7 @@ pub fn create_post(s: &State) -> BoxedFilter<(impl Reply,)> {
 
     warp::post2()
         .and(json_body_filter(12))
-        .and(normal_user_filter(s))
+        .and(rate_limited_user_filter(s, Action::CreatePost))
         .and(s.db.clone())
         .and_then(|request: NewPostRequest, user_uuid: UserUuid, conn: PooledConn| {
             // check if token user id matches the request user id.
@@ -91,17 +112,55 @@ pub fn edit_post(s: &State) -> BoxedFilter<(impl Reply,)> {
 
 pub fn censor_post(s: &State) -> BoxedFilter<(impl Reply,)> {
 
-    log_attach(HttpMethod::Put, "post/censor");
+    log_attach(HttpMethod::Put, "post/censor/<uuid>");
 
     warp::put2()
         .and(warp::path("censor"))
         .and(uuid_wrap_filter())
-//        .and(warp::path::param::<PosUuid>())
+        .and(json_body_filter(4))
         .and(moderator_user_filter(s))
         .and(s.db.clone())
-        .and_then(|post_uuid: PostUuid, _user: UserUuid, conn: PooledConn| {
-            Post::censor_post(post_uuid, &conn)
-                .map(convert_and_json::<ChildlessPostData, PostResponse>)
+        .and_then(|post_uuid: PostUuid, request: CensorPostRequest, moderator: UserUuid, conn: PooledConn| {
+            Post::censor_post(post_uuid, request.reason, moderator, &conn)
+                .map(ModeratorView)
+                .map(convert_and_json::<ModeratorView<ChildlessPostData>, PostResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+pub fn uncensor_post(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "post/uncensor/<uuid>");
+
+    warp::put2()
+        .and(warp::path("uncensor"))
+        .and(uuid_wrap_filter())
+        .and(json_body_filter(4))
+        .and(moderator_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|post_uuid: PostUuid, request: CensorPostRequest, moderator: UserUuid, conn: PooledConn| {
+            Post::uncensor_post(post_uuid, request.reason, moderator, &conn)
+                .map(ModeratorView)
+                .map(convert_and_json::<ModeratorView<ChildlessPostData>, PostResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Lists every time the post was censored or uncensored, for moderators to review.
+pub fn get_censor_history(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "post/censor_history/<uuid>");
+
+    warp::get2()
+        .and(warp::path("censor_history"))
+        .and(uuid_wrap_filter())
+        .and(moderator_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|post_uuid: PostUuid, _moderator: UserUuid, conn: PooledConn| {
+            Post::get_censor_history(post_uuid, &conn)
+                .map(convert_vector_and_json::<PostCensorAction, PostCensorActionResponse>)
                 .map_err(Error::simple_reject)
         })
         .boxed()
@@ -114,11 +173,199 @@ pub fn get_posts_by_user(s: &State) -> BoxedFilter<(impl Reply,)> {
     warp::get2()
         .and(warp::path("users_posts"))
         .and(uuid_wrap_filter())
+        .and(optional_viewer_filter(s))
         .and(s.db.clone())
-        .and_then(|user_uuid: UserUuid, conn: PooledConn| {
+        .and_then(|user_uuid: UserUuid, viewer: Viewer, conn: PooledConn| {
             Post::get_posts_by_user(user_uuid, &conn)
-                .map(convert_vector_and_json::<ChildlessPostData, PostResponse>)
+                .map(|posts| convert_vector_for_viewer_and_json::<ChildlessPostData, PostResponse>(posts, viewer.is_moderator))
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Sets or removes the user's vote on the post, responding with the post's updated vote counts.
+pub fn vote(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "post/vote/<uuid>");
+
+    warp::put2()
+        .and(warp::path("vote"))
+        .and(uuid_wrap_filter())
+        .and(json_body_filter(1))
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|post_uuid: PostUuid, request: VoteRequest, user_uuid: UserUuid, conn: PooledConn| {
+            let vote = Vote { post_uuid, user_uuid };
+            Post::change_vote(vote, request.direction.map(VoteDirection::from), &conn)
+                .map(convert_and_json::<ReactionCounts, VoteCountsResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Leaves a reaction on the post, responding with the post's updated reactions.
+pub fn react_to_post(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "post/react/<uuid>/<reaction>");
+
+    warp::put2()
+        .and(warp::path("react"))
+        .and(uuid_wrap_filter())
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(normal_user_filter(s))
+        .and(s.reactions.clone())
+        .and(s.db.clone())
+        .and_then(|post_uuid: PostUuid, reaction: String, user_uuid: UserUuid, reactions: ReactionSet, conn: PooledConn| {
+            Post::react(post_uuid, user_uuid, reaction, &reactions, &conn)
+                .map(convert_and_json::<ReactionCounts, Vec<ReactionResponse>>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+pub fn remove_reaction(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Delete, "post/react/<uuid>/<reaction>");
+
+    warp::delete2()
+        .and(warp::path("react"))
+        .and(uuid_wrap_filter())
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|post_uuid: PostUuid, reaction: String, user_uuid: UserUuid, conn: PooledConn| {
+            Post::unreact(post_uuid, user_uuid, reaction, &conn)
+                .map(convert_and_json::<ReactionCounts, Vec<ReactionResponse>>)
                 .map_err(Error::simple_reject)
         })
         .boxed()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing_fixtures::fixtures::forum::ForumFixture;
+    use testing_common::setup::setup_warp;
+    use pool::Pool;
+    use crate::util::test::deserialize;
+    use crate::state::jwt::AUTHORIZATION_HEADER_KEY;
+    use wire::user::BEARER;
+    use wire::post::VoteDirection as WireVoteDirection;
+
+    #[test]
+    fn react_and_remove_reaction() {
+        setup_warp(|fixture: &ForumFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let path = format!("/post/react/{}/heart", fixture.post_1.uuid);
+
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&path)
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&post_api(&s));
+            assert_eq!(response.status(), 200);
+            let reactions: Vec<ReactionResponse> = deserialize(response);
+            assert_eq!(reactions, vec![ReactionResponse { reaction: "heart".to_string(), count: 1, user_reacted: true }]);
+
+            // Only the reaction route is used, as the rejection would otherwise fall through to the other post routes.
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&format!("/react/{}/unknown", fixture.post_1.uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&react_to_post(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 400);
+
+            let response = warp::test::request()
+                .method("DELETE")
+                .path(&path)
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&post_api(&s));
+            assert_eq!(response.status(), 200);
+            let reactions: Vec<ReactionResponse> = deserialize(response);
+            assert!(reactions.is_empty());
+        })
+    }
+
+    #[test]
+    fn change_vote() {
+        setup_warp(|fixture: &ForumFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let path = format!("/post/vote/{}", fixture.post_1.uuid);
+            let vote = |direction: Option<WireVoteDirection>| -> VoteCountsResponse {
+                let response = warp::test::request()
+                    .method("PUT")
+                    .path(&path)
+                    .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                    .header("Content-Length", "1000") // Requires sized length
+                    .json(&VoteRequest { direction })
+                    .reply(&post_api(&s));
+                assert_eq!(response.status(), 200);
+                deserialize(response)
+            };
+
+            let counts = vote(Some(WireVoteDirection::Up));
+            assert_eq!(counts, VoteCountsResponse { up: 1, down: 0, score: 1, user_vote: Some(WireVoteDirection::Up) });
+            // Voting the same way again is fine.
+            let counts = vote(Some(WireVoteDirection::Up));
+            assert_eq!(counts.up, 1);
+            let counts = vote(Some(WireVoteDirection::Down));
+            assert_eq!(counts, VoteCountsResponse { up: 0, down: 1, score: -1, user_vote: Some(WireVoteDirection::Down) });
+            let counts = vote(None);
+            assert_eq!(counts, VoteCountsResponse { up: 0, down: 0, score: 0, user_vote: None });
+        })
+    }
+
+    #[test]
+    fn posting_is_rate_limited() {
+        use crate::state::rate_limit::{rate_limiter_filter, RateLimit, RateLimitConfig, RateLimiter};
+        use std::time::Duration;
+
+        setup_warp(|fixture: &ForumFixture, pool: Pool| {
+            let mut s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let mut config = RateLimitConfig::default();
+            config.create_post = RateLimit::new(1, Duration::from_secs(60));
+            s.rate_limiter = rate_limiter_filter(RateLimiter::new(config));
+
+            let post = |user_name: String, author_uuid: UserUuid| {
+                let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, user_name);
+                let request = NewPostRequest {
+                    author_uuid,
+                    thread_uuid: ThreadUuid(fixture.populated_thread.uuid),
+                    parent_uuid: Some(PostUuid(fixture.post_1.uuid)),
+                    content: "Reply".to_string(),
+                };
+                // Only the create route is used, as the rejection would otherwise fall through to the other post routes.
+                warp::test::request()
+                    .method("POST")
+                    .path("/")
+                    .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                    .header("Content-Length", "1000") // Requires sized length
+                    .json(&request)
+                    .reply(&create_post(&s).recover(error::warp_support::customize_error))
+            };
+
+            let normal_user = &fixture.user_fixture.normal_user;
+            let response = post(normal_user.user_name.clone(), UserUuid(normal_user.uuid));
+            assert_eq!(response.status(), 200);
+            let response = post(normal_user.user_name.clone(), UserUuid(normal_user.uuid));
+            assert_eq!(response.status(), 429);
+            let retry_after: u64 = response.headers()["Retry-After"]
+                .to_str()
+                .expect("header should be text")
+                .parse()
+                .expect("header should be a number of seconds");
+            assert!(retry_after > 0 && retry_after <= 60);
+
+            // Admins are exempt.
+            let admin_user = &fixture.user_fixture.admin_user;
+            for _ in 0..2 {
+                let response = post(admin_user.user_name.clone(), UserUuid(admin_user.uuid));
+                assert_eq!(response.status(), 200);
+            }
+        })
+    }
+}
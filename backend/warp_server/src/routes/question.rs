@@ -17,6 +17,7 @@ use db::question::QuestionData;
 use crate::uuid_integration::uuid_filter;
 use identifiers::question::QuestionUuid;
 use crate::state::jwt::normal_user_filter;
+use crate::state::rate_limit::{rate_limited_user_filter, Action};
 use wire::question::NewQuestionRequest;
 use db::Bucket;
 use db::question::NewQuestion;
@@ -104,7 +105,7 @@ fn create_question(s: &State) -> BoxedFilter<(impl Reply,)> {
 
     warp::post2()
         .and(json_body_filter(12))
-        .and(normal_user_filter(s))
+        .and(rate_limited_user_filter(s, Action::CreateQuestion))
         .and(s.db.clone())
         .and_then(|request: NewQuestionRequest, user_uuid: UserUuid, conn: PooledConn| {
             let bucket_uuid: BucketUuid = request.bucket_uuid;
@@ -0,0 +1,168 @@
+use warp::Filter;
+use warp::filters::BoxedFilter;
+use warp::reply::Reply;
+use error::Error;
+use crate::util::convert_and_json;
+use crate::util::convert_vector_and_json;
+use identifiers::user::UserUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::forum::ForumUuid;
+use crate::state::jwt::normal_user_filter;
+use db::subscription::Subscription;
+use wire::subscription::SubscriptionResponse;
+use crate::uuid_integration::uuid_wrap_filter;
+use crate::logging::log_attach;
+use crate::logging::HttpMethod;
+use crate::state::State;
+use pool::PooledConn;
+
+pub fn subscription_api(s: &State) -> BoxedFilter<(impl Reply,)> {
+    info!("Attaching Subscription API");
+    let api = get_subscriptions(s)
+        .or(subscribe_to_thread(s))
+        .or(unsubscribe_from_thread(s))
+        .or(subscribe_to_forum(s))
+        .or(unsubscribe_from_forum(s))
+        ;
+
+    warp::path("subscription")
+        .and(api)
+        .with(warp::log("subscription"))
+        .boxed()
+}
+
+fn get_subscriptions(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "subscription");
+
+    warp::get2()
+        .and(warp::path::end())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|user_uuid: UserUuid, conn: PooledConn| {
+            Subscription::get_subscriptions_for_user(user_uuid, &conn)
+                .map(convert_vector_and_json::<Subscription, SubscriptionResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+fn subscribe_to_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "subscription/thread/<uuid>");
+
+    warp::put2()
+        .and(warp::path("thread"))
+        .and(uuid_wrap_filter::<ThreadUuid>())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|thread_uuid: ThreadUuid, user_uuid: UserUuid, conn: PooledConn| {
+            Subscription::subscribe_to_thread(user_uuid, thread_uuid, &conn)
+                .map(convert_and_json::<Subscription, SubscriptionResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+fn unsubscribe_from_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Delete, "subscription/thread/<uuid>");
+
+    warp::delete2()
+        .and(warp::path("thread"))
+        .and(uuid_wrap_filter::<ThreadUuid>())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|thread_uuid: ThreadUuid, user_uuid: UserUuid, conn: PooledConn| {
+            Subscription::unsubscribe_from_thread(user_uuid, thread_uuid, &conn)
+                .map(convert_and_json::<Subscription, SubscriptionResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+fn subscribe_to_forum(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "subscription/forum/<uuid>");
+
+    warp::put2()
+        .and(warp::path("forum"))
+        .and(uuid_wrap_filter::<ForumUuid>())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|forum_uuid: ForumUuid, user_uuid: UserUuid, conn: PooledConn| {
+            Subscription::subscribe_to_forum(user_uuid, forum_uuid, &conn)
+                .map(convert_and_json::<Subscription, SubscriptionResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+fn unsubscribe_from_forum(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Delete, "subscription/forum/<uuid>");
+
+    warp::delete2()
+        .and(warp::path("forum"))
+        .and(uuid_wrap_filter::<ForumUuid>())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|forum_uuid: ForumUuid, user_uuid: UserUuid, conn: PooledConn| {
+            Subscription::unsubscribe_from_forum(user_uuid, forum_uuid, &conn)
+                .map(convert_and_json::<Subscription, SubscriptionResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing_fixtures::fixtures::forum::ForumFixture;
+    use testing_common::setup::setup_warp;
+    use pool::Pool;
+    use crate::util::test::deserialize;
+    use crate::state::jwt::AUTHORIZATION_HEADER_KEY;
+    use wire::user::BEARER;
+
+    #[test]
+    fn subscribe_and_unsubscribe_from_thread() {
+        setup_warp(|fixture: &ForumFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.user_fixture.secret.clone());
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.user_fixture.normal_user.user_name.clone());
+            let path = format!("/subscription/thread/{}", fixture.populated_thread.uuid);
+
+            let response = warp::test::request()
+                .method("PUT")
+                .path(&path)
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&subscription_api(&s));
+            assert_eq!(response.status(), 200);
+            let subscription: SubscriptionResponse = deserialize(response);
+            assert_eq!(subscription.thread_uuid, Some(ThreadUuid(fixture.populated_thread.uuid)));
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/subscription")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&subscription_api(&s));
+            let subscriptions: Vec<SubscriptionResponse> = deserialize(response);
+            assert_eq!(subscriptions, vec![subscription]);
+
+            let response = warp::test::request()
+                .method("DELETE")
+                .path(&path)
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&subscription_api(&s));
+            assert_eq!(response.status(), 200);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/subscription")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&subscription_api(&s));
+            let subscriptions: Vec<SubscriptionResponse> = deserialize(response);
+            assert!(subscriptions.is_empty());
+        })
+    }
+}
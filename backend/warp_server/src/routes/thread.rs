@@ -7,18 +7,31 @@ use crate::util::convert_vector_and_json;
 //use crate::uuid_integration::uuid_filter;
 use crate::util::json_body_filter;
 use identifiers::user::UserUuid;
-use crate::state::jwt::normal_user_filter;
+use crate::state::rate_limit::{rate_limited_user_filter, Action};
+use crate::state::jwt::optional_normal_user_filter;
 use db::NewThread;
 use wire::thread::NewThreadRequest;
 use db::Thread;
 use wire::thread::ThreadResponse;
 use db::thread::ThreadData;
+use db::poll::NewPollData;
 use warp;
 use crate::state::jwt::moderator_user_filter;
 use identifiers::thread::ThreadUuid;
 use db::thread::MinimalThreadData;
 use wire::thread::MinimalThreadResponse;
-use crate::state::jwt::optional_normal_user_filter;
+use wire::thread::PinThreadRequest;
+use wire::thread::MoveThreadRequest;
+use wire::thread::EditThreadRequest;
+use wire::thread::MergeThreadRequest;
+use wire::thread::SplitThreadRequest;
+use identifiers::post::PostUuid;
+use wire::thread::ThreadListQuery;
+use db::thread::ThreadOrder;
+use crate::util::optional_query_filter;
+use crate::state::jwt::optional_viewer_filter;
+use crate::state::jwt::Viewer;
+use crate::util::convert_for_viewer_and_json;
 use identifiers::forum::ForumUuid;
 use crate::uuid_integration::uuid_wrap_filter;
 use crate::state::State;
@@ -31,6 +44,14 @@ pub fn thread_api(s: &State) -> BoxedFilter<(impl Reply,)> {
         .or(lock_thread(s))
         .or(unlock_thread(s))
         .or(archive_thread(s))
+        .or(unarchive_thread(s))
+        .or(get_archived_threads(s))
+        .or(pin_thread(s))
+        .or(unpin_thread(s))
+        .or(edit_thread(s))
+        .or(move_thread(s))
+        .or(merge_thread(s))
+        .or(split_thread(s))
         .or(get_threads_by_forum_id(s))
         .or(get_thread_contents(s))
         ;
@@ -44,7 +65,7 @@ pub fn thread_api(s: &State) -> BoxedFilter<(impl Reply,)> {
 pub fn create_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
     warp::post2()
         .and(json_body_filter(20))
-        .and(normal_user_filter(s))
+        .and(rate_limited_user_filter(s, Action::CreateThread))
         .and(s.db.clone())
         .and_then(| request: NewThreadRequest, user_uuid: UserUuid, conn: PooledConn|{
             if request.author_uuid != user_uuid {
@@ -53,9 +74,9 @@ pub fn create_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
 
             let new_thread: NewThread = request.clone().into();
             let post_content: String = request.post_content;
+            let poll: Option<NewPollData> = request.poll.map(NewPollData::from);
 
-
-            Thread::create_thread_with_initial_post(new_thread, post_content, &conn)
+            Thread::create_thread_with_initial_post(new_thread, post_content, poll, &conn)
                 .map(convert_and_json::<ThreadData,ThreadResponse>)
                 .map_err(Error::simple_reject)
         })
@@ -91,6 +112,103 @@ pub fn unlock_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
          .boxed()
 }
 
+pub fn pin_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
+     warp::put2()
+         .and(warp::path("pin"))
+         .and(uuid_wrap_filter::<ThreadUuid>())
+         .and(json_body_filter(1))
+         .and(moderator_user_filter(s))
+         .and(s.db.clone())
+         .and_then(|thread_uuid: ThreadUuid, request: PinThreadRequest, _moderator: UserUuid, conn: PooledConn| {
+             Thread::set_pinned_position(thread_uuid, Some(request.position), &conn)
+                 .map(convert_and_json::<MinimalThreadData,MinimalThreadResponse>)
+                 .map_err(Error::simple_reject)
+         })
+         .boxed()
+}
+
+pub fn unpin_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
+     warp::put2()
+         .and(warp::path("unpin"))
+         .and(uuid_wrap_filter::<ThreadUuid>())
+         .and(moderator_user_filter(s))
+         .and(s.db.clone())
+         .and_then(|thread_uuid: ThreadUuid, _moderator: UserUuid, conn: PooledConn| {
+             Thread::set_pinned_position(thread_uuid, None, &conn)
+                 .map(convert_and_json::<MinimalThreadData,MinimalThreadResponse>)
+                 .map_err(Error::simple_reject)
+         })
+         .boxed()
+}
+
+/// Changes the title of a thread.
+/// This is available to the thread's author and to moderators.
+pub fn edit_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
+     warp::put2()
+         .and(warp::path("edit"))
+         .and(uuid_wrap_filter::<ThreadUuid>())
+         .and(json_body_filter(4))
+         .and(optional_viewer_filter(s))
+         .and(s.db.clone())
+         .and_then(|thread_uuid: ThreadUuid, request: EditThreadRequest, viewer: Viewer, conn: PooledConn| {
+             let user_uuid: UserUuid = match viewer.user_uuid {
+                 Some(user_uuid) => user_uuid,
+                 None => return Error::NotAuthorized { reason: "JWT does not contain Basic User privilege" }.reject()
+             };
+             Thread::edit_title(thread_uuid, request.title, user_uuid, viewer.is_moderator, &conn)
+                 .map(convert_and_json::<MinimalThreadData,MinimalThreadResponse>)
+                 .map_err(Error::simple_reject)
+         })
+         .boxed()
+}
+
+pub fn move_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
+     warp::put2()
+         .and(warp::path("move"))
+         .and(uuid_wrap_filter::<ThreadUuid>())
+         .and(json_body_filter(1))
+         .and(moderator_user_filter(s))
+         .and(s.db.clone())
+         .and_then(|thread_uuid: ThreadUuid, request: MoveThreadRequest, _moderator: UserUuid, conn: PooledConn| {
+             Thread::move_thread(thread_uuid, request.forum_uuid, &conn)
+                 .map(convert_and_json::<MinimalThreadData,MinimalThreadResponse>)
+                 .map_err(Error::simple_reject)
+         })
+         .boxed()
+}
+
+/// Merges the thread into the target thread, responding with the target thread.
+pub fn merge_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
+     warp::put2()
+         .and(warp::path("merge"))
+         .and(uuid_wrap_filter::<ThreadUuid>())
+         .and(json_body_filter(1))
+         .and(moderator_user_filter(s))
+         .and(s.db.clone())
+         .and_then(|thread_uuid: ThreadUuid, request: MergeThreadRequest, _moderator: UserUuid, conn: PooledConn| {
+             Thread::merge_threads(thread_uuid, request.target_thread_uuid, &conn)
+                 .map(convert_and_json::<MinimalThreadData,MinimalThreadResponse>)
+                 .map_err(Error::simple_reject)
+         })
+         .boxed()
+}
+
+/// Splits the post and its replies into a new thread, responding with the new thread.
+pub fn split_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
+     warp::post2()
+         .and(warp::path("split"))
+         .and(uuid_wrap_filter::<PostUuid>())
+         .and(json_body_filter(4))
+         .and(moderator_user_filter(s))
+         .and(s.db.clone())
+         .and_then(|post_uuid: PostUuid, request: SplitThreadRequest, _moderator: UserUuid, conn: PooledConn| {
+             Thread::split_thread(post_uuid, request.title, &conn)
+                 .map(convert_and_json::<MinimalThreadData,MinimalThreadResponse>)
+                 .map_err(Error::simple_reject)
+         })
+         .boxed()
+}
+
 pub fn archive_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
      warp::delete2()
          .and(warp::path("archive"))
@@ -106,15 +224,50 @@ pub fn archive_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
          .boxed()
 }
 
+pub fn unarchive_thread(s: &State) -> BoxedFilter<(impl Reply,)> {
+     warp::put2()
+         .and(warp::path("unarchive"))
+         .and(uuid_wrap_filter::<ThreadUuid>())
+         .and(moderator_user_filter(s))
+         .and(s.db.clone())
+         .and_then(|thread_uuid: ThreadUuid, _moderator: UserUuid, conn: PooledConn| {
+             Thread::unarchive_thread(thread_uuid, &conn)
+                 .map(convert_and_json::<MinimalThreadData,MinimalThreadResponse>)
+                 .map_err(Error::simple_reject)
+         })
+         .boxed()
+}
+
+pub fn get_archived_threads(s: &State) -> BoxedFilter<(impl Reply,)> {
+    warp::get2()
+        .and(warp::path("archived"))
+        .and(uuid_wrap_filter::<ForumUuid>())
+        .and(warp::path::param::<i32>())
+        .and(moderator_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|forum_uuid: ForumUuid, index: i32, _moderator: UserUuid, conn: PooledConn| {
+            let results_per_page: i32 = 25;
+            Thread::get_archived_paginated(forum_uuid, index, results_per_page, &conn)
+                .map(convert_vector_and_json::<MinimalThreadData,MinimalThreadResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
 pub fn get_threads_by_forum_id(s: &State) -> BoxedFilter<(impl Reply,)> {
     warp::get2()
         .and(warp::path("get")) // TODO: this api naming scheme is braindead
         .and(uuid_wrap_filter::<ForumUuid>())
         .and(warp::path::param::<i32>())
+        .and(optional_query_filter::<ThreadListQuery>())
+        .and(optional_normal_user_filter(s))
         .and(s.db.clone())
-        .and_then(|forum_uuid: ForumUuid, index: i32, conn: PooledConn| {
+        .and_then(|forum_uuid: ForumUuid, index: i32, query: ThreadListQuery, user_uuid: Option<UserUuid>, conn: PooledConn| {
             let results_per_page: i32 = 25;
-            Thread::get_paginated(forum_uuid, index, results_per_page, &conn)
+            let order: ThreadOrder = query.order
+                .map(ThreadOrder::from)
+                .unwrap_or(ThreadOrder::Created);
+            Thread::get_paginated_ordered(forum_uuid, order, user_uuid, index, results_per_page, &conn)
                 .map(convert_vector_and_json::<MinimalThreadData,MinimalThreadResponse>)
                 .map_err(Error::simple_reject)
 
@@ -125,11 +278,11 @@ pub fn get_threads_by_forum_id(s: &State) -> BoxedFilter<(impl Reply,)> {
 pub fn get_thread_contents(s: &State) -> BoxedFilter<(impl Reply,)> {
     warp::get2()
         .and(uuid_wrap_filter::<ThreadUuid>())
-        .and(optional_normal_user_filter(s))
+        .and(optional_viewer_filter(s))
         .and(s.db.clone())
-        .and_then(|thread_uuid: ThreadUuid, user_uuid: Option<UserUuid>,conn: PooledConn|{
-            Thread::get_full_thread(thread_uuid, user_uuid, &conn)
-                .map(convert_and_json::<ThreadData,ThreadResponse>)
+        .and_then(|thread_uuid: ThreadUuid, viewer: Viewer, conn: PooledConn|{
+            Thread::get_full_thread(thread_uuid, viewer.user_uuid, viewer.is_moderator, &conn)
+                .map(|thread| convert_for_viewer_and_json::<ThreadData,ThreadResponse>(thread, viewer.is_moderator))
                 .map_err(Error::simple_reject)
         })
         .boxed()
@@ -0,0 +1,430 @@
+//! Uploading files to use in articles and posts, and serving them back.
+//!
+//! Files are uploaded to the api at `/api/upload`, and served from `/uploads/<hash prefix>/<hash>.<extension>`,
+//! with thumbnails of images at `/uploads/<hash prefix>/<hash>.thumb.png`.
+
+use std::fs;
+use std::io;
+use warp::Filter;
+use warp::Buf;
+use warp::filters::BoxedFilter;
+use warp::body::FullBody;
+use warp::reply::Reply;
+use warp::Rejection;
+use warp::http::HeaderMap;
+use warp::http::Response;
+use warp::http::StatusCode;
+use warp::http::header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE, ETAG, IF_NONE_MATCH, X_CONTENT_TYPE_OPTIONS};
+use futures::Future;
+use futures::IntoFuture;
+use futures::future::Either;
+use db::upload::{Upload, NewUpload, MAX_UPLOAD_SIZE, UPLOADS_PATH};
+use identifiers::upload::UploadUuid;
+use identifiers::user::UserUuid;
+use wire::upload::UploadResponse;
+use error::Error;
+use pool::PooledConn;
+use crate::state::State;
+use crate::state::jwt::normal_user_filter;
+use crate::state::upload_store::UploadStore;
+use crate::logging::log_attach;
+use crate::logging::HttpMethod;
+use crate::util::convert_and_json;
+use crate::util::convert_vector_and_json;
+use crate::uuid_integration::uuid_wrap_filter;
+use crate::multipart;
+use crate::multipart::Part;
+
+/// How much larger than the file the rest of a multipart body can be.
+const MULTIPART_OVERHEAD: u64 = 16 * 1024;
+/// Stored files never change, so browsers can keep them for as long as they like.
+const CACHE_FOREVER: &str = "public, max-age=31536000, immutable";
+
+pub fn upload_api(s: &State) -> BoxedFilter<(impl Reply,)> {
+    info!("Attaching Upload API");
+    warp::path("upload")
+        .and(
+            create_upload(s)
+                .or(get_own_uploads(s))
+                .or(delete_upload(s))
+        )
+        .with(warp::log("upload"))
+        .boxed()
+}
+
+/// Serves the stored files, outside of the api.
+pub fn upload_files(s: &State) -> BoxedFilter<(impl Reply,)> {
+    info!("Attaching Uploaded Files handler");
+
+    warp::get2()
+        .and(warp::path(UPLOADS_PATH))
+        .and(warp::path::param::<String>())
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(warp::header::headers_cloned())
+        .and(s.uploads.clone())
+        .and_then(|directory: String, name: String, headers: HeaderMap, store: UploadStore| {
+            file_response(&directory, &name, &headers, &store)
+        })
+        .with(warp::log("uploads"))
+        .boxed()
+}
+
+/// Takes the file out of the form, and stores it on behalf of the user.
+fn create_upload(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Post, "upload/");
+
+    warp::post2()
+        .and(warp::path::end())
+        .and(warp::header::<String>("content-type"))
+        .and(warp::body::content_length_limit(MAX_UPLOAD_SIZE + MULTIPART_OVERHEAD))
+        .and(warp::body::concat())
+        .and(normal_user_filter(s))
+        .and(s.uploads.clone())
+        .and(s.db.clone())
+        .and_then(|content_type: String, body: FullBody, user_uuid: UserUuid, store: UploadStore, conn: PooledConn| {
+            let file: Result<Part, Error> = multipart::boundary(&content_type)
+                .and_then(|boundary| multipart::parse(body.bytes(), &boundary))
+                .and_then(|parts| parts.into_iter().find(|part| part.name == "file" && part.file_name.is_some()))
+                .ok_or(Error::BadRequest);
+
+            file.into_future()
+                .and_then(move |file| store_upload(file, user_uuid, store, conn))
+                .map(convert_and_json::<Upload, UploadResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Checks the file, then writes it to disk and records it.
+///
+/// Thumbnails of images are made off of the server's threads, as they can take a while.
+fn store_upload(file: Part, user_uuid: UserUuid, store: UploadStore, conn: PooledConn) -> impl Future<Item = Upload, Error = Error> {
+    let new_upload: Result<(NewUpload, &'static str), Error> = check_and_store(&file, user_uuid, &store);
+    let thumbnail_store: UploadStore = store.clone();
+    new_upload
+        .into_future()
+        .and_then(move |(mut new_upload, extension)| {
+            if new_upload.mime_type.starts_with("image/") {
+                let thumbnail = thumbnail_store
+                    .make_thumbnail_in_background(new_upload.content_hash.clone(), extension)
+                    .map(move |has_thumbnail| {
+                        new_upload.has_thumbnail = has_thumbnail;
+                        (new_upload, extension)
+                    })
+                    .map_err(|_| Error::InternalServerError);
+                Either::A(thumbnail)
+            } else {
+                Either::B(Ok((new_upload, extension)).into_future())
+            }
+        })
+        .and_then(move |(mut new_upload, extension)| {
+            let content_hash: String = new_upload.content_hash.clone();
+            Upload::with_content_lock(&content_hash, &conn, || {
+                // The last other upload of the content may have been deleted since the file was written, taking its files with it.
+                store_file(&store, &file.data, extension)?;
+                new_upload.has_thumbnail = new_upload.has_thumbnail && store.has_thumbnail(&content_hash);
+                Upload::create_upload(new_upload, &conn)
+            })
+        })
+}
+
+/// Checks the file's content, then writes it to disk.
+///
+/// Returns the upload to record, without a thumbnail, and the extension the file was stored with.
+fn check_and_store(file: &Part, user_uuid: UserUuid, store: &UploadStore) -> Result<(NewUpload, &'static str), Error> {
+    let declared_type: &str = file.content_type.as_ref().map(String::as_str).unwrap_or_default();
+    let mime_type: &str = Upload::check_content(declared_type, &file.data)?;
+    let extension: &'static str = Upload::extension_for(mime_type).ok_or(Error::UnsupportedMediaType)?;
+
+    let content_hash: String = store_file(store, &file.data, extension)?;
+
+    let new_upload = NewUpload {
+        owner_uuid: user_uuid.0,
+        content_hash,
+        file_name: Upload::clean_file_name(file.file_name.as_ref().map(String::as_str).unwrap_or_default()),
+        mime_type: mime_type.to_string(),
+        size: file.data.len() as i64,
+        has_thumbnail: false,
+    };
+    Ok((new_upload, extension))
+}
+
+/// Writes the content to disk, unless it is already there, returning its hash.
+fn store_file(store: &UploadStore, data: &[u8], extension: &str) -> Result<String, Error> {
+    store.store(data, extension)
+        .map_err(|e| {
+            error!("Couldn't store upload: {}", e);
+            Error::InternalServerError
+        })
+}
+
+/// Lists the files the user has uploaded, so they can be used again.
+fn get_own_uploads(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "upload/");
+
+    warp::get2()
+        .and(warp::path::end())
+        .and(normal_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|user_uuid: UserUuid, conn: PooledConn| {
+            Upload::get_uploads_for_user(user_uuid, &conn)
+                .map(convert_vector_and_json::<Upload, UploadResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Deletes the upload, along with its file if no other upload has the same content and type,
+/// and its thumbnail if no other upload has the same content.
+fn delete_upload(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Delete, "upload/<uuid>");
+
+    warp::delete2()
+        .and(uuid_wrap_filter::<UploadUuid>())
+        .and(normal_user_filter(s))
+        .and(s.uploads.clone())
+        .and(s.db.clone())
+        .and_then(|upload_uuid: UploadUuid, user_uuid: UserUuid, store: UploadStore, conn: PooledConn| {
+            let upload: Upload = Upload::get_upload(upload_uuid, &conn)
+                .map_err(Error::simple_reject)?;
+            if upload.owner_uuid != user_uuid.0 {
+                return Error::NotAuthorized { reason: "User didn't upload the file" }.reject()
+            }
+
+            let upload: Upload = Upload::with_content_lock(&upload.content_hash, &conn, || {
+                let upload: Upload = Upload::delete_upload(upload_uuid, &conn)?;
+                let shares_file: bool = Upload::get_by_content_hash_and_type(&upload.content_hash, &upload.mime_type, &conn)?.is_some();
+                let shares_thumbnail: bool = shares_file || Upload::get_by_content_hash(&upload.content_hash, &conn)?.is_some();
+
+                // The upload is gone either way, the files just take up space.
+                if !shares_file {
+                    let extension: &str = Upload::extension_for(&upload.mime_type).unwrap_or_default();
+                    if let Err(e) = store.remove_content(&upload.content_hash, extension) {
+                        warn!("Couldn't remove the file of upload {}: {}", upload.uuid, e);
+                    }
+                }
+                if !shares_thumbnail {
+                    if let Err(e) = store.remove_thumbnail(&upload.content_hash) {
+                        warn!("Couldn't remove the thumbnail of upload {}: {}", upload.uuid, e);
+                    }
+                }
+                Ok(upload)
+            })
+                .map_err(Error::simple_reject)?;
+            Ok(convert_and_json::<Upload, UploadResponse>(upload))
+        })
+        .boxed()
+}
+
+/// Works out the type of a stored file from its name, which is its hash followed by its extension.
+///
+/// Returns None if the name isn't one a stored file could have,
+/// which keeps the path from reaching outside of the upload directory.
+fn stored_file_type(directory: &str, name: &str) -> Option<&'static str> {
+    let mut name_parts = name.splitn(2, '.');
+    let hash: &str = name_parts.next()?;
+    let extension: &str = name_parts.next()?;
+    let is_hash: bool = hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase());
+    if !is_hash || directory != &hash[..2] {
+        return None
+    }
+    if extension == "thumb.png" {
+        Some("image/png")
+    } else {
+        Upload::mime_type_for(extension)
+    }
+}
+
+/// Responds with the stored file, or with Not Modified if the client already has it.
+fn file_response(directory: &str, name: &str, headers: &HeaderMap, store: &UploadStore) -> Result<Response<Vec<u8>>, Rejection> {
+    let mime_type: &str = stored_file_type(directory, name)
+        .ok_or_else(warp::reject::not_found)?;
+    // The name includes the hash, so it identifies this version of the file.
+    let etag: String = format!("\"{}\"", name);
+    let not_modified: bool = headers
+        .get(IF_NONE_MATCH)
+        .and_then(|x| x.to_str().ok())
+        .map(|tags| tags.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"))
+        .unwrap_or(false);
+
+    let response = if not_modified {
+        Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(ETAG, etag.as_str())
+            .header(CACHE_CONTROL, CACHE_FOREVER)
+            .body(vec![])
+    } else {
+        let content: Vec<u8> = fs::read(store.path(&format!("{}/{}", directory, name)))
+            .map_err(|e| {
+                if e.kind() != io::ErrorKind::NotFound {
+                    error!("Couldn't read uploaded file {}: {}", name, e);
+                }
+                warp::reject::not_found()
+            })?;
+        let mut builder = Response::builder();
+        builder
+            .header(CONTENT_TYPE, mime_type)
+            .header(ETAG, etag.as_str())
+            .header(CACHE_CONTROL, CACHE_FOREVER)
+            // Browsers shouldn't second guess the type, which was checked when the file was uploaded.
+            .header(X_CONTENT_TYPE_OPTIONS, "nosniff");
+        // Only images are shown in the page, everything else is downloaded rather than opened on the site.
+        if !mime_type.starts_with("image/") {
+            builder.header(CONTENT_DISPOSITION, "attachment");
+        }
+        builder.body(content)
+    };
+    response.map_err(|_| Error::InternalServerError.simple_reject())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::sync::Arc;
+    use testing_fixtures::fixtures::user::UserFixture;
+    use testing_common::setup::setup_warp;
+    use pool::Pool;
+    use crate::util::test::deserialize;
+    use crate::state::jwt::AUTHORIZATION_HEADER_KEY;
+    use crate::state::upload_store::{Thumbnailer, upload_store_filter};
+    use crate::multipart::tests::file_body;
+    use wire::user::BEARER;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nnot really an image";
+    const BOUNDARY: &str = "boundary";
+
+    /// Uses the image as its own thumbnail.
+    struct CopyThumbnailer;
+
+    impl Thumbnailer for CopyThumbnailer {
+        fn thumbnail(&self, source: &Path, destination: &Path) -> Result<(), String> {
+            fs::copy(source, destination).map(|_| ()).map_err(|e| e.to_string())
+        }
+    }
+
+    fn upload(s: &State, jwt: &str, file_name: &str, content_type: &str, data: &[u8]) -> Response<bytes::Bytes> {
+        let body: Vec<u8> = file_body(BOUNDARY, file_name, content_type, data);
+        warp::test::request()
+            .method("POST")
+            .path("/")
+            .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+            .header("content-type", format!("multipart/form-data; boundary={}", BOUNDARY))
+            .header("content-length", body.len().to_string())
+            .body(body)
+            .reply(&create_upload(s).recover(error::warp_support::customize_error))
+    }
+
+    #[test]
+    fn upload_and_serve_image() {
+        setup_warp(|fixture: &UserFixture, pool: Pool| {
+            let mut s = State::testing_init(pool, fixture.secret.clone());
+            let directory = ::std::env::temp_dir().join(format!("uploads-{}", ::uuid::Uuid::new_v4()));
+            s.uploads = upload_store_filter(UploadStore::new(directory, Arc::new(CopyThumbnailer)));
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.normal_user.user_name.clone());
+
+            let response = upload(&s, &jwt, "C:\\Pictures\\cat.png", "image/png", PNG);
+            assert_eq!(response.status(), 200);
+            let uploaded: UploadResponse = deserialize(response);
+            assert_eq!(uploaded.file_name, "cat.png");
+            assert_eq!(uploaded.mime_type, "image/png");
+            assert_eq!(uploaded.size, PNG.len() as i64);
+            assert_eq!(uploaded.markdown, format!("![cat.png]({})", uploaded.url));
+            let hash: String = UploadStore::hash(PNG);
+            assert_eq!(uploaded.url, format!("/uploads/{}/{}.png", &hash[..2], hash));
+            let thumbnail_url: String = uploaded.thumbnail_url.clone().expect("Should have a thumbnail");
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&uploaded.url)
+                .reply(&upload_files(&s));
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.headers()[CONTENT_TYPE], "image/png");
+            assert_eq!(response.headers()[CACHE_CONTROL], CACHE_FOREVER);
+            assert!(response.headers().get(CONTENT_DISPOSITION).is_none());
+            assert_eq!(response.body().as_ref(), PNG);
+
+            let etag = response.headers()[ETAG].clone();
+            let response = warp::test::request()
+                .method("GET")
+                .path(&uploaded.url)
+                .header("If-None-Match", etag.to_str().unwrap())
+                .reply(&upload_files(&s));
+            assert_eq!(response.status(), 304);
+
+            let response = warp::test::request()
+                .method("GET")
+                .path(&thumbnail_url)
+                .reply(&upload_files(&s));
+            assert_eq!(response.status(), 200);
+
+            // Paths that couldn't be a stored file aren't looked up.
+            let response = warp::test::request()
+                .method("GET")
+                .path("/uploads/..%2F/secret.png")
+                .reply(&upload_files(&s));
+            assert_eq!(response.status(), 404);
+
+            // Deleting the only upload of the content deletes the file.
+            let response = warp::test::request()
+                .method("DELETE")
+                .path(&format!("/upload/{}", uploaded.uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&upload_api(&s));
+            assert_eq!(response.status(), 200);
+            let response = warp::test::request()
+                .method("GET")
+                .path(&uploaded.url)
+                .reply(&upload_files(&s));
+            assert_eq!(response.status(), 404);
+        })
+    }
+
+    #[test]
+    fn uploads_are_checked() {
+        setup_warp(|fixture: &UserFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.secret.clone());
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.normal_user.user_name.clone());
+
+            // The content has to match the declared type.
+            let response = upload(&s, &jwt, "cat.png", "image/png", b"<script>alert(1)</script>");
+            assert_eq!(response.status(), 415);
+            let response = upload(&s, &jwt, "cat.svg", "image/svg+xml", b"<svg></svg>");
+            assert_eq!(response.status(), 415);
+
+            // Without ImageMagick, or if the image can't be read, there is no thumbnail.
+            let response = upload(&s, &jwt, "notes.txt", "text/plain; charset=utf-8", b"Some [notes]");
+            assert_eq!(response.status(), 200);
+            let uploaded: UploadResponse = deserialize(response);
+            assert_eq!(uploaded.thumbnail_url, None);
+            assert_eq!(uploaded.markdown, format!("[notes.txt]({})", uploaded.url));
+            let response = warp::test::request()
+                .method("GET")
+                .path(&uploaded.url)
+                .reply(&upload_files(&s));
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.headers()[CONTENT_DISPOSITION], "attachment");
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/upload")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .reply(&upload_api(&s));
+            let uploads: Vec<UploadResponse> = deserialize(response);
+            assert_eq!(uploads, vec![uploaded.clone()]);
+
+            // Only the uploader can delete the upload.
+            let admin_jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.admin_user.user_name.clone());
+            let response = warp::test::request()
+                .method("DELETE")
+                .path(&format!("/{}", uploaded.uuid))
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, admin_jwt))
+                .reply(&delete_upload(&s).recover(error::warp_support::customize_error));
+            assert_eq!(response.status(), 403);
+        })
+    }
+}
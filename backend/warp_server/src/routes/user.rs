@@ -22,11 +22,16 @@ use pool::PooledConn;
 use crate::state::banned_list::BannedList;
 use error::Error;
 
+/// The number of users listed on the reputation leaderboard.
+const LEADERBOARD_SIZE: i64 = 25;
+
 pub fn user_api(s: &State) -> BoxedFilter<(impl warp::Reply,)> {
     info!("Attaching User API");
     warp::path("user")
         .and(
-            get_user(s)
+            get_leaderboard(s)
+                .or(recompute_reputation(s))
+                .or(get_user(s))
                 .or(get_users(s))
                 .or(create_user(s))
                 .or(update_user_display_name(s))
@@ -57,6 +62,41 @@ fn get_user(s: &State) -> BoxedFilter<(impl Reply,)> {
 
 
 
+/// Gets the users with the highest reputation.
+fn get_leaderboard(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Get, "user/leaderboard");
+
+    warp::get2()
+        .and(warp::path("leaderboard"))
+        .and(warp::path::end())
+        .and(s.db.clone())
+        .and_then(|conn: PooledConn| {
+            User::get_leaderboard(LEADERBOARD_SIZE, &conn)
+                .map(convert_vector_and_json::<User, UserResponse>)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+/// Recomputes every user's reputation from scratch, responding with the number of users updated.
+fn recompute_reputation(s: &State) -> BoxedFilter<(impl Reply,)> {
+
+    log_attach(HttpMethod::Put, "user/reputation");
+
+    warp::put2()
+        .and(warp::path("reputation"))
+        .and(warp::path::end())
+        .and(admin_user_filter(s))
+        .and(s.db.clone())
+        .and_then(|_admin: UserUuid, conn: PooledConn| {
+            User::recompute_reputation(&conn)
+                .map(|updated: usize| warp::reply::json(&updated))
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
 fn get_users(s: &State) -> BoxedFilter<(impl Reply,)> {
 
     log_attach(HttpMethod::Get, "user/<i32 where i32 >= 1>");
@@ -337,4 +377,38 @@ mod tests {
         })
     }
 
+    #[test]
+    fn leaderboard_and_recompute() {
+        setup_warp(|fixture: &UserFixture, pool: Pool| {
+            let s = State::testing_init(pool, fixture.secret.clone());
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/user/leaderboard")
+                .reply(&user_api(&s));
+            assert_eq!(response.status(), 200);
+            let users: Vec<UserResponse> = deserialize(response);
+            assert_eq!(users.len(), 2);
+            assert!(users.iter().all(|user| user.reputation == 0));
+
+            let jwt: String = crate::routes::auth::tests::get_jwt_string(&s, fixture.normal_user.user_name.clone());
+            let response = warp::test::request()
+                .method("PUT")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .path("/user/reputation")
+                .reply(&user_api(&s));
+            assert_ne!(response.status(), 200, "Only admins can recompute reputation");
+
+            let jwt: String = crate::routes::auth::tests::get_admin_jwt_string(&s, fixture);
+            let response = warp::test::request()
+                .method("PUT")
+                .header(AUTHORIZATION_HEADER_KEY, format!("{} {}", BEARER, jwt))
+                .path("/user/reputation")
+                .reply(&user_api(&s));
+            assert_eq!(response.status(), 200);
+            let updated: usize = deserialize(response);
+            assert_eq!(updated, 2);
+        })
+    }
+
 }
\ No newline at end of file
@@ -96,7 +96,40 @@ pub fn optional_normal_user_filter(s: &State) -> BoxedFilter<(Option<UserUuid>,)
 }
 
 
-#[allow(dead_code)]
+/// The user making a request, if any, and whether they have moderator privileges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewer {
+    pub user_uuid: Option<UserUuid>,
+    pub is_moderator: bool,
+    pub is_publisher: bool,
+}
+
+/// Gets the Viewer from the request.
+/// This is intended for routes that are open to anyone, but show more to moderators and publishers.
+pub fn optional_viewer_filter(s: &State) -> BoxedFilter<(Viewer,)> {
+
+    fn handle_jwt(server_jwt: ServerJwt) -> Result<Viewer, Rejection>{
+        let roles = &server_jwt.0.user_roles;
+        let user_uuid = if roles.contains(&UserRole::Unprivileged) {
+            Some(server_jwt.0.sub)
+        } else {
+            None
+        };
+        Ok(Viewer {
+            user_uuid,
+            is_moderator: roles.contains(&UserRole::Moderator),
+            is_publisher: roles.contains(&UserRole::Publisher)
+        })
+    }
+    warp::any()
+        .and(jwt_filter(s))
+        .and_then(handle_jwt)
+        .or(warp::any().map(|| Viewer { user_uuid: None, is_moderator: false, is_publisher: false }))
+        .unify::<(Viewer,)>()
+        .boxed()
+}
+
+
 pub fn publisher_user_filter(s: &State) -> BoxedFilter<(UserUuid,)> {
     warp::any()
         .and(jwt_filter(s))
@@ -1,9 +1,12 @@
 /// This module deals with anything in the server that requires some stateful interaction.
-/// This includes DB access, and secret management.
+/// This includes DB access, secret management, the storage of uploaded files, and counting article views.
 
 pub mod db_integration;
 pub mod jwt;
 pub mod banned_list;
+pub mod rate_limit;
+pub mod upload_store;
+pub mod view_counter;
 
 use pool::PooledConn;
 use warp::filters::BoxedFilter;
@@ -11,32 +14,72 @@ use auth::Secret;
 use self::jwt::secret_filter;
 use self::banned_list::BannedList;
 use self::banned_list::banned_list_filter;
+use self::rate_limit::RateLimiter;
+use self::rate_limit::RateLimitConfig;
+use self::rate_limit::rate_limiter_filter;
+use self::upload_store::{UploadStore, ImageMagickThumbnailer};
+use self::upload_store::upload_store_filter;
+use self::view_counter::ViewCounter;
+use self::view_counter::view_counter_filter;
+use std::path::PathBuf;
+use std::sync::Arc;
+use db::reaction::ReactionSet;
+use warp::Filter;
 #[cfg(test)]
 use pool::Pool;
 
+/// The address of the server when it is run on this machine.
+pub const DEFAULT_SITE_URL: &str = "http://localhost:8001";
+
 /// State object that should be accessable to most routes.
 /// This object will hold references to functions that will allow the production
 /// of database connections and secrets used in validating JWTs.
 pub struct State {
     pub db: BoxedFilter<(PooledConn,)>,
     pub secret: BoxedFilter<(Secret,)>,
-    pub banned_list: BoxedFilter<(BannedList,)>
+    pub banned_list: BoxedFilter<(BannedList,)>,
+    /// The reactions users may leave on posts.
+    pub reactions: BoxedFilter<(ReactionSet,)>,
+    pub rate_limiter: BoxedFilter<(RateLimiter,)>,
+    pub uploads: BoxedFilter<(UploadStore,)>,
+    pub view_counter: BoxedFilter<(ViewCounter,)>,
+    /// The address the site is reached at, without a trailing slash.
+    pub site_url: BoxedFilter<(String,)>
 }
 
 /// Configuration struct used in constructing the State struct.
 pub struct StateConfig {
     pub specified_secret: Option<String>,
-    pub database_url: String
+    pub database_url: String,
+    pub reactions: ReactionSet,
+    pub rate_limits: RateLimitConfig,
+    /// Where uploaded files are kept.
+    pub upload_directory: PathBuf,
+    /// Whether the server is behind a proxy that sets the client's address in the headers,
+    /// so that the readers of articles that aren't signed in can be counted.
+    pub trusted_proxy: bool,
+    /// The address the site is reached at, which absolute links, like those in feeds, start with.
+    pub site_url: String
 }
 
 /// By default:
 /// * The secret will be randomly generated.
 /// * The database URL will point to the default database as defined by an environment variable.
+/// * The default set of reactions will be allowed on posts.
+/// * The default rate limits will be applied.
+/// * Uploaded files will be kept in the `uploads` directory.
+/// * Addresses in the headers won't be trusted, so only signed in readers are counted.
+/// * Links will point to the server running on this machine.
 impl Default for StateConfig {
     fn default() -> Self {
         StateConfig {
             specified_secret: None,
             database_url: pool::DATABASE_URL.to_string(),
+            reactions: ReactionSet::default(),
+            rate_limits: RateLimitConfig::default(),
+            upload_directory: PathBuf::from("uploads"),
+            trusted_proxy: false,
+            site_url: DEFAULT_SITE_URL.to_string(),
         }
     }
 }
@@ -59,23 +102,48 @@ impl State {
         State {
             db: db_integration::db_filter(pool),
             secret: secret_filter(secret),
-            banned_list: banned_list_filter(banned_list)
+            banned_list: banned_list_filter(banned_list),
+            reactions: reactions_filter(config.reactions),
+            rate_limiter: rate_limiter_filter(RateLimiter::new(config.rate_limits)),
+            uploads: upload_store_filter(UploadStore::new(config.upload_directory, Arc::new(ImageMagickThumbnailer))),
+            view_counter: view_counter_filter(ViewCounter::new(config.trusted_proxy)),
+            site_url: site_url_filter(config.site_url)
         }
     }
 }
 
 
+fn reactions_filter(reactions: ReactionSet) -> BoxedFilter<(ReactionSet,)> {
+    warp::any()
+        .map(move || reactions.clone())
+        .boxed()
+}
+
+fn site_url_filter(site_url: String) -> BoxedFilter<(String,)> {
+    let site_url: String = site_url.trim_end_matches('/').to_string();
+    warp::any()
+        .map(move || site_url.clone())
+        .boxed()
+}
+
 #[cfg(test)]
 impl State {
 
     /// An initialization of the State struct that should only be used for testing.
     /// It uses a parameterized Pool, which allows for the same connections used in testing to be provided,
     /// as well as the same secret used to authorize user sign ins.
+    /// Uploads are kept in a new temporary directory.
     pub fn testing_init(pool: Pool, secret: Secret) -> State {
+        let upload_directory: PathBuf = ::std::env::temp_dir().join(format!("uploads-{}", ::uuid::Uuid::new_v4()));
         State {
             db: db_integration::db_filter(pool),
             secret: secret_filter(secret),
-            banned_list: banned_list_filter(BannedList::default())
+            banned_list: banned_list_filter(BannedList::default()),
+            reactions: reactions_filter(ReactionSet::default()),
+            rate_limiter: rate_limiter_filter(RateLimiter::default()),
+            uploads: upload_store_filter(UploadStore::new(upload_directory, Arc::new(ImageMagickThumbnailer))),
+            view_counter: view_counter_filter(ViewCounter::default()),
+            site_url: site_url_filter(DEFAULT_SITE_URL.to_string())
         }
     }
 }
@@ -0,0 +1,240 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+use identifiers::user::UserUuid;
+use warp::filters::BoxedFilter;
+use warp::Filter;
+use wire::user::UserRole;
+use auth::ServerJwt;
+use error::Error;
+use crate::state::State;
+use crate::state::jwt::jwt_filter;
+
+/// Once this many buckets are being tracked, the ones that have refilled are dropped,
+/// as they are indistinguishable from a user that hasn't acted yet.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// The actions that users are limited in how often they can perform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    CreatePost,
+    CreateThread,
+    CreateQuestion,
+    SendMessage,
+}
+
+/// How often a single user can perform an action.
+///
+/// Users can perform the action `capacity` times in quick succession,
+/// after which they regain the ability to perform it once every `refill_interval`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub capacity: u32,
+    pub refill_interval: Duration,
+}
+
+impl RateLimit {
+    pub fn new(capacity: u32, refill_interval: Duration) -> RateLimit {
+        RateLimit {
+            capacity,
+            refill_interval,
+        }
+    }
+}
+
+/// The rate limits for each action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    pub create_post: RateLimit,
+    pub create_thread: RateLimit,
+    pub create_question: RateLimit,
+    pub send_message: RateLimit,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            create_post: RateLimit::new(10, Duration::from_secs(6)),
+            create_thread: RateLimit::new(3, Duration::from_secs(120)),
+            create_question: RateLimit::new(5, Duration::from_secs(30)),
+            send_message: RateLimit::new(20, Duration::from_secs(1)),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn limit(&self, action: Action) -> RateLimit {
+        match action {
+            Action::CreatePost => self.create_post,
+            Action::CreateThread => self.create_thread,
+            Action::CreateQuestion => self.create_question,
+            Action::SendMessage => self.send_message,
+        }
+    }
+
+    pub fn limit_mut(&mut self, action: Action) -> &mut RateLimit {
+        match action {
+            Action::CreatePost => &mut self.create_post,
+            Action::CreateThread => &mut self.create_thread,
+            Action::CreateQuestion => &mut self.create_question,
+            Action::SendMessage => &mut self.send_message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token buckets for each user and action.
+///
+/// Like the BannedList, this is held in memory, so limits are reset when the server restarts.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    config: Arc<RateLimitConfig>,
+    buckets: Arc<Mutex<HashMap<(UserUuid, Action), Bucket>>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::new(RateLimitConfig::default())
+    }
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> RateLimiter {
+        RateLimiter {
+            config: Arc::new(config),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Takes a token from the user's bucket for the action.
+    /// If the bucket is empty, the error indicates how many seconds the user must wait.
+    pub fn check(&self, user_uuid: UserUuid, action: Action) -> Result<(), Error> {
+        self.check_at(user_uuid, action, Instant::now())
+    }
+
+    fn check_at(&self, user_uuid: UserUuid, action: Action, now: Instant) -> Result<(), Error> {
+        let limit: RateLimit = self.config.limit(action);
+        let capacity = f64::from(limit.capacity);
+        let interval: f64 = seconds(limit.refill_interval);
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= PRUNE_THRESHOLD {
+            let config = &self.config;
+            buckets.retain(|&(_, action), bucket| !bucket.refilled(config.limit(action), now));
+        }
+
+        let bucket = buckets
+            .entry((user_uuid, action))
+            .or_insert(Bucket {
+                tokens: capacity,
+                last_refill: now,
+            });
+
+        // Another request may have refilled the bucket after this one took its timestamp.
+        if now > bucket.last_refill {
+            let elapsed: f64 = seconds(now - bucket.last_refill);
+            bucket.tokens = (bucket.tokens + elapsed / interval).min(capacity);
+            bucket.last_refill = now;
+        }
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let retry_after = ((1.0 - bucket.tokens) * interval).ceil() as u64;
+            Err(Error::RateLimited { retry_after: retry_after.max(1) })
+        }
+    }
+}
+
+impl Bucket {
+    /// Whether enough time has passed for the bucket to be full again.
+    fn refilled(&self, limit: RateLimit, now: Instant) -> bool {
+        now > self.last_refill
+            && self.tokens + seconds(now - self.last_refill) / seconds(limit.refill_interval) >= f64::from(limit.capacity)
+    }
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1_000_000_000.0
+}
+
+pub fn rate_limiter_filter(rate_limiter: RateLimiter) -> BoxedFilter<(RateLimiter,)> {
+    warp::any()
+        .map(move || rate_limiter.clone())
+        .boxed()
+}
+
+/// Like the `normal_user_filter`, but rejects the request if the user has performed the action too often.
+/// Moderators and admins are exempt from rate limits.
+pub fn rate_limited_user_filter(s: &State, action: Action) -> BoxedFilter<(UserUuid,)> {
+    warp::any()
+        .and(jwt_filter(s))
+        .and(s.rate_limiter.clone())
+        .and_then(move |server_jwt: ServerJwt, rate_limiter: RateLimiter| {
+            let roles = &server_jwt.0.user_roles;
+            if !roles.contains(&UserRole::Unprivileged) {
+                return Error::NotAuthorized{reason: "JWT does not contain Basic User privilege"}.reject()
+            }
+            if roles.contains(&UserRole::Moderator) || roles.contains(&UserRole::Admin) {
+                return Ok(server_jwt.0.sub)
+            }
+            rate_limiter
+                .check(server_jwt.0.sub, action)
+                .map(|_| server_jwt.0.sub)
+                .map_err(Error::simple_reject)
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter() -> RateLimiter {
+        let mut config = RateLimitConfig::default();
+        config.create_post = RateLimit::new(2, Duration::from_secs(10));
+        RateLimiter::new(config)
+    }
+
+    #[test]
+    fn bucket_empties_and_refills() {
+        let limiter = limiter();
+        let user = UserUuid::default();
+        let start = Instant::now();
+
+        assert!(limiter.check_at(user, Action::CreatePost, start).is_ok());
+        assert!(limiter.check_at(user, Action::CreatePost, start).is_ok());
+        assert_eq!(
+            limiter.check_at(user, Action::CreatePost, start),
+            Err(Error::RateLimited { retry_after: 10 })
+        );
+        assert_eq!(
+            limiter.check_at(user, Action::CreatePost, start + Duration::from_secs(4)),
+            Err(Error::RateLimited { retry_after: 6 })
+        );
+        assert!(limiter.check_at(user, Action::CreatePost, start + Duration::from_secs(10)).is_ok());
+    }
+
+    #[test]
+    fn buckets_are_per_user_and_action() {
+        let limiter = limiter();
+        let user = UserUuid::default();
+        let other_user = UserUuid(uuid::Uuid::new_v4());
+        let now = Instant::now();
+
+        assert!(limiter.check_at(user, Action::CreatePost, now).is_ok());
+        assert!(limiter.check_at(user, Action::CreatePost, now).is_ok());
+        assert!(limiter.check_at(user, Action::CreatePost, now).is_err());
+
+        assert!(limiter.check_at(other_user, Action::CreatePost, now).is_ok());
+        assert!(limiter.check_at(user, Action::CreateThread, now).is_ok());
+    }
+}
@@ -0,0 +1,226 @@
+//! Keeps uploaded files on disk.
+//!
+//! Files are named after the hash of their content, so uploading the same file twice only stores it once,
+//! and a file's url never has to change, letting browsers cache it indefinitely.
+
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, SyncSender};
+use std::thread;
+use std::time::{Duration, Instant};
+use futures::Future;
+use futures::sync::oneshot;
+use sha1::{Digest, Sha1};
+use uuid::Uuid;
+use warp::filters::BoxedFilter;
+use warp::Filter;
+use db::upload::Upload;
+
+/// The largest that either side of a thumbnail can be, in pixels.
+pub const THUMBNAIL_SIZE: u32 = 256;
+/// How long ImageMagick gets to make a thumbnail before it is stopped,
+/// so a huge or malicious image can't tie up the server.
+const THUMBNAIL_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often to check whether ImageMagick has finished.
+const THUMBNAIL_POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// How many thumbnails are made at once.
+const THUMBNAIL_WORKERS: usize = 2;
+/// How many images can wait for a thumbnail, beyond which images are stored without one.
+const THUMBNAIL_QUEUE_SIZE: usize = 32;
+
+/// Makes a thumbnail on one of the worker threads.
+type ThumbnailJob = Box<dyn FnOnce() + Send>;
+
+/// Something that can shrink images.
+pub trait Thumbnailer: Send + Sync {
+    /// Writes a PNG version of the image at the source, shrunk to fit within `THUMBNAIL_SIZE`, to the destination.
+    fn thumbnail(&self, source: &Path, destination: &Path) -> Result<(), String>;
+}
+
+/// Makes thumbnails by running ImageMagick's `convert`.
+/// If ImageMagick isn't installed, uploads just won't have thumbnails.
+pub struct ImageMagickThumbnailer;
+
+impl Thumbnailer for ImageMagickThumbnailer {
+    fn thumbnail(&self, source: &Path, destination: &Path) -> Result<(), String> {
+        let mut child = Command::new("convert")
+            // Images can claim to be far larger than their files, so ImageMagick's resources are capped.
+            .args(&["-limit", "memory", "256MiB"])
+            .args(&["-limit", "map", "512MiB"])
+            .args(&["-limit", "area", "128MP"])
+            .args(&["-limit", "disk", "1GiB"])
+            // Only the first frame of animated images is used.
+            .arg(format!("{}[0]", source.display()))
+            // Images that are already small enough aren't enlarged.
+            .arg("-thumbnail")
+            .arg(format!("{0}x{0}>", THUMBNAIL_SIZE))
+            .arg(format!("png:{}", destination.display()))
+            .spawn()
+            .map_err(|e| e.to_string())?;
+
+        let started: Instant = Instant::now();
+        let status: ExitStatus = loop {
+            if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+                break status
+            }
+            if started.elapsed() > THUMBNAIL_TIMEOUT {
+                let _ = child.kill();
+                let _ = child.wait();
+                // Don't leave a partly written thumbnail to be served.
+                let _ = fs::remove_file(destination);
+                return Err(format!("convert took longer than {:?}", THUMBNAIL_TIMEOUT))
+            }
+            thread::sleep(THUMBNAIL_POLL_INTERVAL);
+        };
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("convert exited with {}", status))
+        }
+    }
+}
+
+/// Where uploaded files are kept, and how their thumbnails are made.
+#[derive(Clone)]
+pub struct UploadStore {
+    directory: PathBuf,
+    thumbnailer: Arc<dyn Thumbnailer>,
+    /// Queues images for the threads that make thumbnails.
+    thumbnail_jobs: SyncSender<ThumbnailJob>,
+}
+
+impl UploadStore {
+    /// Creates the store, starting the threads that make thumbnails.
+    /// The threads stop once the store and all of its clones are dropped.
+    pub fn new(directory: PathBuf, thumbnailer: Arc<dyn Thumbnailer>) -> UploadStore {
+        UploadStore {
+            directory,
+            thumbnailer,
+            thumbnail_jobs: start_thumbnail_workers(),
+        }
+    }
+
+    /// Gets the hex encoded SHA-1 hash of the content.
+    pub fn hash(data: &[u8]) -> String {
+        Sha1::digest(data)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Gets where the file is, given its path relative to the upload directory.
+    pub fn path(&self, relative_path: &str) -> PathBuf {
+        self.directory.join(relative_path)
+    }
+
+    /// Writes the content to disk, unless the same content is already there.
+    ///
+    /// Returns the hash of the content.
+    pub fn store(&self, data: &[u8], extension: &str) -> io::Result<String> {
+        let hash: String = UploadStore::hash(data);
+        let path: PathBuf = self.path(&Upload::content_path(&hash, extension));
+        if path.exists() {
+            return Ok(hash)
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // The file is moved into place once it is complete, so a partially written file is never served.
+        let partial: PathBuf = path.with_extension(format!("{}.partial", Uuid::new_v4()));
+        fs::write(&partial, data)?;
+        fs::rename(&partial, &path)
+            .map_err(|e| {
+                let _ = fs::remove_file(&partial);
+                e
+            })?;
+        Ok(hash)
+    }
+
+    /// Makes a thumbnail for the stored image, unless it already has one.
+    ///
+    /// Returns whether the image has a thumbnail.
+    pub fn make_thumbnail(&self, hash: &str, extension: &str) -> bool {
+        let destination: PathBuf = self.path(&Upload::thumbnail_path(hash));
+        if destination.exists() {
+            return true
+        }
+        let source: PathBuf = self.path(&Upload::content_path(hash, extension));
+        match self.thumbnailer.thumbnail(&source, &destination) {
+            Ok(()) => destination.exists(),
+            Err(e) => {
+                warn!("Couldn't make a thumbnail for {}: {}", hash, e);
+                false
+            }
+        }
+    }
+
+    /// Makes the thumbnail on one of the store's worker threads, so the server can carry on handling requests in the meantime.
+    ///
+    /// Resolves to whether the image has a thumbnail.
+    /// Images are left without one if too many are already waiting for theirs.
+    pub fn make_thumbnail_in_background(&self, hash: String, extension: &'static str) -> impl Future<Item = bool, Error = ()> {
+        let (sender, receiver) = oneshot::channel::<bool>();
+        let store: UploadStore = self.clone();
+        let job: ThumbnailJob = Box::new(move || {
+            let _ = sender.send(store.make_thumbnail(&hash, extension));
+        });
+        if self.thumbnail_jobs.try_send(job).is_err() {
+            warn!("Too many images are waiting for thumbnails, so an upload won't have one");
+        }
+        // If the job was dropped or panicked, there is no thumbnail.
+        receiver.or_else(|_| Ok(false))
+    }
+
+    /// Whether the content with the hash has a thumbnail.
+    pub fn has_thumbnail(&self, hash: &str) -> bool {
+        self.path(&Upload::thumbnail_path(hash)).exists()
+    }
+
+    /// Deletes the stored content.
+    pub fn remove_content(&self, hash: &str, extension: &str) -> io::Result<()> {
+        self.remove_if_present(&Upload::content_path(hash, extension))
+    }
+
+    /// Deletes the thumbnail of the content, which is shared by every type the content is stored as.
+    pub fn remove_thumbnail(&self, hash: &str) -> io::Result<()> {
+        // Not every file has a thumbnail.
+        self.remove_if_present(&Upload::thumbnail_path(hash))
+    }
+
+    fn remove_if_present(&self, relative_path: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(relative_path)) {
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result
+        }
+    }
+}
+
+/// Starts the threads that make thumbnails, returning where to send them images.
+fn start_thumbnail_workers() -> SyncSender<ThumbnailJob> {
+    let (sender, receiver) = mpsc::sync_channel::<ThumbnailJob>(THUMBNAIL_QUEUE_SIZE);
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..THUMBNAIL_WORKERS {
+        let receiver = receiver.clone();
+        thread::spawn(move || loop {
+            // The lock is only held while waiting for a job, so the workers make thumbnails side by side.
+            let job: ThumbnailJob = match receiver.lock().map(|receiver| receiver.recv()) {
+                Ok(Ok(job)) => job,
+                // Every store has been dropped.
+                _ => return
+            };
+            // A panicking job shouldn't take the worker down with it.
+            let _ = panic::catch_unwind(AssertUnwindSafe(job));
+        });
+    }
+    sender
+}
+
+pub fn upload_store_filter(store: UploadStore) -> BoxedFilter<(UploadStore,)> {
+    warp::any()
+        .map(move || store.clone())
+        .boxed()
+}
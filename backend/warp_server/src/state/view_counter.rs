@@ -0,0 +1,147 @@
+//! Identifies the viewers of articles, without keeping anything that reveals who they are.
+//!
+//! Viewers are hashed along with a salt that is only held in memory and is replaced every day,
+//! so a viewer's hashes can't be linked across days, or traced back to them once the day is over.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use chrono::NaiveDate;
+use sha1::{Digest, Sha1};
+use uuid::Uuid;
+use warp::filters::BoxedFilter;
+use warp::http::HeaderMap;
+use warp::Filter;
+use identifiers::user::UserUuid;
+
+/// The salt used on a day.
+#[derive(Debug, Clone, Copy)]
+struct DailySalt {
+    date: NaiveDate,
+    salt: Uuid,
+}
+
+/// Hashes viewers with the current day's salt.
+///
+/// Like the RateLimiter, this is held in memory,
+/// so viewers that return after the server restarts are counted again that day.
+#[derive(Debug, Clone, Default)]
+pub struct ViewCounter {
+    salt: Arc<Mutex<Option<DailySalt>>>,
+    /// Whether the server can only be reached through a proxy that sets the client's address in the headers.
+    trusted_proxy: bool,
+}
+
+impl ViewCounter {
+    pub fn new(trusted_proxy: bool) -> Self {
+        ViewCounter {
+            salt: Arc::default(),
+            trusted_proxy,
+        }
+    }
+
+    /// Gets the hash that identifies the viewer for the day.
+    ///
+    /// Signed in users are identified by their account, and everyone else by their address.
+    /// The address is only known when the server is behind a trusted proxy,
+    /// otherwise anyone could send made up addresses to be counted again and again,
+    /// so there is no hash for viewers that aren't signed in.
+    pub fn viewer_hash(&self, user_uuid: Option<UserUuid>, headers: &HeaderMap, today: NaiveDate) -> Option<String> {
+        let viewer: String = match user_uuid {
+            Some(user_uuid) => format!("user:{}", user_uuid),
+            None if self.trusted_proxy => format!("address:{}", client_address(headers)?),
+            None => return None,
+        };
+
+        let salt: Uuid = {
+            let mut salt = self.salt.lock().unwrap();
+            match *salt {
+                Some(daily) if daily.date == today => daily.salt,
+                _ => {
+                    let daily = DailySalt {
+                        date: today,
+                        salt: Uuid::new_v4(),
+                    };
+                    *salt = Some(daily);
+                    daily.salt
+                }
+            }
+        };
+
+        let mut hasher = Sha1::default();
+        hasher.input(salt.as_bytes());
+        hasher.input(viewer.as_bytes());
+        let hash: String = hasher
+            .result()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        Some(hash)
+    }
+}
+
+/// Gets the address of the client from the headers set by the proxy in front of the server.
+///
+/// Warp doesn't expose the address of the connection itself,
+/// so these headers are all there is to go on, and they can only be trusted if they were set by the proxy.
+fn client_address(headers: &HeaderMap) -> Option<String> {
+    let forwarded_for = headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        // The proxy adds the address it was reached from to the end,
+        // and anything before that was sent by the client, which could claim to be anyone.
+        .and_then(|value| value.rsplit(',').next());
+    let real_ip = headers
+        .get("x-real-ip")
+        .and_then(|value| value.to_str().ok());
+    forwarded_for
+        .or(real_ip)
+        .map(|address| address.trim().to_string())
+        .filter(|address| !address.is_empty())
+}
+
+pub fn view_counter_filter(view_counter: ViewCounter) -> BoxedFilter<(ViewCounter,)> {
+    warp::any()
+        .map(move || view_counter.clone())
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn viewers_are_hashed_with_a_daily_salt() {
+        let counter = ViewCounter::new(true);
+        let today = NaiveDate::from_ymd(2026, 10, 18);
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "198.51.100.1, 203.0.113.7".parse().unwrap());
+        let mut spoofed_headers = HeaderMap::new();
+        spoofed_headers.insert("x-forwarded-for", "198.51.100.2, 203.0.113.7".parse().unwrap());
+        let mut other_headers = HeaderMap::new();
+        other_headers.insert("x-real-ip", "203.0.113.8".parse().unwrap());
+
+        let hash: String = counter.viewer_hash(None, &headers, today).expect("Should hash the address");
+        assert_eq!(Some(hash.clone()), counter.viewer_hash(None, &headers, today));
+        assert!(!hash.contains("203.0.113.7"));
+        // Only the address the proxy added counts, not the ones the client made up.
+        assert_eq!(Some(hash.clone()), counter.viewer_hash(None, &spoofed_headers, today));
+        assert_ne!(Some(hash.clone()), counter.viewer_hash(None, &other_headers, today));
+        // Signed in users are the same viewer wherever they are.
+        let user_uuid = UserUuid(Uuid::new_v4());
+        assert_eq!(counter.viewer_hash(Some(user_uuid), &headers, today), counter.viewer_hash(Some(user_uuid), &other_headers, today));
+
+        assert_ne!(Some(hash), counter.viewer_hash(None, &headers, today.succ()));
+        assert_eq!(counter.viewer_hash(None, &HeaderMap::new(), today), None);
+    }
+
+    #[test]
+    fn addresses_are_ignored_without_a_trusted_proxy() {
+        let counter = ViewCounter::default();
+        let today = NaiveDate::from_ymd(2026, 10, 18);
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "203.0.113.7".parse().unwrap());
+
+        assert_eq!(counter.viewer_hash(None, &headers, today), None);
+        assert!(counter.viewer_hash(Some(UserUuid(Uuid::new_v4())), &headers, today).is_some());
+    }
+}
@@ -8,6 +8,7 @@ use serde::Deserialize;
 use uuid::Uuid;
 use std::collections::HashMap;
 use warp::Filter;
+use db::ModeratorView;
 
 /// Util function that makes replying easier
 pub fn convert_and_json<T, U>(source: T) -> impl Reply where
@@ -29,6 +30,35 @@ pub fn convert_vector_and_json<T, U>(source: Vec<T>) -> impl Reply where
 }
 
 
+/// Converts the source to U, using the moderator's view of the source if the viewer is a moderator.
+pub fn convert_for_viewer_and_json<T, U>(source: T, is_moderator: bool) -> impl Reply where
+    U: From<T> + From<ModeratorView<T>>,
+    U: Serialize
+{
+    let target: U = if is_moderator {
+        U::from(ModeratorView(source))
+    } else {
+        U::from(source)
+    };
+    warp::reply::json(&target)
+}
+
+/// Converts a vector of T to a vector of U, using the moderator's view of the sources if the viewer is a moderator.
+pub fn convert_vector_for_viewer_and_json<T, U>(source: Vec<T>, is_moderator: bool) -> impl Reply where
+    U: From<T> + From<ModeratorView<T>>,
+    U: Serialize
+{
+    let target: Vec<U> = source
+        .into_iter()
+        .map(|x| if is_moderator {
+            U::from(ModeratorView(x))
+        } else {
+            U::from(x)
+        })
+        .collect();
+    warp::reply::json(&target)
+}
+
 
 pub fn json_body_filter<T> (kb_limit: u64) -> BoxedFilter<(T,)>
     where
@@ -39,6 +69,17 @@ pub fn json_body_filter<T> (kb_limit: u64) -> BoxedFilter<(T,)>
             .boxed()
 }
 
+/// Deserializes the query string, falling back to the default value if it is absent or can't be deserialized.
+pub fn optional_query_filter<T>() -> BoxedFilter<(T,)>
+    where
+        T: for<'de> Deserialize<'de> + Default + Send + 'static
+{
+    warp::query::query::<T>()
+        .or(warp::any().map(T::default))
+        .unify()
+        .boxed()
+}
+
 pub fn query_uuid(key: &'static str) -> BoxedFilter<(Uuid,)> {
     warp::query::query::<HashMap<String, String>>()
         .and_then(move |hm: HashMap<String,String>| {
@@ -0,0 +1,31 @@
+//! Periodically deletes the hashes of article viewers from earlier days.
+//!
+//! Viewers are only counted once a day, so their hashes are kept to tell them apart until the day ends.
+//! After that, the hashes are made with a different salt, so they are of no use and are purged by this job.
+
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration as StdDuration;
+use chrono::Utc;
+use db::article_view::ArticleDailyViews;
+use error::Error;
+use pool::Pool;
+
+/// How often the hashes from earlier days are purged.
+pub const VIEWER_PURGE_INTERVAL: StdDuration = StdDuration::from_secs(60 * 60);
+
+/// Starts a thread that purges the old viewer hashes every interval for the life of the process.
+pub fn spawn_viewer_purge_job(interval: StdDuration, pool: Pool) -> JoinHandle<()> {
+    info!("Starting viewer purge job, running every {:?}", interval);
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        let result = pool
+            .get()
+            .map_err(|_| Error::DatabaseUnavailable)
+            .and_then(|conn| ArticleDailyViews::purge_old_viewers(Utc::now().naive_utc().date(), &conn));
+        match result {
+            Ok(purged) => info!("Purged {} old viewer hash(es)", purged),
+            Err(e) => error!("Viewer purge job failed: {:?}", e)
+        }
+    })
+}
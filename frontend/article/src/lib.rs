@@ -0,0 +1,158 @@
+extern crate common;
+extern crate identifiers;
+#[macro_use]
+extern crate log;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate util;
+extern crate wire;
+#[macro_use]
+extern crate yew;
+#[macro_use]
+extern crate yew_router;
+
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yew_router::router_agent::RouterSenderBase;
+use common::datatypes::article::ArticleData;
+use common::fetch::Networking;
+use common::fetch::FetchResponse;
+use util::loadable::Loadable;
+use util::markdown::render_markdown;
+use wire::article::FullArticleResponse;
+use requests::ArticleRequest;
+
+mod requests;
+
+/// Displays a single article, identified in the url by its slug.
+pub struct ArticleModel {
+    article: Loadable<ArticleData>,
+    slug: String,
+    networking: Networking,
+    router: RouterSenderBase<()>,
+    link: ComponentLink<ArticleModel>
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct Props {
+    pub slug: String
+}
+
+pub enum Msg {
+    HandleGetArticleResponse(FetchResponse<FullArticleResponse>),
+    NoOp
+}
+
+impl Default for Msg {
+    fn default() -> Self {
+        Msg::NoOp
+    }
+}
+
+impl ArticleModel {
+    fn get_article(&mut self) {
+        info!("Getting Article: {}", self.slug);
+        self.networking.fetch(
+            &ArticleRequest::GetArticleBySlug { slug: self.slug.clone() },
+            Msg::HandleGetArticleResponse,
+            &self.link
+        );
+    }
+}
+
+impl Component for ArticleModel {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let cb = link.send_back(|_| Msg::NoOp);
+        let mut article = ArticleModel {
+            article: Loadable::default(),
+            slug: props.slug,
+            networking: Networking::new(&link),
+            router: RouterSenderBase::<()>::new(cb),
+            link
+        };
+        article.get_article();
+        article
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::HandleGetArticleResponse(response) => {
+                let response = response.map(ArticleData::from);
+                if let FetchResponse::Success(ref article) = response {
+                    // The server follows old slugs to the current article,
+                    // so the url is updated to point at its current slug.
+                    if article.slug != self.slug {
+                        self.slug = article.slug.clone();
+                        self.router.send(RouterRequest::ChangeRoute(route!("article/{}", article.slug)));
+                    }
+                }
+                self.article = Loadable::from_fetch_response(response);
+                true
+            }
+            Msg::NoOp => false
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.slug != props.slug {
+            self.slug = props.slug;
+            self.get_article();
+        }
+        false
+    }
+}
+
+impl Renderable<ArticleModel> for ArticleModel {
+    fn view(&self) -> Html<ArticleModel> {
+        fn article_view(article: &ArticleData) -> Html<ArticleModel> {
+            html! {
+                <div class="article",>
+                    <h1>{&article.title}</h1>
+                    <div class="article-author",>{&article.author.display_name}</div>
+                    <div class="article-tags",>
+                        { for article.tags.iter().map(|tag| html! { <span class=("badge", "badge-secondary"),>{tag}</span> }) }
+                    </div>
+                    <div>
+                        {render_markdown::<ArticleModel>(&article.body)}
+                    </div>
+                    <div class="article-comments",>{format!("{} comments", article.comment_count)}</div>
+                </div>
+            }
+        }
+
+        html! {
+            <div class=("full-height", "scrollable"),>
+                {self.article.default_view(article_view)}
+            </div>
+        }
+    }
+}
+
+impl Routable for ArticleModel {
+    fn resolve_props(route: &Route) -> Option<<Self as Component>::Properties> {
+        // /article/<slug>
+        if let Some(seg_1) = route.path_segments.get(0) {
+            if seg_1.as_str() == "article" {
+                route.path_segments
+                    .get(1)
+                    .map(|slug| Props { slug: slug.clone() })
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+
+    fn will_try_to_route(route: &Route) -> bool {
+        if let Some(seg_1) = route.path_segments.get(0) {
+            seg_1.as_str() == "article" && route.path_segments.get(1).is_some()
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use common::fetch::Auth;
+use common::fetch::FetchRequest;
+use common::fetch::HttpMethod;
+
+#[derive(Serialize, Deserialize)]
+pub enum ArticleRequest {
+    GetArticleBySlug { slug: String },
+}
+
+impl FetchRequest for ArticleRequest {
+    fn resolve_path(&self) -> String {
+        use self::ArticleRequest::*;
+        match *self {
+            GetArticleBySlug { ref slug } => format!("article/slug/{}", slug),
+        }
+    }
+    fn resolve_auth(&self) -> Auth {
+        use self::ArticleRequest::*;
+        use self::Auth::*;
+        match *self {
+            GetArticleBySlug {..} => NotRequired,
+        }
+    }
+    fn resolve_body_and_method(&self) -> HttpMethod {
+        use self::ArticleRequest::*;
+        use self::HttpMethod::*;
+        match self {
+            GetArticleBySlug {..} => Get,
+        }
+    }
+}
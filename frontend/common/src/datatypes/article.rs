@@ -0,0 +1,35 @@
+use wire::article::FullArticleResponse;
+use datatypes::user::UserData;
+use chrono::NaiveDateTime;
+use identifiers::article::ArticleUuid;
+use identifiers::thread::ThreadUuid;
+
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ArticleData {
+    pub uuid: ArticleUuid,
+    pub author: UserData,
+    pub title: String,
+    pub slug: String,
+    pub body: String,
+    pub tags: Vec<String>,
+    pub publish_date: Option<NaiveDateTime>,
+    pub comment_thread_uuid: Option<ThreadUuid>,
+    pub comment_count: i64,
+}
+
+impl From<FullArticleResponse> for ArticleData {
+    fn from(response: FullArticleResponse) -> Self {
+        ArticleData {
+            uuid: response.id,
+            author: UserData::from(response.author),
+            title: response.title,
+            slug: response.slug,
+            body: response.body,
+            tags: response.tags,
+            publish_date: response.publish_date,
+            comment_thread_uuid: response.comment_thread_uuid,
+            comment_count: response.comment_count,
+        }
+    }
+}
@@ -1,5 +1,10 @@
 use wire::forum::ForumResponse;
+use wire::forum::ForumSearchResultResponse;
 use identifiers::forum::ForumUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::post::PostUuid;
+use datatypes::user::UserData;
+use chrono::NaiveDateTime;
 
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct ForumData {
@@ -18,3 +23,31 @@ impl From<ForumResponse> for ForumData {
     }
 }
 
+
+/// A post that matched a forum search.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForumSearchResultData {
+    pub forum_uuid: ForumUuid,
+    pub thread_uuid: ThreadUuid,
+    pub post_uuid: PostUuid,
+    /// The thread's title, with matches delimited by `<mark>` tags.
+    pub title: String,
+    /// An excerpt of the post, with matches delimited by `<mark>` tags.
+    pub snippet: String,
+    pub author: UserData,
+    pub created_date: NaiveDateTime,
+}
+
+impl From<ForumSearchResultResponse> for ForumSearchResultData {
+    fn from(response: ForumSearchResultResponse) -> Self {
+        ForumSearchResultData {
+            forum_uuid: response.forum_uuid,
+            thread_uuid: response.thread_uuid,
+            post_uuid: response.post_uuid,
+            title: response.title,
+            snippet: response.snippet,
+            author: response.author.into(),
+            created_date: response.created_date,
+        }
+    }
+}
@@ -6,3 +6,4 @@ pub mod bucket;
 
 pub mod answer;
 pub mod question;
+pub mod article;
@@ -3,12 +3,14 @@
 use wire::thread::MinimalThreadResponse;
 use wire::thread::NewThreadRequest;
 use wire::thread::ThreadResponse;
+use wire::poll::PollResponse;
 use datatypes::user::UserData;
 use datatypes::post::PostData;
 use chrono::NaiveDateTime;
 use identifiers::thread::ThreadUuid;
 use identifiers::forum::ForumUuid;
 use identifiers::user::UserUuid;
+use identifiers::post::PostUuid;
 
 
 
@@ -20,6 +22,13 @@ pub struct MinimalThreadData {
     pub created_date: NaiveDateTime,
     //    pub replies: i32,
     pub locked: bool,
+    pub pinned: bool,
+    pub last_post_date: NaiveDateTime,
+    pub last_post_author: Option<UserData>,
+    pub reply_count: i32,
+    /// Only known when the threads were fetched by a logged in user.
+    pub unread_count: Option<i64>,
+    pub first_unread_post_uuid: Option<PostUuid>,
 }
 
 impl Default for MinimalThreadData {
@@ -30,6 +39,12 @@ impl Default for MinimalThreadData {
             author: UserData::default(),
             created_date: NaiveDateTime::from_timestamp(0,0),
             locked: bool::default(),
+            pinned: bool::default(),
+            last_post_date: NaiveDateTime::from_timestamp(0,0),
+            last_post_author: None,
+            reply_count: 0,
+            unread_count: None,
+            first_unread_post_uuid: None,
         }
     }
 }
@@ -42,6 +57,12 @@ impl From<MinimalThreadResponse> for MinimalThreadData {
             author: response.author.into(),
             created_date: response.created_date,
             locked: response.locked,
+            pinned: response.pinned_position.is_some(),
+            last_post_date: response.last_post_date,
+            last_post_author: response.last_post_author.map(UserData::from),
+            reply_count: response.reply_count,
+            unread_count: response.unread_count,
+            first_unread_post_uuid: response.first_unread_post_uuid,
         }
     }
 }
@@ -59,6 +80,7 @@ impl NewThreadData {
             author_uuid: user_uuid,
             title: self.title.clone(),
             post_content: self.post_content.clone(),
+            poll: None,
         }
     }
 }
@@ -72,6 +94,7 @@ pub struct ThreadData {
     pub posts: PostData,
     pub created_date: NaiveDateTime,
     pub locked: bool,
+    pub poll: Option<PollResponse>,
 }
 
 impl Default for ThreadData {
@@ -83,7 +106,8 @@ impl Default for ThreadData {
             author: UserData::default(),
             posts: PostData::default(),
             created_date: NaiveDateTime::from_timestamp(0,0),
-            locked: bool::default()
+            locked: bool::default(),
+            poll: None,
         }
     }
 }
@@ -97,7 +121,8 @@ impl From<ThreadResponse> for ThreadData {
             author: UserData::from(response.author),
             posts: PostData::from(response.posts),
             created_date: response.created_date,
-            locked: response.locked
+            locked: response.locked,
+            poll: response.poll,
         }
     }
 }
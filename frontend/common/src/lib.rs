@@ -23,4 +23,6 @@ pub mod user;
 
 pub mod datatypes;
 
-//pub mod notification;
+pub mod notification;
+
+pub mod upload;
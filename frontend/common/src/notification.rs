@@ -1,14 +1,70 @@
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use yew::prelude::worker::*;
+use wire::notification::NotificationResponse;
+use wire::notification::NotificationKind;
+use identifiers::notification::NotificationUuid;
+use identifiers::forum::ForumUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::post::PostUuid;
 
 pub enum Msg {
 
 }
 
+/// Where a forum notification points to, and who caused it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ForumNotification {
+    pub uuid: NotificationUuid,
+    pub forum_uuid: ForumUuid,
+    pub thread_uuid: ThreadUuid,
+    pub post_uuid: PostUuid,
+    pub thread_title: String,
+    /// The start of the post, without any formatting.
+    pub excerpt: String,
+    /// The display name of the user who wrote the post.
+    pub actor_name: String,
+    pub read: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Notification {
+    /// Someone replied to one of the user's posts.
+    ForumReply(ForumNotification),
+    /// Someone mentioned the user in a post.
+    ForumMention(ForumNotification),
+    /// Someone posted in a thread or forum the user subscribed to.
+    ForumSubscription(ForumNotification),
+}
 
+impl Notification {
+    /// The notification's details, whatever caused it.
+    pub fn forum_notification(&self) -> &ForumNotification {
+        match self {
+            Notification::ForumReply(n) | Notification::ForumMention(n) | Notification::ForumSubscription(n) => n,
+        }
+    }
+}
+
+impl From<NotificationResponse> for Notification {
+    fn from(response: NotificationResponse) -> Self {
+        let kind = response.kind;
+        let forum_notification = ForumNotification {
+            uuid: response.uuid,
+            forum_uuid: response.forum_uuid,
+            thread_uuid: response.thread_uuid,
+            post_uuid: response.post_uuid,
+            thread_title: response.thread_title,
+            excerpt: response.excerpt,
+            actor_name: response.actor.display_name,
+            read: response.read,
+        };
+        match kind {
+            NotificationKind::Reply => Notification::ForumReply(forum_notification),
+            NotificationKind::Mention => Notification::ForumMention(forum_notification),
+            NotificationKind::Subscription => Notification::ForumSubscription(forum_notification),
+        }
+    }
 }
 
 pub struct NotificationSink {
@@ -68,10 +124,10 @@ impl Agent for NotificationRepository {
     }
 
     fn handle(&mut self, notification: Self::Input, who: HandlerId) {
-        self.history.push_back(notification);
         for sub in self.subscribers.iter() {
             self.link.response(*sub, notification.clone());
         }
+        self.history.push_back(notification);
     }
 
     fn connected(&mut self, id: HandlerId) {
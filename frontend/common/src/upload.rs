@@ -0,0 +1,121 @@
+//! Sends files to the server to be stored.
+//!
+//! The fetch service can only send text, so the browser builds and sends the multipart form itself.
+use stdweb::Value;
+use yew::callback::Callback;
+use yew::services::storage::{StorageService, Area};
+use serde_json;
+use wire::upload::UploadResponse;
+use wire::user::BEARER;
+use fetch::{FetchRequest, FetchResponse, FetchError, Auth, HttpMethod};
+use super::user;
+
+#[derive(Serialize, Deserialize)]
+/// Uploads the file sent along with the request.
+struct Upload;
+
+impl FetchRequest for Upload {
+    fn resolve_path(&self) -> String {
+        "/upload".into()
+    }
+
+    fn resolve_auth(&self) -> Auth {
+        Auth::Required
+    }
+
+    fn resolve_body_and_method(&self) -> HttpMethod {
+        HttpMethod::Post(String::new())
+    }
+}
+
+pub struct UploadService {
+    storage_service: StorageService,
+}
+
+/// The upload being sent. It is canceled if this is dropped before it finishes.
+pub struct UploadTask(Option<Value>);
+
+impl UploadService {
+    pub fn new() -> UploadService {
+        UploadService {
+            storage_service: StorageService::new(Area::Local),
+        }
+    }
+
+    /// Uploads the file chosen in the file input with the id.
+    ///
+    /// Returns None without calling the callback if no file has been chosen.
+    pub fn upload(&mut self, input_id: &str, callback: Callback<FetchResponse<UploadResponse>>) -> Option<UploadTask> {
+        let token: String = match user::get_token_if_valid(&mut self.storage_service) {
+            Some(token) => token,
+            None => {
+                callback.emit(FetchResponse::Error(FetchError::AuthAbsent));
+                return None
+            }
+        };
+        let authorization: String = format!("{} {}", BEARER, token);
+        let url: String = Upload.resolve_url();
+
+        let callback_for_js = callback.clone();
+        let on_done = move |status: u16, body: String| {
+            callback_for_js.emit(to_response(status, body));
+        };
+        let handle: Value = js! {
+            var input = document.getElementById(@{input_id});
+            var on_done = @{on_done};
+            if (input === null || input.files.length === 0) {
+                on_done.drop();
+                return null;
+            }
+            var form = new FormData();
+            form.append("file", input.files[0]);
+            input.value = "";
+
+            var request = new XMLHttpRequest();
+            request.open("POST", @{url});
+            request.setRequestHeader("Authorization", @{authorization});
+            request.onload = function() {
+                on_done(request.status, request.responseText);
+                on_done.drop();
+            };
+            request.onerror = function() {
+                on_done(0, "");
+                on_done.drop();
+            };
+            request.onabort = function() {
+                on_done.drop();
+            };
+            request.send(form);
+            return request;
+        };
+        if handle.is_null() {
+            return None
+        }
+        callback.emit(FetchResponse::Started);
+        Some(UploadTask(Some(handle)))
+    }
+}
+
+impl Drop for UploadTask {
+    fn drop(&mut self) {
+        let handle = self.0.take().expect("Upload task already empty.");
+        // Finished requests ignore being aborted.
+        js! {
+            @(no_return)
+            var request = @{handle};
+            request.abort();
+        }
+    }
+}
+
+fn to_response(status: u16, body: String) -> FetchResponse<UploadResponse> {
+    match status {
+        200 ... 299 => serde_json::from_str(&body)
+            .map(FetchResponse::Success)
+            .unwrap_or_else(|_| FetchResponse::Error(FetchError::DecodeError(body))),
+        400 => FetchResponse::Error(FetchError::BadRequest),
+        401 => FetchResponse::Error(FetchError::Unauthorized),
+        403 => FetchResponse::Error(FetchError::Forbidden),
+        _ => FetchResponse::Error(FetchError::Misc)
+    }
+}
@@ -31,12 +31,16 @@ mod title;
 mod forums_list;
 mod threads_list;
 mod thread;
+mod search;
+mod notifications;
 
 use title::{ForumTitle, ForumsTitle};
 use forums_list::ForumsList;
 use threads_list::ThreadsList;
 use thread::Thread;
 use new_forum::NewForum;
+use search::ForumSearch;
+use notifications::ForumNotifications;
 //pub mod forum_list;
 //mod forum;
 
@@ -76,6 +80,12 @@ impl Renderable<ForumModel> for ForumModel {
                     <div class=("title-bar", "flexbox-center-vert"),> // Title bar
                         <YewRouter: routes=routes![ForumTitle, ForumsTitle], />
                     </div>
+                    <div class="flexbox-center-vert",>
+                        <ForumNotifications: />
+                    </div>
+                    <div class="flexbox-center-vert",>
+                        <ForumSearch: />
+                    </div>
                 </div>
                 <div class=("flexbox-horiz", "full-height", "no-scroll"), > // Horizontal container
                     <div class=("vertical-expand", "list-background", "forum-list-width", "scrollable"),> // Vertical - list container
@@ -0,0 +1,214 @@
+use yew::prelude::*;
+use yew::services::storage::{Area, StorageService};
+use yew_router::components::RouterLink;
+use common::fetch::Networking;
+use common::fetch::FetchResponse;
+use common::notification::{Notification, ForumNotification};
+use util::button::Button;
+use util::loadable::Loadable;
+use requests::ForumRequest;
+use identifiers::notification::NotificationUuid;
+use wire::notification::NotificationResponse;
+
+/// A button showing how many of the user's notifications are unread, that lists them below it when opened.
+pub struct ForumNotifications {
+    notifications: Loadable<Vec<Notification>>,
+    is_open: bool,
+    networking: Networking,
+    link: ComponentLink<ForumNotifications>
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct ForumNotificationsProps;
+
+pub enum Msg {
+    Toggle,
+    MarkRead(NotificationUuid),
+    MarkAllRead,
+    HandleGetNotificationsResponse(FetchResponse<Vec<Notification>>),
+    HandleMarkReadResponse(FetchResponse<Notification>),
+    NoOp
+}
+
+impl Default for Msg {
+    fn default() -> Self {
+        Msg::NoOp
+    }
+}
+
+impl ForumNotifications {
+    fn unread_count(&self) -> usize {
+        self.notifications
+            .as_option()
+            .map(|notifications| {
+                notifications
+                    .iter()
+                    .filter(|x| !x.forum_notification().read)
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+}
+
+impl Component for ForumNotifications {
+    type Message = Msg;
+    type Properties = ForumNotificationsProps;
+
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut notifications = ForumNotifications {
+            notifications: Loadable::default(),
+            is_open: false,
+            networking: Networking::new(&link),
+            link
+        };
+
+        // Users that aren't logged in don't have notifications to show.
+        let mut storage_service = StorageService::new(Area::Local);
+        if ::common::user::is_logged_in(&mut storage_service) {
+            notifications.networking.fetch(
+                &ForumRequest::GetNotifications,
+                |r: FetchResponse<Vec<NotificationResponse>>| Msg::HandleGetNotificationsResponse(r.map(
+                    |x: Vec<NotificationResponse>| {
+                        x.into_iter()
+                            .map(Notification::from)
+                            .collect()
+                    }
+                )),
+                &notifications.link
+            );
+        }
+
+        notifications
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Toggle => {
+                self.is_open = !self.is_open;
+                true
+            }
+            Msg::MarkRead(notification_uuid) => {
+                self.is_open = false;
+                self.networking.fetch(
+                    &ForumRequest::MarkNotificationRead { notification_uuid },
+                    |r: FetchResponse<NotificationResponse>| Msg::HandleMarkReadResponse(r.map(Notification::from)),
+                    &self.link
+                );
+                true
+            }
+            Msg::MarkAllRead => {
+                self.networking.fetch(
+                    &ForumRequest::MarkAllNotificationsRead,
+                    |r: FetchResponse<Vec<NotificationResponse>>| Msg::HandleGetNotificationsResponse(r.map(
+                        |x: Vec<NotificationResponse>| {
+                            x.into_iter()
+                                .map(Notification::from)
+                                .collect()
+                        }
+                    )),
+                    &self.link
+                );
+                false
+            }
+            Msg::HandleGetNotificationsResponse(response) => {
+                match response {
+                    // Keep showing the current notifications while they are being marked as read.
+                    FetchResponse::Started if self.notifications.as_option().is_some() => false,
+                    response => {
+                        self.notifications = Loadable::from_fetch_response(response);
+                        true
+                    }
+                }
+            }
+            Msg::HandleMarkReadResponse(response) => {
+                if let FetchResponse::Success(marked) = response {
+                    if let Some(notifications) = self.notifications.as_mut_option() {
+                        let marked_uuid = marked.forum_notification().uuid;
+                        for notification in notifications.iter_mut() {
+                            if notification.forum_notification().uuid == marked_uuid {
+                                *notification = marked.clone();
+                            }
+                        }
+                    }
+                    true
+                } else {
+                    false
+                }
+            }
+            Msg::NoOp => false
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+}
+
+impl Renderable<ForumNotifications> for ForumNotifications {
+    fn view(&self) -> Html<ForumNotifications> {
+        let notifications = match self.notifications.as_option() {
+            Some(notifications) => notifications,
+            None => return html! { <></> }
+        };
+        let unread_count: usize = self.unread_count();
+        let all_read: bool = unread_count == 0;
+        let title: String = if unread_count > 0 {
+            format!("Notifications ({})", unread_count)
+        } else {
+            "Notifications".to_string()
+        };
+
+        let list = if !self.is_open {
+            html! { <></> }
+        } else if notifications.is_empty() {
+            html! {
+                <div class="search-results",>
+                    {"No notifications"}
+                </div>
+            }
+        } else {
+            html! {
+                <div class="search-results",>
+                    <Button: title="Mark all read", disabled=all_read, onclick=|_| Msg::MarkAllRead, />
+                    <ul class="forum-list",>
+                        { for notifications.iter().map(Notification::view) }
+                    </ul>
+                </div>
+            }
+        };
+
+        html! {
+            <div class="forum-search",>
+                <Button: title=title, onclick=|_| Msg::Toggle, />
+                {list}
+            </div>
+        }
+    }
+}
+
+impl Renderable<ForumNotifications> for Notification {
+    fn view(&self) -> Html<ForumNotifications> {
+        let description: String = match self {
+            Notification::ForumReply(n) => format!("{} replied to you in {}", n.actor_name, n.thread_title),
+            Notification::ForumMention(n) => format!("{} mentioned you in {}", n.actor_name, n.thread_title),
+            Notification::ForumSubscription(n) => format!("{} posted in {}", n.actor_name, n.thread_title),
+        };
+        let notification: &ForumNotification = self.forum_notification();
+        let notification_uuid: NotificationUuid = notification.uuid;
+        let class = if notification.read {
+            "forum-list-element"
+        } else {
+            "forum-list-element notification-unread"
+        };
+        html! {
+            <li class=class, onclick=move |_| Msg::MarkRead(notification_uuid),>
+                <div>
+                    <RouterLink: text=description, route=route!("forum/{}/{}#{}", notification.forum_uuid, notification.thread_uuid, notification.post_uuid), />
+                </div>
+                <div>
+                    {&notification.excerpt}
+                </div>
+            </li>
+        }
+    }
+}
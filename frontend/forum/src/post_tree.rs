@@ -272,15 +272,16 @@ impl Renderable<PostTree> for PostTree {
             }
         }
 
+        // The id lets links to the post, like search results, scroll to it.
         html! {
             <div class=("post-left-pad"),>
-                <div class=("post-card", "flexbox-vert"),>
+                <div class=("post-card", "flexbox-vert"), id=self.post.uuid.to_string(),>
                     <div class=("post-content"),>
                         { edit_area_view(&self.edit_instance, &self.post.content) }
                     </div>
                     <div class=("post-info","flexbox-horiz"),>
                         <div>
-                            {"By "}{&self.post.author.display_name}
+                            {"By "}{&self.post.author.display_name}{format!(" ({})", self.post.author.reputation)}
                         </div>
                         <div>
                             {reply_button_fn(self)}
@@ -4,9 +4,13 @@ use common::fetch::HttpMethod;
 use common::fetch::to_body;
 use identifiers::forum::ForumUuid;
 use identifiers::thread::ThreadUuid;
+use identifiers::notification::NotificationUuid;
+use identifiers::poll::PollUuid;
+use wire::poll::PollVoteRequest;
 use wire::post::*;
 use wire::thread::*;
 use wire::forum::NewForumRequest;
+use wire::forum::ForumSearchQuery;
 
 #[derive(Serialize, Deserialize)]
 pub enum ForumRequest {
@@ -18,6 +22,12 @@ pub enum ForumRequest {
     GetThread { thread_uuid: ThreadUuid },
     CreatePostResponse(NewPostRequest),
     UpdatePost(EditPostRequest),
+    Search(ForumSearchQuery),
+    GetNotifications,
+    MarkNotificationRead { notification_uuid: NotificationUuid },
+    MarkAllNotificationsRead,
+    VotePoll { poll_uuid: PollUuid, vote: PollVoteRequest },
+    RetractPollVote { poll_uuid: PollUuid },
 }
 
 impl FetchRequest for ForumRequest {
@@ -29,12 +39,18 @@ impl FetchRequest for ForumRequest {
             GetThreads {
                 forum_uuid,
                 page_index,
-            } => format!("thread/get/{}/{}", forum_uuid, page_index),
+            } => format!("thread/get/{}/{}?order=last_activity", forum_uuid, page_index),
             GetForums => "forum/forums".into(),
             GetForum { forum_uuid } => format!("forum/{}", forum_uuid),
             GetThread { thread_uuid } => format!("thread/{}", thread_uuid),
             CreatePostResponse(_) => "post/create".into(),
             UpdatePost(_) => "post/edit".into(),
+            Search(ref query) => search_path(query),
+            GetNotifications => "notification".into(),
+            MarkNotificationRead { notification_uuid } => format!("notification/read/{}", notification_uuid),
+            MarkAllNotificationsRead => "notification/read".into(),
+            VotePoll { poll_uuid, .. } => format!("poll/vote/{}", poll_uuid),
+            RetractPollVote { poll_uuid } => format!("poll/vote/{}", poll_uuid),
         }
     }
     fn resolve_auth(&self) -> Auth {
@@ -49,6 +65,12 @@ impl FetchRequest for ForumRequest {
             GetThread {..} => NotRequired,
             CreatePostResponse(_) => Required,
             UpdatePost(_) => Required,
+            Search(_) => NotRequired,
+            GetNotifications => Required,
+            MarkNotificationRead {..} => Required,
+            MarkAllNotificationsRead => Required,
+            VotePoll {..} => Required,
+            RetractPollVote {..} => Required,
         }
 
     }
@@ -64,6 +86,44 @@ impl FetchRequest for ForumRequest {
             GetThread {..} => Get,
             CreatePostResponse(r) => Post(to_body(r)),
             UpdatePost(r) => Put(to_body(r)),
+            Search(_) => Get,
+            GetNotifications => Get,
+            MarkNotificationRead {..} => Put(String::new()),
+            MarkAllNotificationsRead => Put(String::new()),
+            VotePoll { vote, .. } => Put(to_body(vote)),
+            RetractPollVote {..} => Delete,
         }
     }
+}
+
+/// Builds the search path, with the query's populated fields as query parameters.
+fn search_path(query: &ForumSearchQuery) -> String {
+    let mut path = format!("forum/search?q={}", encode_query_value(&query.q));
+    if let Some(forum) = query.forum {
+        path.push_str(&format!("&forum={}", forum));
+    }
+    if let Some(author) = query.author {
+        path.push_str(&format!("&author={}", author));
+    }
+    if let Some(before) = query.before {
+        path.push_str(&format!("&before={}", before.format("%Y-%m-%dT%H:%M:%S")));
+    }
+    if let Some(after) = query.after {
+        path.push_str(&format!("&after={}", after.format("%Y-%m-%dT%H:%M:%S")));
+    }
+    if let Some(page) = query.page {
+        path.push_str(&format!("&page={}", page));
+    }
+    path
+}
+
+/// Percent-encodes everything but unreserved characters.
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
 }
\ No newline at end of file
@@ -0,0 +1,194 @@
+use yew::prelude::*;
+use yew_router::components::RouterLink;
+use common::fetch::Networking;
+use common::fetch::FetchResponse;
+use util::loadable::Loadable;
+use util::input::Input;
+use util::input::InputState;
+use datatypes::forum::ForumSearchResultData;
+use requests::ForumRequest;
+use wire::forum::ForumSearchQuery;
+use wire::forum::ForumSearchResultResponse;
+
+const HIGHLIGHT_START: &str = "<mark>";
+const HIGHLIGHT_END: &str = "</mark>";
+
+/// A search box that shows the posts matching its query below it.
+pub struct ForumSearch {
+    query: InputState,
+    results: Loadable<Vec<ForumSearchResultData>>,
+    networking: Networking,
+    link: ComponentLink<ForumSearch>
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct ForumSearchProps;
+
+pub enum Msg {
+    UpdateQuery(InputState),
+    Search,
+    HandleSearchResponse(FetchResponse<Vec<ForumSearchResultData>>),
+    ClearResults,
+    NoOp
+}
+
+impl Default for Msg {
+    fn default() -> Self {
+        Msg::NoOp
+    }
+}
+
+impl ForumSearch {
+    fn search(&mut self) {
+        let query = ForumSearchQuery {
+            q: self.query.inner_text(),
+            forum: None,
+            author: None,
+            before: None,
+            after: None,
+            page: None,
+        };
+        self.networking.fetch(
+            &ForumRequest::Search(query),
+            |r: FetchResponse<Vec<ForumSearchResultResponse>>| Msg::HandleSearchResponse(r.map(
+                |x: Vec<ForumSearchResultResponse>| {
+                    x.into_iter()
+                        .map(ForumSearchResultData::from)
+                        .collect()
+                }
+            )),
+            &self.link
+        );
+    }
+}
+
+impl Component for ForumSearch {
+    type Message = Msg;
+    type Properties = ForumSearchProps;
+
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        ForumSearch {
+            query: InputState::default(),
+            results: Loadable::default(),
+            networking: Networking::new(&link),
+            link
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::UpdateQuery(query) => {
+                self.query = query;
+                true
+            }
+            Msg::Search => {
+                if self.query.inner_text().trim().is_empty() {
+                    self.results = Loadable::Unloaded;
+                } else {
+                    self.search();
+                }
+                true
+            }
+            Msg::HandleSearchResponse(response) => {
+                self.results = Loadable::from_fetch_response(response);
+                true
+            }
+            Msg::ClearResults => {
+                self.query = InputState::default();
+                self.results = Loadable::Unloaded;
+                true
+            }
+            Msg::NoOp => false
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+}
+
+impl Renderable<ForumSearch> for ForumSearch {
+    fn view(&self) -> Html<ForumSearch> {
+        fn results_fn(results: &Vec<ForumSearchResultData>) -> Html<ForumSearch> {
+            if results.is_empty() {
+                html! {
+                    <div class="search-results",>
+                        {"No results"}
+                    </div>
+                }
+            } else {
+                html! {
+                    <ul class=("forum-list", "search-results"),>
+                        { for results.iter().map(ForumSearchResultData::view) }
+                    </ul>
+                }
+            }
+        }
+        html! {
+            <div class="forum-search",>
+                <Input:
+                    input_state=&self.query,
+                    placeholder="Search",
+                    on_change=Msg::UpdateQuery,
+                    on_enter=|_| Msg::Search,
+                />
+                {self.results.small_view(results_fn)}
+            </div>
+        }
+    }
+}
+
+impl Renderable<ForumSearch> for ForumSearchResultData {
+    fn view(&self) -> Html<ForumSearch> {
+        let title: String = self.title
+            .replace(HIGHLIGHT_START, "")
+            .replace(HIGHLIGHT_END, "");
+        html! {
+            <li class="forum-list-element", onclick=|_| Msg::ClearResults,>
+                <div>
+                    <RouterLink: text=title, route=route!("forum/{}/{}#{}", self.forum_uuid, self.thread_uuid, self.post_uuid), />
+                </div>
+                <div>
+                    { for highlighted_segments(&self.snippet).into_iter().map(segment_view) }
+                </div>
+                <div>
+                    {&self.author.display_name}
+                </div>
+            </li>
+        }
+    }
+}
+
+fn segment_view((text, highlighted): (String, bool)) -> Html<ForumSearch> {
+    if highlighted {
+        html! {
+            <span class="search-highlight",>{text}</span>
+        }
+    } else {
+        html! {
+            <span>{text}</span>
+        }
+    }
+}
+
+/// Splits text on the highlight tags added by the search, so the matches can be rendered
+/// without interpreting the rest of the text as markup.
+fn highlighted_segments(text: &str) -> Vec<(String, bool)> {
+    let mut segments = vec![];
+    let mut rest: &str = text;
+    while let Some(start) = rest.find(HIGHLIGHT_START) {
+        let (before, after_start) = rest.split_at(start);
+        let after_start = &after_start[HIGHLIGHT_START.len()..];
+        let end = after_start.find(HIGHLIGHT_END).unwrap_or_else(|| after_start.len());
+        let (highlighted, after_end) = after_start.split_at(end);
+        segments.push((before.to_string(), false));
+        segments.push((highlighted.to_string(), true));
+        rest = if after_end.starts_with(HIGHLIGHT_END) {
+            &after_end[HIGHLIGHT_END.len()..]
+        } else {
+            after_end
+        };
+    }
+    segments.push((rest.to_string(), false));
+    segments
+}
@@ -11,9 +11,15 @@ use common::fetch::FetchResponse;
 use requests::ForumRequest;
 use yew::services::storage::{StorageService, Area};
 use wire::thread::ThreadResponse;
+use wire::poll::PollResponse;
+use wire::poll::PollOptionResponse;
+use wire::poll::PollVoteRequest;
+use identifiers::poll_option::PollOptionUuid;
+use util::button::Button;
 
 pub struct Thread {
     posts: Loadable<PostTreeProps>,
+    poll: Option<PollResponse>,
     thread_uuid: ThreadUuid,
     user_uuid: Option<UserUuid>,
     networking: Networking,
@@ -25,7 +31,10 @@ pub struct ThreadProps {
 }
 
 pub enum Msg {
-    HandleGetThreadResponse(FetchResponse<PostData>),
+    HandleGetThreadResponse(FetchResponse<ThreadResponse>),
+    Vote(PollOptionUuid),
+    RetractVote,
+    HandlePollResponse(FetchResponse<PollResponse>),
     // TODO, should handle log out to remove the user uuid.2
     NoOp
 }
@@ -41,10 +50,37 @@ impl Thread {
         let thread_uuid: ThreadUuid = self.thread_uuid;
         self.networking.fetch(
             &ForumRequest::GetThread{thread_uuid},
-            |r: FetchResponse<ThreadResponse>| Msg::HandleGetThreadResponse(r.map(|x| PostData::from(x.posts))), // TODO, I throw away a lot of info here
+            Msg::HandleGetThreadResponse,
             &self.link
         );
     }
+
+    /// Votes for the option.
+    /// Multiple choice polls keep the user's other votes, so clicking an option toggles it.
+    fn vote(&mut self, option_uuid: PollOptionUuid) {
+        if let Some(ref poll) = self.poll {
+            let mut option_uuids: Vec<PollOptionUuid> = if poll.multiple_choice {
+                poll.options
+                    .iter()
+                    .filter(|option| option.voted && option.uuid != option_uuid)
+                    .map(|option| option.uuid)
+                    .collect()
+            } else {
+                vec![]
+            };
+            let already_voted: bool = poll.options.iter().any(|option| option.uuid == option_uuid && option.voted);
+            if !(poll.multiple_choice && already_voted) {
+                option_uuids.push(option_uuid);
+            }
+
+            let request = if option_uuids.is_empty() {
+                ForumRequest::RetractPollVote { poll_uuid: poll.uuid }
+            } else {
+                ForumRequest::VotePoll { poll_uuid: poll.uuid, vote: PollVoteRequest { option_uuids } }
+            };
+            self.networking.fetch(&request, Msg::HandlePollResponse, &self.link);
+        }
+    }
 }
 
 impl Component for Thread {
@@ -56,6 +92,7 @@ impl Component for Thread {
 
         let mut thread = Thread {
             posts: Loadable::default(),
+            poll: None,
             thread_uuid: props.thread_uuid,
             user_uuid: ::common::user::user_id(&mut storage_service).ok(),
             networking: Networking::new(&link),
@@ -69,9 +106,12 @@ impl Component for Thread {
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::HandleGetThreadResponse(response) => {
-                let response = response.map(|post_data| {
+                if let FetchResponse::Success(ref thread) = response {
+                    self.poll = thread.poll.clone();
+                }
+                let response = response.map(|thread| {
                     PostTreeProps {
-                        post: post_data,
+                        post: PostData::from(thread.posts),
                         thread_uuid: self.thread_uuid,
                         user_uuid: self.user_uuid
                     }
@@ -79,6 +119,24 @@ impl Component for Thread {
                 self.posts = Loadable::from_fetch_response(response);
                 true
             }
+            Msg::Vote(option_uuid) => {
+                self.vote(option_uuid);
+                false
+            }
+            Msg::RetractVote => {
+                if let Some(ref poll) = self.poll {
+                    self.networking.fetch(&ForumRequest::RetractPollVote { poll_uuid: poll.uuid }, Msg::HandlePollResponse, &self.link);
+                }
+                false
+            }
+            Msg::HandlePollResponse(response) => {
+                if let FetchResponse::Success(poll) = response {
+                    self.poll = Some(poll);
+                    true
+                } else {
+                    false
+                }
+            }
             Msg::NoOp => false
         }
     }
@@ -103,7 +161,69 @@ impl Renderable<Thread> for Thread {
             }
         }
 
-        self.posts.default_view(posts_view)
+        html! {
+            <div>
+                {
+                    if let Some(ref poll) = self.poll {
+                        poll_view(poll, self.user_uuid.is_some())
+                    } else {
+                        html! { <></> }
+                    }
+                }
+                {self.posts.default_view(posts_view)}
+            </div>
+        }
+    }
+}
+
+fn poll_view(poll: &PollResponse, logged_in: bool) -> Html<Thread> {
+    let can_vote: bool = logged_in && !poll.closed;
+    let has_voted: bool = poll.options.iter().any(|option| option.voted);
+    let status: &str = if poll.closed {
+        "This poll is closed."
+    } else if !poll.results_visible && poll.hide_results_until_closed {
+        "Results will be shown when the poll closes."
+    } else if !poll.results_visible {
+        "Vote to see the results."
+    } else {
+        ""
+    };
+    html! {
+        <div class="forum-poll",>
+            <h3>{&poll.question}</h3>
+            <ul>
+                { for poll.options.iter().map(|option| poll_option_view(option, can_vote)) }
+            </ul>
+            <div>{status}</div>
+            {
+                if can_vote && has_voted {
+                    html! { <Button: title="Retract Vote", onclick=|_| Msg::RetractVote, /> }
+                } else {
+                    html! { <></> }
+                }
+            }
+        </div>
+    }
+}
+
+fn poll_option_view(option: &PollOptionResponse, can_vote: bool) -> Html<Thread> {
+    let option_uuid: PollOptionUuid = option.uuid;
+    let votes: String = option.votes
+        .map(|votes| format!("{} vote(s)", votes))
+        .unwrap_or_default();
+    let class: &str = if option.voted { "poll-option voted" } else { "poll-option" };
+    html! {
+        <li class=class,>
+            <span>{&option.content}</span>
+            <span>{votes}</span>
+            {
+                if can_vote {
+                    html! { <Button: title="Vote", onclick=move |_| Msg::Vote(option_uuid), /> }
+                } else {
+                    html! { <></> }
+                }
+            }
+        </li>
     }
 }
 
@@ -124,6 +124,46 @@ impl Renderable<ThreadsList> for ThreadsList {
 impl Renderable<ThreadsList> for SelectableMinimalThreadData {
    fn view(&self) -> Html<ThreadsList> {
 
+       fn last_activity_text(minimal_thread_data: &MinimalThreadData) -> String {
+           let replies = match minimal_thread_data.reply_count {
+               1 => String::from("1 reply"),
+               count => format!("{} replies", count)
+           };
+           match minimal_thread_data.last_post_author {
+               Some(ref author) => format!("{}, last by {} at {}", replies, author.display_name, minimal_thread_data.last_post_date.format("%Y-%m-%d %H:%M")),
+               None => format!("{}, last active {}", replies, minimal_thread_data.last_post_date.format("%Y-%m-%d %H:%M"))
+           }
+       }
+
+       fn unread_fn(minimal_thread_data: &MinimalThreadData, forum_uuid: &ForumUuid) -> Html<ThreadsList> {
+           match (minimal_thread_data.unread_count, minimal_thread_data.first_unread_post_uuid) {
+               (Some(unread_count), Some(first_unread_post_uuid)) if unread_count > 0 => {
+                   html! {
+                       <div class="unread-thread",>
+                           <RouterLink: text=format!("{} new", unread_count), route=route!("forum/{}/{}#{}", forum_uuid, minimal_thread_data.uuid, first_unread_post_uuid), />
+                       </div>
+                   }
+               }
+               _ => {
+                   html! {
+                       <></>
+                   }
+               }
+           }
+       }
+
+       fn pinned_fn(minimal_thread_data: &MinimalThreadData) -> Html<ThreadsList> {
+           if minimal_thread_data.pinned {
+               html! {
+                   <div class="pinned-thread",>{"Pinned"}</div>
+               }
+           } else {
+               html! {
+                   <></>
+               }
+           }
+       }
+
        fn element_internals(minimal_thread_data: &MinimalThreadData, forum_uuid: &ForumUuid) -> Html<ThreadsList> {
            html! {
                <>
@@ -133,6 +173,11 @@ impl Renderable<ThreadsList> for SelectableMinimalThreadData {
                    <div>
                         {format!("By: {}", minimal_thread_data.author.display_name)}
                    </div>
+                   <div>
+                        {last_activity_text(minimal_thread_data)}
+                   </div>
+                   {pinned_fn(minimal_thread_data)}
+                   {unread_fn(minimal_thread_data, forum_uuid)}
                </>
            }
 
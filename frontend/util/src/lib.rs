@@ -2,10 +2,11 @@
 extern crate yew;
 //extern crate context;
 extern crate common;
+extern crate wire;
 #[macro_use]
 extern crate stdweb;
 
-extern crate pulldown_cmark;
+extern crate markdown as shared_markdown;
 
 pub mod loadable;
 pub mod uploadable;
@@ -1,5 +1,7 @@
 use yew::prelude::*;
 
+use super::markdown_upload::MarkdownUpload;
+
 pub struct AuthorMarkdownSideBySide {
     text: String,
     callback: Option<Callback<String>>,
@@ -8,6 +10,8 @@ pub struct AuthorMarkdownSideBySide {
 
 pub enum Msg {
     UpdateText(String),
+    /// Appends the markdown for an uploaded file to the text.
+    InsertMarkdown(String),
 }
 
 #[derive(Clone, PartialEq)]
@@ -46,6 +50,17 @@ impl Component for AuthorMarkdownSideBySide {
                 }
                 true
             }
+            Msg::InsertMarkdown(markdown) => {
+                if !self.text.is_empty() && !self.text.ends_with('\n') {
+                    self.text.push('\n');
+                }
+                self.text.push_str(&markdown);
+                self.text.push('\n');
+                if let Some(ref mut cb) = self.callback {
+                    cb.emit(self.text.clone());
+                }
+                true
+            }
         }
     }
 
@@ -58,16 +73,21 @@ impl Renderable<AuthorMarkdownSideBySide> for AuthorMarkdownSideBySide {
     fn view(&self) -> Html<Self> {
 
         return html! {
-            <div class="edit-markdown-side-by-side-wrapper", >
-                <div class=("edit-markdown-half", "border-right"),>
-                    <textarea
-                        class=("markdown-textarea","form-control"),
-                        value=&self.text,
-                        oninput=|e| Msg::UpdateText(e.value),
-                    />
+            <div>
+                <div class="edit-markdown-bar",>
+                    <MarkdownUpload: callback=Msg::InsertMarkdown, />
                 </div>
-                <div class="edit-markdown-half",>
-                    {super::render_markdown::<Self>(&self.text)}
+                <div class="edit-markdown-side-by-side-wrapper", >
+                    <div class=("edit-markdown-half", "border-right"),>
+                        <textarea
+                            class=("markdown-textarea","form-control"),
+                            value=&self.text,
+                            oninput=|e| Msg::UpdateText(e.value),
+                        />
+                    </div>
+                    <div class="edit-markdown-half",>
+                        {super::render_markdown::<Self>(&self.text)}
+                    </div>
                 </div>
             </div>
         };
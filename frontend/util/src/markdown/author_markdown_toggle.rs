@@ -1,5 +1,7 @@
 use yew::prelude::*;
 
+use super::markdown_upload::MarkdownUpload;
+
 use button::Button;
 
 pub enum State {
@@ -16,6 +18,8 @@ pub struct AuthorMarkdownToggle {
 
 pub enum Msg {
     UpdateText(String),
+    /// Appends the markdown for an uploaded file to the text.
+    InsertMarkdown(String),
     ChangeState(State),
 }
 
@@ -56,6 +60,17 @@ impl Component for AuthorMarkdownToggle {
                 }
                 true
             }
+            Msg::InsertMarkdown(markdown) => {
+                if !self.text.is_empty() && !self.text.ends_with('\n') {
+                    self.text.push('\n');
+                }
+                self.text.push_str(&markdown);
+                self.text.push('\n');
+                if let Some(ref mut cb) = self.callback {
+                    cb.emit(self.text.clone());
+                }
+                true
+            }
             Msg::ChangeState(state) => {
                 self.editor_state = state;
                 true
@@ -98,6 +113,7 @@ impl Renderable<AuthorMarkdownToggle> for AuthorMarkdownToggle {
                 <div class="edit-markdown-bar",>
                     <Button: title="Edit", onclick=|_| Msg::ChangeState(State::Editing), />
                     <Button: title="View", onclick=|_| Msg::ChangeState(State::RenderingMarkdown), />
+                    <MarkdownUpload: callback=Msg::InsertMarkdown, />
                 </div>
                 <div class="markdown-min-height",>
                     {view()}
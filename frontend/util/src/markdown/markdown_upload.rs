@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use yew::prelude::*;
+use common::fetch::FetchResponse;
+use common::upload::{UploadService, UploadTask};
+use wire::upload::UploadResponse;
+
+use button::Button;
+
+/// Used to give each file input on the page its own id.
+static NEXT_INPUT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Lets authors upload a file, giving the markdown that shows it to the callback.
+pub struct MarkdownUpload {
+    input_id: String,
+    upload_service: UploadService,
+    upload_task: Option<UploadTask>,
+    error: Option<String>,
+    callback: Option<Callback<String>>,
+    link: ComponentLink<MarkdownUpload>,
+}
+
+pub enum Msg {
+    Upload,
+    HandleUploadResponse(FetchResponse<UploadResponse>),
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Props {
+    pub callback: Option<Callback<String>>,
+}
+
+impl Default for Props {
+    fn default() -> Self {
+        Props {
+            callback: None,
+        }
+    }
+}
+
+impl Component for MarkdownUpload {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let input_id: usize = NEXT_INPUT_ID.fetch_add(1, Ordering::Relaxed);
+
+        MarkdownUpload {
+            input_id: format!("markdown-upload-{}", input_id),
+            upload_service: UploadService::new(),
+            upload_task: None,
+            error: None,
+            callback: props.callback,
+            link,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Upload => {
+                let callback = self.link.send_back(Msg::HandleUploadResponse);
+                self.upload_task = self.upload_service.upload(&self.input_id, callback);
+                true
+            }
+            Msg::HandleUploadResponse(response) => {
+                match response {
+                    FetchResponse::Success(upload) => {
+                        self.upload_task = None;
+                        self.error = None;
+                        if let Some(ref mut cb) = self.callback {
+                            cb.emit(upload.markdown);
+                        }
+                    }
+                    FetchResponse::Error(_) => {
+                        self.upload_task = None;
+                        self.error = Some("The file couldn't be uploaded".to_string());
+                    }
+                    FetchResponse::Started => {
+                        self.error = None;
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.callback = props.callback;
+        false
+    }
+}
+
+impl Renderable<MarkdownUpload> for MarkdownUpload {
+    fn view(&self) -> Html<Self> {
+        let is_uploading: bool = self.upload_task.is_some();
+        let title: &str = if is_uploading {
+            "Uploading..."
+        } else {
+            "Upload"
+        };
+        let error = match self.error {
+            Some(ref error) => html! {
+                <span>{error}</span>
+            },
+            None => html! { <></> }
+        };
+
+        html! {
+            <>
+                <input type="file", id=&self.input_id, disabled=is_uploading, />
+                <Button: title=title, disabled=is_uploading, onclick=|_| Msg::Upload, />
+                {error}
+            </>
+        }
+    }
+}
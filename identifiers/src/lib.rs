@@ -21,6 +21,13 @@ pub mod question;
 pub mod answer;
 pub mod chat;
 pub mod message;
+pub mod notification;
+pub mod subscription;
+pub mod poll;
+pub mod poll_option;
+pub mod article_revision;
+pub mod article_review;
+pub mod upload;
 
 
 
@@ -0,0 +1,60 @@
+use uuid::Uuid;
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FormatResult;
+use uuid::ParseError;
+
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Default, Hash, Eq)]
+pub struct NotificationUuid(pub Uuid);
+
+const PARAM_NAME: &str = "notification_uuid";
+impl NotificationUuid {
+    pub fn to_query_parameter(self) -> String {
+        format!("{}={}", PARAM_NAME, self.0)
+    }
+    pub fn parse_str(input: &str) -> Result<Self, ParseError> {
+        Uuid::parse_str(input).map(NotificationUuid)
+    }
+}
+
+impl Display for NotificationUuid {
+    fn fmt(&self, f: &mut Formatter) -> FormatResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<Uuid> for NotificationUuid {
+    fn from(uuid: Uuid) -> NotificationUuid {
+        NotificationUuid(uuid)
+    }
+}
+
+#[cfg(feature = "rocket_support")]
+mod rocket {
+    use super::*;
+    use ::rocket::http::RawStr;
+    use ::rocket::request::FromParam;
+    use crate::uuid_from_param;
+    use crate::uuid_from_form;
+    use ::rocket::request::{FromForm, FormItems};
+
+    impl<'a> FromParam<'a> for NotificationUuid {
+        type Error = &'a RawStr;
+
+        #[inline]
+        fn from_param(param: &'a RawStr) -> Result<Self, Self::Error> {
+            uuid_from_param(param).map(NotificationUuid)
+        }
+    }
+
+
+    impl<'f> FromForm<'f> for NotificationUuid {
+        type Error = ();
+
+        #[inline]
+        fn from_form(items: &mut FormItems<'f>, strict: bool) -> Result<Self, ()> {
+            uuid_from_form(items, strict, PARAM_NAME)
+                .map(NotificationUuid)
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use uuid::Uuid;
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FormatResult;
+use uuid::ParseError;
+
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Default, Hash, Eq)]
+pub struct PollOptionUuid(pub Uuid);
+
+const PARAM_NAME: &str = "poll_option_uuid";
+impl PollOptionUuid {
+    pub fn to_query_parameter(self) -> String {
+        format!("{}={}", PARAM_NAME, self.0)
+    }
+    pub fn parse_str(input: &str) -> Result<Self, ParseError> {
+        Uuid::parse_str(input).map(PollOptionUuid)
+    }
+}
+
+impl Display for PollOptionUuid {
+    fn fmt(&self, f: &mut Formatter) -> FormatResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<Uuid> for PollOptionUuid {
+    fn from(uuid: Uuid) -> PollOptionUuid {
+        PollOptionUuid(uuid)
+    }
+}
+
+#[cfg(feature = "rocket_support")]
+mod rocket {
+    use super::*;
+    use ::rocket::http::RawStr;
+    use ::rocket::request::FromParam;
+    use crate::uuid_from_param;
+    use crate::uuid_from_form;
+    use ::rocket::request::{FromForm, FormItems};
+
+    impl<'a> FromParam<'a> for PollOptionUuid {
+        type Error = &'a RawStr;
+
+        #[inline]
+        fn from_param(param: &'a RawStr) -> Result<Self, Self::Error> {
+            uuid_from_param(param).map(PollOptionUuid)
+        }
+    }
+
+
+    impl<'f> FromForm<'f> for PollOptionUuid {
+        type Error = ();
+
+        #[inline]
+        fn from_form(items: &mut FormItems<'f>, strict: bool) -> Result<Self, ()> {
+            uuid_from_form(items, strict, PARAM_NAME)
+                .map(PollOptionUuid)
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use uuid::Uuid;
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FormatResult;
+use uuid::ParseError;
+
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Default, Hash, Eq)]
+pub struct SubscriptionUuid(pub Uuid);
+
+const PARAM_NAME: &str = "subscription_uuid";
+impl SubscriptionUuid {
+    pub fn to_query_parameter(self) -> String {
+        format!("{}={}", PARAM_NAME, self.0)
+    }
+    pub fn parse_str(input: &str) -> Result<Self, ParseError> {
+        Uuid::parse_str(input).map(SubscriptionUuid)
+    }
+}
+
+impl Display for SubscriptionUuid {
+    fn fmt(&self, f: &mut Formatter) -> FormatResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<Uuid> for SubscriptionUuid {
+    fn from(uuid: Uuid) -> SubscriptionUuid {
+        SubscriptionUuid(uuid)
+    }
+}
+
+#[cfg(feature = "rocket_support")]
+mod rocket {
+    use super::*;
+    use ::rocket::http::RawStr;
+    use ::rocket::request::FromParam;
+    use crate::uuid_from_param;
+    use crate::uuid_from_form;
+    use ::rocket::request::{FromForm, FormItems};
+
+    impl<'a> FromParam<'a> for SubscriptionUuid {
+        type Error = &'a RawStr;
+
+        #[inline]
+        fn from_param(param: &'a RawStr) -> Result<Self, Self::Error> {
+            uuid_from_param(param).map(SubscriptionUuid)
+        }
+    }
+
+
+    impl<'f> FromForm<'f> for SubscriptionUuid {
+        type Error = ();
+
+        #[inline]
+        fn from_form(items: &mut FormItems<'f>, strict: bool) -> Result<Self, ()> {
+            uuid_from_form(items, strict, PARAM_NAME)
+                .map(SubscriptionUuid)
+        }
+    }
+}
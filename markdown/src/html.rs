@@ -0,0 +1,128 @@
+//! Writes rendered markdown out as HTML, for the backend.
+
+use crate::tree::{Node, Element, parse};
+
+/// Renders the markdown to HTML that is safe to show to readers.
+pub fn render_html(src: &str) -> String {
+    let mut html = String::with_capacity(src.len() * 3 / 2);
+    for node in parse(src) {
+        write_node(&mut html, &node);
+    }
+    html
+}
+
+fn write_node(html: &mut String, node: &Node) {
+    match node {
+        Node::Text(text) => escape(html, text),
+        Node::Element(element) => write_element(html, element),
+    }
+}
+
+fn write_element(html: &mut String, element: &Element) {
+    html.push('<');
+    html.push_str(element.name);
+    if !element.classes.is_empty() {
+        html.push_str(" class=\"");
+        html.push_str(&element.classes.join(" "));
+        html.push('"');
+    }
+    for (name, value) in element.attributes() {
+        html.push(' ');
+        html.push_str(name);
+        html.push_str("=\"");
+        escape(html, value);
+        html.push('"');
+    }
+    html.push('>');
+
+    if !element.is_void() {
+        for child in &element.children {
+            write_node(html, child);
+        }
+        html.push_str("</");
+        html.push_str(element.name);
+        html.push('>');
+    }
+    if element.is_block() {
+        html.push('\n');
+    }
+}
+
+fn escape(html: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_markdown() {
+        assert_eq!(render_html("*hello* **there**"), "<p><em>hello</em> <strong>there</strong></p>\n");
+    }
+
+    #[test]
+    fn escapes_html() {
+        let rendered = render_html("<script>alert(1)</script>\n\nhi <b onclick=\"x\">there</b>");
+        assert!(!rendered.contains("<script>"));
+        assert!(!rendered.contains("<b "));
+        assert!(rendered.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn removes_unsafe_urls() {
+        let rendered = render_html("[a](javascript:alert(1)) [b]( JaVa\tScript:alert(1)) [c](https://example.com) [d](/relative) ![e](data:image/png,x)");
+        assert!(!rendered.to_lowercase().contains("script:"));
+        assert!(!rendered.contains("data:"));
+        assert!(rendered.contains("<a>a</a>"));
+        assert!(rendered.contains("href=\"https://example.com\""));
+        assert!(rendered.contains("href=\"/relative\""));
+        assert!(rendered.contains("<img alt=\"e\">"));
+    }
+
+    #[test]
+    fn strikethrough() {
+        assert_eq!(render_html("a ~~b~~ c ~~d"), "<p>a <del>b</del> c ~~d</p>\n");
+        assert_eq!(render_html("`~~code~~`"), "<p><code>~~code~~</code></p>\n");
+    }
+
+    #[test]
+    fn task_lists() {
+        assert_eq!(
+            render_html("- [ ] todo\n- [x] done\n- plain"),
+            "<ul><li class=\"task-list-item\"><input type=\"checkbox\" disabled=\"\">todo</li>\n\
+             <li class=\"task-list-item\"><input type=\"checkbox\" disabled=\"\" checked=\"\">done</li>\n\
+             <li>plain</li>\n\
+             </ul>\n"
+        );
+    }
+
+    #[test]
+    fn footnotes() {
+        let rendered = render_html("text[^note]\n\n[^note]: The \"note\"");
+        assert!(rendered.contains("<sup class=\"footnote-reference\"><a href=\"#fn-note\">1</a></sup>"));
+        assert!(rendered.contains("<div class=\"footnote-definition\" id=\"fn-note\"><sup class=\"footnote-definition-label\">1</sup>"));
+        assert!(rendered.contains("The &quot;note&quot;"));
+    }
+
+    #[test]
+    fn tables() {
+        let rendered = render_html("| a | b |\n|:--|--:|\n| 1 | 2 |");
+        assert!(rendered.contains("<th class=\"text-left\" scope=\"col\">a</th>"));
+        assert!(rendered.contains("<td class=\"text-right\">2</td>"));
+    }
+
+    #[test]
+    fn image_descriptions_become_alt_text() {
+        assert_eq!(render_html("![an *image*](/a.png \"title\")"), "<p><img src=\"/a.png\" title=\"title\" alt=\"an image\"></p>\n");
+    }
+}
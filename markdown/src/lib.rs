@@ -0,0 +1,67 @@
+//! Renders markdown for both the frontend and the backend.
+//!
+//! Markdown is parsed into a tree of `Node`s, which only ever contains elements and attributes from an allowlist,
+//! and only urls with safe schemes.
+//! The frontend turns this tree into virtual DOM nodes, and the backend writes it out as HTML,
+//! so the same markdown looks the same wherever it is shown.
+//!
+//! Tables, footnotes, strikethrough (`~~text~~`) and task lists (`- [x] item`) are supported.
+//! Any HTML written in the markdown is shown as text rather than rendered.
+
+extern crate pulldown_cmark;
+
+pub mod sanitize;
+mod tree;
+mod html;
+
+pub use crate::tree::{Node, Element, parse};
+pub use crate::html::render_html;
+
+/// Gets the text of the markdown without any formatting, shortened to at most `max_chars` characters.
+///
+/// Whitespace is collapsed, footnotes are left out, and if the text had to be shortened,
+/// it is cut at the end of a word and followed by an ellipsis.
+pub fn excerpt(src: &str, max_chars: usize) -> String {
+    let mut text = String::new();
+    for node in parse(src) {
+        node.push_text(&mut text);
+        text.push(' ');
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    let mut excerpt = String::new();
+    for word in words {
+        let length: usize = excerpt.chars().count();
+        let separator: usize = if excerpt.is_empty() { 0 } else { 1 };
+        if length + separator + word.chars().count() > max_chars {
+            if excerpt.is_empty() {
+                // A single word is too long, so it has to be cut.
+                excerpt.extend(word.chars().take(max_chars.saturating_sub(1)));
+            }
+            excerpt.push('…');
+            return excerpt;
+        }
+        if separator == 1 {
+            excerpt.push(' ');
+        }
+        excerpt.push_str(word);
+    }
+    excerpt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excerpt_strips_formatting() {
+        assert_eq!(excerpt("# Title\n\nSome *emphasised* `code`[^1]\n\n[^1]: A note", 100), "Title Some emphasised code");
+    }
+
+    #[test]
+    fn excerpt_is_cut_between_words() {
+        assert_eq!(excerpt("one two three", 9), "one two…");
+        assert_eq!(excerpt("one two three", 13), "one two three");
+        assert_eq!(excerpt("abcdefghij", 5), "abcd…");
+    }
+}
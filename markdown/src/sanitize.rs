@@ -0,0 +1,66 @@
+//! The allowlists that decide what can end up in rendered markdown.
+
+/// The url schemes that links and images are allowed to use.
+/// Urls without a scheme are relative, and are always allowed.
+pub const ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// The attributes that may be set on each element, other than `class`, which is never taken from the markdown.
+pub const ALLOWED_ATTRIBUTES: &[(&str, &str)] = &[
+    ("a", "href"),
+    ("a", "title"),
+    ("img", "src"),
+    ("img", "alt"),
+    ("img", "title"),
+    ("ol", "start"),
+    ("th", "scope"),
+    ("div", "id"),
+    ("input", "type"),
+    ("input", "checked"),
+    ("input", "disabled"),
+];
+
+/// The attributes that hold urls, which have to use one of the allowed schemes.
+pub const URL_ATTRIBUTES: &[&str] = &["href", "src"];
+
+pub fn is_allowed_attribute(element: &str, attribute: &str) -> bool {
+    ALLOWED_ATTRIBUTES.contains(&(element, attribute))
+}
+
+/// Checks if the url is relative, or uses one of the allowed schemes.
+pub fn is_allowed_url(url: &str) -> bool {
+    // Browsers ignore whitespace and control characters when reading the scheme.
+    let url: String = url.chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect();
+    match url.find(&[':', '/', '?', '#'][..]) {
+        Some(index) if url[index..].starts_with(':') => {
+            let scheme: String = url[..index].to_lowercase();
+            ALLOWED_SCHEMES.contains(&scheme.as_str())
+        }
+        _ => true
+    }
+}
+
+/// Makes an identifier that can be used as an element's id, or in a url fragment,
+/// from text provided by the author.
+pub fn to_identifier(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls() {
+        assert!(is_allowed_url("https://example.com"));
+        assert!(is_allowed_url("mailto:someone@example.com"));
+        assert!(is_allowed_url("/relative/path:with-colon"));
+        assert!(is_allowed_url("#fragment"));
+        assert!(!is_allowed_url("javascript:alert(1)"));
+        assert!(!is_allowed_url(" JaVa\tScript:alert(1)"));
+        assert!(!is_allowed_url("data:text/html,hi"));
+    }
+}
@@ -0,0 +1,403 @@
+//! Builds the tree of elements that markdown renders to.
+//!
+//! The choice of elements and classes was originally written by [Nathan Ringo](https://github.com/remexre)
+//! Source: https://github.com/acmumn/mentoring/blob/master/web-client/src/view/markdown.rs
+
+use pulldown_cmark::{Alignment, Event, Options, Parser, Tag};
+use crate::sanitize::{is_allowed_attribute, is_allowed_url, to_identifier, URL_ATTRIBUTES};
+
+/// A piece of rendered markdown.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+/// An HTML element.
+/// Its attributes can only be set through `set_attribute`, which enforces the allowlist.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+    pub name: &'static str,
+    pub classes: Vec<&'static str>,
+    attributes: Vec<(&'static str, String)>,
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    pub fn new(name: &'static str) -> Element {
+        Element {
+            name,
+            classes: vec![],
+            attributes: vec![],
+            children: vec![],
+        }
+    }
+
+    fn with_class(mut self, class: &'static str) -> Element {
+        self.classes.push(class);
+        self
+    }
+
+    /// Sets the attribute, unless the element isn't allowed to have it,
+    /// or it is a url that doesn't use an allowed scheme.
+    pub fn set_attribute(&mut self, name: &'static str, value: String) {
+        if !is_allowed_attribute(self.name, name) {
+            return;
+        }
+        if URL_ATTRIBUTES.contains(&name) && !is_allowed_url(&value) {
+            return;
+        }
+        self.attributes.retain(|(existing, _)| *existing != name);
+        self.attributes.push((name, value));
+    }
+
+    pub fn attributes(&self) -> &[(&'static str, String)] {
+        &self.attributes
+    }
+
+    /// Void elements can't have children or a closing tag.
+    pub fn is_void(&self) -> bool {
+        matches!(self.name, "br" | "hr" | "img" | "input")
+    }
+
+    /// Block elements are written on their own lines, and separate the words of their neighbours.
+    pub fn is_block(&self) -> bool {
+        matches!(
+            self.name,
+            "p" | "hr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "blockquote" | "pre" | "ul" | "ol" | "li"
+            | "div" | "table" | "tr" | "th" | "td"
+        )
+    }
+
+    fn has_class(&self, class: &str) -> bool {
+        self.classes.contains(&class)
+    }
+}
+
+impl Node {
+    /// Appends the text of the node to the string, leaving out footnotes.
+    pub(crate) fn push_text(&self, text: &mut String) {
+        match self {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(element) => {
+                if element.has_class("footnote-reference") || element.has_class("footnote-definition") {
+                    return;
+                }
+                if let Some((_, alt)) = element.attributes.iter().find(|(name, _)| *name == "alt") {
+                    text.push_str(alt);
+                }
+                for child in &element.children {
+                    child.push_text(text);
+                }
+                if element.is_block() {
+                    text.push(' ');
+                }
+            }
+        }
+    }
+}
+
+/// Parses the markdown into a tree of sanitized elements.
+pub fn parse(src: &str) -> Vec<Node> {
+    let mut builder = Builder::default();
+    for event in Parser::new_ext(src, Options::ENABLE_TABLES | Options::ENABLE_FOOTNOTES) {
+        builder.event(event);
+    }
+    builder.finish()
+}
+
+#[derive(Default)]
+struct Builder {
+    /// The elements that have been started, but not yet ended.
+    spine: Vec<Element>,
+    nodes: Vec<Node>,
+    /// The names of the footnotes, in the order they were first referred to.
+    footnotes: Vec<String>,
+}
+
+impl Builder {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => {
+                let element = self.start(tag);
+                self.spine.push(element);
+            }
+            Event::End(tag) => {
+                if let Some(element) = self.spine.pop() {
+                    let node = self.end(element, tag);
+                    self.add(node);
+                }
+            }
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => self.add(Node::Text(text.into_owned())),
+            Event::FootnoteReference(name) => {
+                let number: usize = self.footnote_number(&name);
+                let mut link = Element::new("a");
+                link.set_attribute("href", format!("#{}", footnote_id(&name)));
+                link.children.push(Node::Text(number.to_string()));
+                let mut reference = Element::new("sup").with_class("footnote-reference");
+                reference.children.push(Node::Element(link));
+                self.add(Node::Element(reference));
+            }
+            Event::SoftBreak => self.add(Node::Text("\n".to_string())),
+            Event::HardBreak => self.add(Node::Element(Element::new("br"))),
+        }
+    }
+
+    fn add(&mut self, node: Node) {
+        match self.spine.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.nodes.push(node),
+        }
+    }
+
+    fn footnote_number(&mut self, name: &str) -> usize {
+        match self.footnotes.iter().position(|footnote| footnote == name) {
+            Some(index) => index + 1,
+            None => {
+                self.footnotes.push(name.to_string());
+                self.footnotes.len()
+            }
+        }
+    }
+
+    fn start(&mut self, tag: Tag) -> Element {
+        match tag {
+            Tag::Paragraph => Element::new("p"),
+            Tag::Rule => Element::new("hr"),
+            Tag::Header(1) => Element::new("h1"),
+            Tag::Header(2) => Element::new("h2"),
+            Tag::Header(3) => Element::new("h3"),
+            Tag::Header(4) => Element::new("h4"),
+            Tag::Header(5) => Element::new("h5"),
+            Tag::Header(_) => Element::new("h6"),
+            Tag::BlockQuote => Element::new("blockquote").with_class("blockquote"),
+            Tag::CodeBlock(lang) => {
+                let code = Element::new("code");
+                // Different color schemes may be used for different code blocks,
+                // but a different library (likely js based at the moment) would be necessary to actually provide the
+                // highlighting support by locating the language classes and applying dom transforms
+                // on their contents.
+                match lang.as_ref() {
+                    "html" => code.with_class("html-language"),
+                    "rust" => code.with_class("rust-language"),
+                    "java" => code.with_class("java-language"),
+                    "c" => code.with_class("c-language"),
+                    _ => code // Add your own language highlighting support
+                }
+            }
+            Tag::List(None) => Element::new("ul"),
+            Tag::List(Some(1)) => Element::new("ol"),
+            Tag::List(Some(start)) => {
+                let mut list = Element::new("ol");
+                list.set_attribute("start", start.to_string());
+                list
+            }
+            Tag::Item => Element::new("li"),
+            Tag::FootnoteDefinition(name) => {
+                let number: usize = self.footnote_number(&name);
+                let mut definition = Element::new("div").with_class("footnote-definition");
+                definition.set_attribute("id", footnote_id(&name));
+                let mut label = Element::new("sup").with_class("footnote-definition-label");
+                label.children.push(Node::Text(number.to_string()));
+                definition.children.push(Node::Element(label));
+                definition
+            }
+            Tag::Table(_) => Element::new("table").with_class("table"),
+            Tag::TableHead => Element::new("tr"),
+            Tag::TableRow => Element::new("tr"),
+            Tag::TableCell => Element::new("td"),
+            Tag::Emphasis => Element::new("em"),
+            Tag::Strong => Element::new("strong"),
+            Tag::Code => Element::new("code"),
+            Tag::Link(href, title) => {
+                let mut link = Element::new("a");
+                link.set_attribute("href", href.into_owned());
+                if !title.is_empty() {
+                    link.set_attribute("title", title.into_owned());
+                }
+                link
+            }
+            Tag::Image(src, title) => {
+                let mut image = Element::new("img");
+                image.set_attribute("src", src.into_owned());
+                if !title.is_empty() {
+                    image.set_attribute("title", title.into_owned());
+                }
+                image
+            }
+        }
+    }
+
+    fn end(&mut self, mut element: Element, tag: Tag) -> Node {
+        merge_text(&mut element.children);
+        if element.name != "code" {
+            strikethrough(&mut element.children);
+        }
+        match tag {
+            Tag::CodeBlock(_) => {
+                let mut pre = Element::new("pre");
+                pre.children.push(Node::Element(element));
+                return Node::Element(pre);
+            }
+            Tag::Table(aligns) => {
+                for row in &mut element.children {
+                    if let Node::Element(row) = row {
+                        for (cell, align) in row.children.iter_mut().zip(aligns.iter()) {
+                            if let Node::Element(cell) = cell {
+                                match align {
+                                    Alignment::None => {}
+                                    Alignment::Left => cell.classes.push("text-left"),
+                                    Alignment::Center => cell.classes.push("text-center"),
+                                    Alignment::Right => cell.classes.push("text-right"),
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Tag::TableHead => {
+                for cell in &mut element.children {
+                    if let Node::Element(cell) = cell {
+                        cell.name = "th";
+                        cell.set_attribute("scope", "col".to_string());
+                    }
+                }
+            }
+            Tag::Image(..) => {
+                // The image's description is parsed as markdown, but images can't have children.
+                let mut alt = String::new();
+                for child in element.children.drain(..) {
+                    child.push_text(&mut alt);
+                }
+                element.set_attribute("alt", alt.split_whitespace().collect::<Vec<_>>().join(" "));
+            }
+            Tag::TableCell => {
+                // The parser keeps the spaces around the cell's text.
+                if let Some(Node::Text(text)) = element.children.first_mut() {
+                    *text = text.trim_start().to_string();
+                }
+                if let Some(Node::Text(text)) = element.children.last_mut() {
+                    *text = text.trim_end().to_string();
+                }
+            }
+            Tag::Item => task_list_item(&mut element),
+            _ => {}
+        }
+        Node::Element(element)
+    }
+
+    fn finish(mut self) -> Vec<Node> {
+        // Unbalanced events shouldn't happen, but if they do, the unfinished elements are kept.
+        while let Some(element) = self.spine.pop() {
+            self.add(Node::Element(element));
+        }
+        self.nodes
+    }
+}
+
+/// Footnote ids are prefixed so they can't clash with the ids of the page the markdown is shown on.
+fn footnote_id(name: &str) -> String {
+    format!("fn-{}", to_identifier(name))
+}
+
+/// Joins neighbouring text nodes, which the parser splits up around characters that could have been markup.
+fn merge_text(children: &mut Vec<Node>) {
+    let mut merged: Vec<Node> = Vec::with_capacity(children.len());
+    for child in children.drain(..) {
+        match (merged.last_mut(), child) {
+            (Some(Node::Text(previous)), Node::Text(text)) => previous.push_str(&text),
+            (_, child) => merged.push(child),
+        }
+    }
+    *children = merged;
+}
+
+/// Turns text between pairs of `~~` into struck through elements.
+fn strikethrough(children: &mut Vec<Node>) {
+    let mut struck: Vec<Node> = Vec::with_capacity(children.len());
+    for child in children.drain(..) {
+        match child {
+            Node::Text(text) => {
+                let mut rest: &str = &text;
+                while let Some(start) = rest.find("~~") {
+                    let after_start: &str = &rest[start + 2..];
+                    let end: usize = match after_start.find("~~") {
+                        Some(end) if end > 0 => end,
+                        _ => break
+                    };
+                    if start > 0 {
+                        struck.push(Node::Text(rest[..start].to_string()));
+                    }
+                    let mut del = Element::new("del");
+                    del.children.push(Node::Text(after_start[..end].to_string()));
+                    struck.push(Node::Element(del));
+                    rest = &after_start[end + 2..];
+                }
+                if !rest.is_empty() {
+                    struck.push(Node::Text(rest.to_string()));
+                }
+            }
+            child => struck.push(child),
+        }
+    }
+    *children = struck;
+}
+
+/// Turns a list item starting with `[ ]` or `[x]` into a task, with a checkbox that can't be changed.
+fn task_list_item(item: &mut Element) {
+    // Items in loose lists have their text wrapped in a paragraph.
+    let children: &mut Vec<Node> = match item.children.first_mut() {
+        Some(Node::Element(paragraph)) if paragraph.name == "p" => &mut paragraph.children,
+        _ => &mut item.children
+    };
+    let checked: bool = match children.first_mut() {
+        Some(Node::Text(text)) => {
+            let checked: bool = if text.starts_with("[ ] ") {
+                false
+            } else if text.starts_with("[x] ") || text.starts_with("[X] ") {
+                true
+            } else {
+                return;
+            };
+            text.replace_range(..4, "");
+            checked
+        }
+        _ => return
+    };
+
+    let mut checkbox = Element::new("input");
+    checkbox.set_attribute("type", "checkbox".to_string());
+    checkbox.set_attribute("disabled", String::new());
+    if checked {
+        checkbox.set_attribute("checked", String::new());
+    }
+    children.insert(0, Node::Element(checkbox));
+    item.classes.push("task-list-item");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_is_kept_as_text() {
+        let nodes = parse("<b>bold</b>");
+        assert_eq!(nodes.len(), 1);
+        match &nodes[0] {
+            Node::Element(paragraph) => assert_eq!(paragraph.children, vec![Node::Text("<b>bold</b>".to_string())]),
+            Node::Text(_) => panic!("Expected a paragraph")
+        }
+    }
+
+    #[test]
+    fn disallowed_attributes_are_not_set() {
+        let mut paragraph = Element::new("p");
+        paragraph.set_attribute("onclick", "alert(1)".to_string());
+        paragraph.set_attribute("href", "https://example.com".to_string());
+        assert!(paragraph.attributes().is_empty());
+
+        let mut link = Element::new("a");
+        link.set_attribute("href", "javascript:alert(1)".to_string());
+        assert!(link.attributes().is_empty());
+    }
+}
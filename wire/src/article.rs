@@ -1,7 +1,11 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use crate::user::UserResponse;
 use identifiers::article::ArticleUuid;
+use identifiers::article_revision::ArticleRevisionUuid;
+use identifiers::article_review::ArticleReviewUuid;
+use identifiers::thread::ThreadUuid;
 use identifiers::user::UserUuid;
+use uuid::Uuid;
 
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -9,6 +13,9 @@ pub struct NewArticleRequest {
     pub title: String,
     pub body: String,
     pub author_id: UserUuid,
+    /// The names of the article's tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -16,6 +23,41 @@ pub struct UpdateArticleRequest {
     pub uuid: ArticleUuid,
     pub title: Option<String>,
     pub body: Option<String>,
+    /// Replaces the article's tags, if provided.
+    /// Unlike the title and body, tag changes are seen by readers right away.
+    pub tags: Option<Vec<String>>,
+}
+
+/// Query parameters accepted when listing published articles.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct ArticleListQuery {
+    /// Only list articles with this tag.
+    pub tag: Option<String>,
+    /// Only list articles written by this user.
+    ///
+    /// Query strings can't carry the identifier newtypes, so a plain uuid is used here.
+    pub author: Option<Uuid>,
+    /// Defaults to the first page.
+    pub page: Option<i32>,
+}
+
+/// A tag, and how many published articles have it.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct TagCountResponse {
+    pub name: String,
+    pub article_count: i64,
+}
+
+/// Schedules the article to be published once the date passes.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SchedulePublishRequest {
+    pub publish_date: NaiveDateTime,
+}
+
+/// Allows or disallows comments on the article.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SetCommentsRequest {
+    pub enabled: bool,
 }
 
 
@@ -24,8 +66,15 @@ pub struct MinimalArticleResponse {
     pub uuid: ArticleUuid,
     pub author_uuid: UserUuid,
     pub title: String,
+    /// Used to identify the article in urls.
+    pub slug: String,
     pub body: String,
     pub publish_date: Option<NaiveDateTime>,
+    /// The revision readers see.
+    /// Revisions saved after it are drafts, until the article is republished.
+    pub published_revision_uuid: Option<ArticleRevisionUuid>,
+    pub comments_enabled: bool,
+    pub review_state: ReviewState,
 }
 
 /// Doesn't have the body attached.
@@ -35,6 +84,11 @@ pub struct ArticlePreviewResponse {
     pub uuid: ArticleUuid,
     pub author: UserResponse,
     pub title: String,
+    /// Used to identify the article in urls.
+    pub slug: String,
+    /// The start of the body, without any formatting.
+    pub excerpt: String,
+    pub tags: Vec<String>,
     pub publish_date: Option<NaiveDateTime>,
 }
 
@@ -43,7 +97,172 @@ pub struct ArticlePreviewResponse {
 pub struct FullArticleResponse {
     pub id: ArticleUuid,
     pub author: UserResponse,
+    /// Users credited alongside the author.
+    pub co_authors: Vec<UserResponse>,
     pub title: String,
+    /// Used to identify the article in urls.
+    pub slug: String,
     pub body: String,
+    pub tags: Vec<String>,
     pub publish_date: Option<NaiveDateTime>,
+    /// The forum thread that comments on the article are posted in, once it is published.
+    pub comment_thread_uuid: Option<ThreadUuid>,
+    /// The number of replies in the comment thread.
+    pub comment_count: i64,
+    pub review_state: ReviewState,
+}
+
+/// A saved version of an article.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ArticleRevisionResponse {
+    pub uuid: ArticleRevisionUuid,
+    pub article_uuid: ArticleUuid,
+    pub title: String,
+    pub body: String,
+    /// Whether the editor saved the revision on its own, rather than the author.
+    pub autosave: bool,
+    pub created_date: NaiveDateTime,
+}
+
+/// A line of text, and how it changed between two revisions.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum DiffLineResponse {
+    Unchanged(String),
+    Added(String),
+    Removed(String),
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RevisionDiffResponse {
+    pub from: ArticleRevisionResponse,
+    pub to: ArticleRevisionResponse,
+    pub title: Vec<DiffLineResponse>,
+    pub body: Vec<DiffLineResponse>,
+}
+
+/// Where an article is in the editorial process.
+///
+/// Articles are written as drafts, submitted for review,
+/// and must be approved by a publisher other than their authors before they can be published.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReviewState {
+    Draft,
+    InReview,
+    Approved,
+    Published,
+}
+
+impl From<ReviewState> for i32 {
+    fn from(state: ReviewState) -> i32 {
+        match state {
+            ReviewState::Draft => 1,
+            ReviewState::InReview => 2,
+            ReviewState::Approved => 3,
+            ReviewState::Published => 4,
+        }
+    }
+}
+
+impl From<i32> for ReviewState {
+    fn from(number: i32) -> ReviewState {
+        match number {
+            1 => ReviewState::Draft,
+            2 => ReviewState::InReview,
+            3 => ReviewState::Approved,
+            4 => ReviewState::Published,
+            _ => {
+                eprintln!("Tried to convert an unsupported number into a review state");
+                ReviewState::Draft
+            }
+        }
+    }
+}
+
+/// What a reviewer decided when leaving a review.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReviewDecision {
+    /// Leaves the article's review state as it is.
+    Comment,
+    /// Allows the article to be published.
+    Approve,
+    /// Returns the article to its authors as a draft.
+    RequestChanges,
+}
+
+impl From<ReviewDecision> for i32 {
+    fn from(decision: ReviewDecision) -> i32 {
+        match decision {
+            ReviewDecision::Comment => 1,
+            ReviewDecision::Approve => 2,
+            ReviewDecision::RequestChanges => 3,
+        }
+    }
+}
+
+impl From<i32> for ReviewDecision {
+    fn from(number: i32) -> ReviewDecision {
+        match number {
+            1 => ReviewDecision::Comment,
+            2 => ReviewDecision::Approve,
+            3 => ReviewDecision::RequestChanges,
+            _ => {
+                eprintln!("Tried to convert an unsupported number into a review decision");
+                ReviewDecision::Comment
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ReviewRequest {
+    pub decision: ReviewDecision,
+    pub comment: String,
+}
+
+/// Replaces the article's co-authors.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SetCoAuthorsRequest {
+    pub co_author_uuids: Vec<UserUuid>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ArticleReviewResponse {
+    pub uuid: ArticleReviewUuid,
+    pub article_uuid: ArticleUuid,
+    pub reviewer: UserResponse,
+    pub decision: ReviewDecision,
+    pub comment: String,
+    pub created_date: NaiveDateTime,
+}
+
+/// A published article, and how many people read it recently.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct MostReadArticleResponse {
+    pub article: ArticlePreviewResponse,
+    pub views: i64,
+}
+
+/// Query parameters accepted when getting an article's view counts.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct ArticleStatsQuery {
+    /// How many days, up to and including today, to get the view counts of.
+    pub days: Option<i64>,
+}
+
+/// The number of people that viewed an article on a day.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct DailyViewsResponse {
+    pub date: NaiveDate,
+    pub views: i64,
+}
+
+/// How many people have viewed an article.
+///
+/// Each person is counted at most once a day.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ArticleStatsResponse {
+    pub article_uuid: ArticleUuid,
+    pub total_views: i64,
+    /// The views on each of the requested days, oldest first.
+    pub daily_views: Vec<DailyViewsResponse>,
 }
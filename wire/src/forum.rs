@@ -1,10 +1,16 @@
 use identifiers::forum::ForumUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::post::PostUuid;
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+use crate::user::UserResponse;
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct ForumResponse {
     pub uuid: ForumUuid,
     pub title: String,
     pub description: String,
+    pub position: i32,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -12,3 +18,60 @@ pub struct NewForumRequest {
     pub title: String,
     pub description: String,
 }
+
+/// Used when editing a forum.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct EditForumRequest {
+    pub title: String,
+    pub description: String,
+}
+
+/// Query parameters accepted when deleting a forum.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct DeleteForumQuery {
+    /// Forums that still contain threads are only deleted, along with their threads, if this is set.
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Used when reordering forums.
+/// Each forum's position is set to its index in the list.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ReorderForumsRequest {
+    pub forum_uuids: Vec<ForumUuid>,
+}
+
+/// Query parameters accepted when searching the forums.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ForumSearchQuery {
+    /// The search terms, in web search syntax (`"quoted phrases"`, `-excluded`, `or`).
+    pub q: String,
+    /// Only search within this forum.
+    ///
+    /// Query strings can't carry the identifier newtypes, so plain uuids are used here.
+    pub forum: Option<Uuid>,
+    /// Only return posts written by this user.
+    pub author: Option<Uuid>,
+    /// Only return posts created before this time.
+    pub before: Option<NaiveDateTime>,
+    /// Only return posts created after this time.
+    pub after: Option<NaiveDateTime>,
+    /// The page of results to return, starting at 1.
+    pub page: Option<i32>,
+}
+
+/// A single ranked search result, pointing at a post within its thread.
+///
+/// The `title` and `snippet` are not HTML-escaped.
+/// Matching terms within them are delimited by `<mark>` and `</mark>`.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ForumSearchResultResponse {
+    pub forum_uuid: ForumUuid,
+    pub thread_uuid: ThreadUuid,
+    pub post_uuid: PostUuid,
+    pub title: String,
+    pub snippet: String,
+    pub author: UserResponse,
+    pub created_date: NaiveDateTime,
+    pub rank: f32,
+}
@@ -21,6 +21,10 @@ pub mod question;
 pub mod answer;
 pub mod chat;
 pub mod message;
+pub mod notification;
+pub mod subscription;
+pub mod poll;
+pub mod upload;
 pub mod login;
 
 
@@ -0,0 +1,57 @@
+use crate::user::UserResponse;
+use chrono::NaiveDateTime;
+use identifiers::notification::NotificationUuid;
+use identifiers::post::PostUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::forum::ForumUuid;
+
+/// The reasons a user can be notified about a post.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NotificationKind {
+    /// The post replies directly to one of the user's posts.
+    Reply,
+    /// The post mentions the user by their `@user_name`.
+    Mention,
+    /// The post was made in a thread or forum the user subscribed to.
+    Subscription,
+}
+
+impl From<NotificationKind> for i32 {
+    fn from(kind: NotificationKind) -> i32 {
+        match kind {
+            NotificationKind::Reply => 1,
+            NotificationKind::Mention => 2,
+            NotificationKind::Subscription => 3,
+        }
+    }
+}
+
+impl From<i32> for NotificationKind {
+    fn from(number: i32) -> NotificationKind {
+        match number {
+            1 => NotificationKind::Reply,
+            2 => NotificationKind::Mention,
+            3 => NotificationKind::Subscription,
+            _ => {
+                eprintln!("Tried to convert an unsupported number into a notification kind");
+                NotificationKind::Reply
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotificationResponse {
+    pub uuid: NotificationUuid,
+    pub kind: NotificationKind,
+    /// The author of the post that caused the notification.
+    pub actor: UserResponse,
+    pub post_uuid: PostUuid,
+    pub thread_uuid: ThreadUuid,
+    pub forum_uuid: ForumUuid,
+    pub thread_title: String,
+    /// The start of the post's content, without any formatting.
+    pub excerpt: String,
+    pub created_date: NaiveDateTime,
+    pub read: bool,
+}
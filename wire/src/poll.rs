@@ -0,0 +1,51 @@
+use chrono::NaiveDateTime;
+use identifiers::poll::PollUuid;
+use identifiers::poll_option::PollOptionUuid;
+use identifiers::thread::ThreadUuid;
+
+/// Used when attaching a poll to a new thread.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NewPollRequest {
+    pub question: String,
+    /// The options, in the order they should be listed.
+    pub options: Vec<String>,
+    /// Whether users can vote for more than one option.
+    pub multiple_choice: bool,
+    /// Votes are no longer accepted after this date.
+    pub close_date: Option<NaiveDateTime>,
+    /// If set, nobody can see the results until the poll closes.
+    /// Otherwise, users can see the results once they have voted.
+    pub hide_results_until_closed: bool,
+}
+
+/// Used when voting in a poll.
+/// The options replace any the user previously voted for.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PollVoteRequest {
+    pub option_uuids: Vec<PollOptionUuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PollOptionResponse {
+    pub uuid: PollOptionUuid,
+    pub content: String,
+    /// The number of votes for the option, if the results are visible to the user.
+    pub votes: Option<i64>,
+    /// Whether the requesting user voted for this option.
+    pub voted: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PollResponse {
+    pub uuid: PollUuid,
+    pub thread_uuid: ThreadUuid,
+    pub question: String,
+    pub multiple_choice: bool,
+    pub close_date: Option<NaiveDateTime>,
+    /// Whether the poll has stopped accepting votes.
+    pub closed: bool,
+    pub hide_results_until_closed: bool,
+    /// Whether the vote counts of the options are included.
+    pub results_visible: bool,
+    pub options: Vec<PollOptionResponse>,
+}
@@ -5,6 +5,7 @@ use identifiers::thread::ThreadUuid;
 use identifiers::user::UserUuid;
 
 
+/// If the post is censored, the content will be empty unless the post is being viewed by a moderator.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PostResponse {
     pub uuid: PostUuid,
@@ -13,9 +14,22 @@ pub struct PostResponse {
     pub modified_date: Option<NaiveDateTime>,
     pub content: String,
     pub censored: bool,
+    pub censor_reason: Option<String>,
+    /// Up votes less down votes.
+    pub score: i64,
+    pub reactions: Vec<ReactionResponse>,
     pub children: Vec<PostResponse>,
 }
 
+/// The number of times a reaction was left on a post.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReactionResponse {
+    pub reaction: String,
+    pub count: i64,
+    /// Whether the requesting user left this reaction.
+    pub user_reacted: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NewPostRequest {
     pub author_uuid: UserUuid,
@@ -31,3 +45,45 @@ pub struct EditPostRequest {
     pub thread_uuid: ThreadUuid,
     pub content: String,
 }
+
+/// Used when censoring or uncensoring a post.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CensorPostRequest {
+    pub reason: String,
+}
+
+/// A moderator censoring or uncensoring a post, and what the post said at the time.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostCensorActionResponse {
+    pub post_uuid: PostUuid,
+    pub moderator_uuid: Option<UserUuid>,
+    pub censored: bool,
+    pub reason: String,
+    pub content: String,
+    pub created_date: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteDirection {
+    Up,
+    Down,
+}
+
+/// Used when changing a vote on a post.
+/// Omitting the direction removes the user's vote.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VoteRequest {
+    #[serde(default)]
+    pub direction: Option<VoteDirection>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VoteCountsResponse {
+    pub up: i64,
+    pub down: i64,
+    /// Up votes less down votes.
+    pub score: i64,
+    /// The requesting user's vote, if they cast one.
+    pub user_vote: Option<VoteDirection>,
+}
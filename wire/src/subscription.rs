@@ -0,0 +1,22 @@
+use chrono::NaiveDateTime;
+use identifiers::subscription::SubscriptionUuid;
+use identifiers::thread::ThreadUuid;
+use identifiers::forum::ForumUuid;
+use crate::user::UserResponse;
+use crate::notification::NotificationResponse;
+
+/// A subscription to either a thread or a forum.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubscriptionResponse {
+    pub uuid: SubscriptionUuid,
+    pub thread_uuid: Option<ThreadUuid>,
+    pub forum_uuid: Option<ForumUuid>,
+    pub created_date: NaiveDateTime,
+}
+
+/// The notifications a user hasn't seen, gathered up to be delivered all at once.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DigestResponse {
+    pub user: UserResponse,
+    pub notifications: Vec<NotificationResponse>,
+}
@@ -1,9 +1,12 @@
 use crate::user::UserResponse;
 use crate::post::PostResponse;
+use crate::poll::NewPollRequest;
+use crate::poll::PollResponse;
 use chrono::NaiveDateTime;
 use identifiers::thread::ThreadUuid;
 use identifiers::forum::ForumUuid;
 use identifiers::user::UserUuid;
+use identifiers::post::PostUuid;
 
 
 /// Used when requesting that a thread be created.
@@ -13,6 +16,9 @@ pub struct NewThreadRequest {
     pub author_uuid: UserUuid, // TODO, this should be removed, JWT provides this anyway
     pub title: String,
     pub post_content: String,
+    /// An optional poll to attach to the thread.
+    #[serde(default)]
+    pub poll: Option<NewPollRequest>,
 }
 
 /// Used when viewing an individual thread.
@@ -25,6 +31,12 @@ pub struct ThreadResponse {
     pub posts: PostResponse,
     pub created_date: NaiveDateTime,
     pub locked: bool,
+    /// When the most recent post was made in the thread.
+    pub last_post_date: NaiveDateTime,
+    pub last_post_author: Option<UserResponse>,
+    /// The number of posts in the thread, not counting the original post.
+    pub reply_count: i32,
+    pub poll: Option<PollResponse>,
 }
 
 /// Used when returning a list of threads for perusing
@@ -35,4 +47,67 @@ pub struct MinimalThreadResponse {
     pub author: UserResponse,
     pub created_date: NaiveDateTime,
     pub locked: bool,
+    /// When the most recent post was made in the thread.
+    pub last_post_date: NaiveDateTime,
+    pub last_post_author: Option<UserResponse>,
+    /// The number of posts in the thread, not counting the original post.
+    pub reply_count: i32,
+    /// If present, the thread is pinned, and will be listed before unpinned threads.
+    pub pinned_position: Option<i32>,
+    /// The number of posts the user hasn't read yet.
+    /// This is only present if the threads were requested by a logged in user.
+    pub unread_count: Option<i64>,
+    /// The oldest post the user hasn't read yet.
+    pub first_unread_post_uuid: Option<PostUuid>,
+}
+
+
+/// Used when pinning a thread.
+/// Pinned threads are listed in ascending order of their position.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PinThreadRequest {
+    pub position: i32,
+}
+
+/// Used when changing the title of a thread.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EditThreadRequest {
+    pub title: String,
+}
+
+/// Used when moving a thread to another forum.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MoveThreadRequest {
+    pub forum_uuid: ForumUuid,
+}
+
+/// Used when merging a thread into another.
+/// The merged thread's original post becomes a reply to the original post of the target thread.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeThreadRequest {
+    pub target_thread_uuid: ThreadUuid,
+}
+
+/// Used when splitting a post and its replies out into a new thread.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SplitThreadRequest {
+    /// The title of the new thread.
+    pub title: String,
+}
+
+/// The orders in which the threads of a forum can be listed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadListOrder {
+    /// Oldest threads first.
+    Created,
+    /// Threads that were most recently posted in first.
+    LastActivity,
+}
+
+/// Query parameters accepted when listing the threads of a forum.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ThreadListQuery {
+    /// Defaults to the order in which threads were created.
+    pub order: Option<ThreadListOrder>,
 }
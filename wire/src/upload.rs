@@ -0,0 +1,21 @@
+use chrono::NaiveDateTime;
+use identifiers::upload::UploadUuid;
+use identifiers::user::UserUuid;
+
+/// A file that a user has uploaded, and where it can be found.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct UploadResponse {
+    pub uuid: UploadUuid,
+    pub owner_uuid: UserUuid,
+    pub file_name: String,
+    pub mime_type: String,
+    /// The size of the file in bytes.
+    pub size: i64,
+    pub url: String,
+    /// A smaller version of the image, if the file is an image that could be shrunk.
+    pub thumbnail_url: Option<String>,
+    /// Markdown that shows the file, for editors to insert into the text being written.
+    /// Images are shown inline, and other files are linked to.
+    pub markdown: String,
+    pub created_date: NaiveDateTime,
+}
@@ -7,6 +7,8 @@ pub struct UserResponse {
     pub user_name: String,
     pub display_name: String,
     pub uuid: UserUuid,
+    /// The net score of the votes left on the user's posts.
+    pub reputation: i32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]